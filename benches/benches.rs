@@ -1,3 +1,4 @@
+#![cfg(has_test_crate)]
 #![feature(test)]
 
 extern crate test;
@@ -62,6 +63,37 @@ fn bench_response_fast(b: &mut Bencher) {
   });
 }
 
+#[bench]
+fn bench_response_pre_encoded(b: &mut Bencher) {
+  const BODY: &'static [u8] = b"
+  <!DOCTYPE html>
+  <body>
+    Hello World!
+  </body>
+  ";
+
+  let mut buffer = vec![];
+
+  b.iter(|| -> Result<usize, InsufficientSpaceError> {
+    const OK: Status = Status::new(200);
+    const CONTENT_TYPE: PreEncodedHeader =
+      pre_encoded_header!("Content-Type", "text/html");
+    const CONTENT_LENGTH: Header<usize> =
+      Header::new("Content-Length", BODY.len());
+
+    buffer.clear();
+
+    let mut request = httpencode::response(&mut buffer, Version::HTTP_1_1, OK)?;
+    request.header_pre_encoded(CONTENT_TYPE)?;
+    request.header(CONTENT_LENGTH)?;
+    request.finish()?;
+
+    buffer.extend_from_slice(&BODY);
+
+    Ok(buffer.len())
+  });
+}
+
 #[bench]
 fn bench_request(b: &mut Bencher) {
   let mut buffer = vec![];
@@ -120,12 +152,29 @@ fn bench_request_fast(b: &mut Bencher) {
       Version::HTTP_1_1,
     )?;
 
-    for header in HEADERS.iter().copied() {
-      request.header(header)?;
-    }
-
+    request.headers(HEADERS.iter().copied())?;
     request.finish()?;
 
     Ok(buffer.len())
   });
 }
+
+#[bench]
+fn bench_field_validation(b: &mut Bencher) {
+  use std::convert::TryFrom;
+
+  const FIELDS: &[&str] = &[
+    "Host",
+    "Content-Type",
+    "Access-Control-Allow-Credentials",
+    "X-Forwarded-For",
+    "Strict-Transport-Security",
+  ];
+
+  b.iter(|| {
+    FIELDS
+      .iter()
+      .map(|&field| test::black_box(field))
+      .all(|field| CheckedField::try_from(field).is_ok())
+  });
+}
@@ -4,8 +4,20 @@ const HAS_CONST_PANIC: &str = r#"
 }
 "#;
 
+const HAS_TEST_CRATE: &str = r#"
+#![feature(test)]
+extern crate test;
+"#;
+
 fn main() {
   let cfg = autocfg::new();
 
   cfg.emit_expression_cfg(HAS_CONST_PANIC, "has_const_panic");
+
+  // The benchmarks need the unstable test crate so they're only built on
+  // nightly compilers.
+  autocfg::emit_possibility("has_test_crate");
+  if cfg.probe_raw(HAS_TEST_CRATE).is_ok() {
+    autocfg::emit("has_test_crate");
+  }
 }
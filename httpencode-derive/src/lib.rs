@@ -0,0 +1,210 @@
+//! Derive macros for [`httpencode`](https://docs.rs/httpencode).
+//!
+//! This crate should not be used directly. Instead enable the `derive`
+//! feature of `httpencode` and use the macros re-exported from there.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+  Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments,
+  Type,
+};
+
+/// Derive `httpencode::ToHeaders` for a struct with named fields.
+///
+/// Every field is written out as one header, in declaration order. The
+/// header field name is derived from the struct field name by replacing
+/// underscores with dashes and capitalizing every word, so that
+/// `cache_control` becomes `Cache-Control`. Fields with an `Option` type
+/// are only written out when they are `Some`.
+///
+/// Fields can be customized with a `#[header(...)]` attribute:
+/// - `#[header(rename = "X-Name")]` uses `X-Name` as the field name.
+/// - `#[header(skip)]` never writes out the field.
+#[proc_macro_derive(ToHeaders, attributes(header))]
+pub fn derive_to_headers(
+  input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+  let input = syn::parse_macro_input!(input as DeriveInput);
+
+  match to_headers(input) {
+    Ok(tokens) => tokens.into(),
+    Err(e) => e.to_compile_error().into(),
+  }
+}
+
+fn to_headers(input: DeriveInput) -> Result<TokenStream, Error> {
+  let fields = match &input.data {
+    Data::Struct(data) => match &data.fields {
+      Fields::Named(fields) => &fields.named,
+      _ => {
+        return Err(Error::new(
+          input.ident.span(),
+          "ToHeaders can only be derived for structs with named fields",
+        ))
+      }
+    },
+    _ => {
+      return Err(Error::new(
+        input.ident.span(),
+        "ToHeaders can only be derived for structs",
+      ))
+    }
+  };
+
+  let mut generics = input.generics.clone();
+  let mut writes = Vec::with_capacity(fields.len());
+  for field in fields {
+    let options = FieldOptions::parse(field)?;
+    if options.skip {
+      continue;
+    }
+
+    let ident = field.ident.as_ref().expect("field in named struct");
+    let name = options
+      .rename
+      .unwrap_or_else(|| LitStr::new(&header_name(ident), ident.span()));
+
+    let value_ty = option_inner(&field.ty);
+    let write = if value_ty.is_some() {
+      quote! {
+        if let ::core::option::Option::Some(value) = &self.#ident {
+          builder.header(::httpencode::Header::checked_new(FIELD, value))?;
+        }
+      }
+    } else {
+      quote! {
+        builder.header(::httpencode::Header::checked_new(FIELD, &self.#ident))?;
+      }
+    };
+
+    // Only bound generic parameters directly since bounds on concrete
+    // types involving lifetimes can confuse type inference.
+    let value_ty = value_ty.unwrap_or(&field.ty);
+    if is_type_param(&input.generics, value_ty) {
+      generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#value_ty: ::httpencode::HttpWriteable));
+    }
+
+    writes.push(quote! {
+      {
+        const FIELD: ::httpencode::CheckedField<'static> =
+          ::httpencode::CheckedField::new(#name);
+        #write
+      }
+    });
+  }
+
+  let ident = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics ::httpencode::ToHeaders for #ident #ty_generics
+      #where_clause
+    {
+      fn write_headers<__B: ::httpencode::BufMut>(
+        &self,
+        builder: &mut ::httpencode::HttpBuilder<__B>,
+      ) -> ::core::result::Result<(), ::httpencode::InsufficientSpaceError> {
+        #( #writes )*
+        ::core::result::Result::Ok(())
+      }
+    }
+  })
+}
+
+#[derive(Default)]
+struct FieldOptions {
+  rename: Option<LitStr>,
+  skip: bool,
+}
+
+impl FieldOptions {
+  fn parse(field: &syn::Field) -> Result<Self, Error> {
+    let mut options = Self::default();
+
+    for attr in &field.attrs {
+      if !attr.path().is_ident("header") {
+        continue;
+      }
+
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("rename") {
+          options.rename = Some(meta.value()?.parse()?);
+          Ok(())
+        } else if meta.path.is_ident("skip") {
+          options.skip = true;
+          Ok(())
+        } else {
+          Err(meta.error("unsupported header attribute"))
+        }
+      })?;
+    }
+
+    if let (Some(rename), true) = (&options.rename, options.skip) {
+      return Err(Error::new(
+        rename.span(),
+        "a skipped field cannot also be renamed",
+      ));
+    }
+
+    Ok(options)
+  }
+}
+
+/// Convert a snake_case field name into a Train-Case header name.
+fn header_name(ident: &syn::Ident) -> String {
+  let ident = ident.to_string();
+  let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+
+  ident
+    .split('_')
+    .filter(|word| !word.is_empty())
+    .map(|word| {
+      let mut chars = word.chars();
+      match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("-")
+}
+
+/// If `ty` is an `Option<T>` then return `T`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+  let path = match ty {
+    Type::Path(path) if path.qself.is_none() => &path.path,
+    _ => return None,
+  };
+
+  let segment = path.segments.last()?;
+  if segment.ident != "Option" {
+    return None;
+  }
+
+  match &segment.arguments {
+    PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+      match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Whether `ty` is exactly one of the type parameters in `generics`.
+fn is_type_param(generics: &syn::Generics, ty: &Type) -> bool {
+  let ident = match ty {
+    Type::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+      Some(ident) => ident,
+      None => return false,
+    },
+    _ => return false,
+  };
+
+  generics.type_params().any(|param| param.ident == *ident)
+}
@@ -0,0 +1,74 @@
+use httpencode::*;
+
+fn encode<T: ToHeaders>(value: T) -> String {
+  let mut builder = HttpBuilder::response(
+    vec![],
+    Version::HTTP_1_1,
+    Status::with_reason(200, "OK"),
+  )
+  .unwrap();
+  value.write_headers(&mut builder).unwrap();
+
+  String::from_utf8(builder.finish().unwrap()).unwrap()
+}
+
+#[derive(ToHeaders)]
+struct Cors<'a> {
+  access_control_allow_origin: &'a str,
+  access_control_max_age: Option<u32>,
+  #[header(rename = "Vary")]
+  varies_on: &'static str,
+  #[header(skip)]
+  #[allow(dead_code)]
+  internal: u32,
+}
+
+#[test]
+fn field_names() {
+  let cors = Cors {
+    access_control_allow_origin: "*",
+    access_control_max_age: Some(600),
+    varies_on: "Origin",
+    internal: 0,
+  };
+
+  assert_eq!(
+    encode(&cors),
+    "HTTP/1.1 200 OK\r\n\
+     Access-Control-Allow-Origin: *\r\n\
+     Access-Control-Max-Age: 600\r\n\
+     Vary: Origin\r\n\
+     \r\n"
+  );
+}
+
+#[test]
+fn none_is_skipped() {
+  let cors = Cors {
+    access_control_allow_origin: "https://example.com",
+    access_control_max_age: None,
+    varies_on: "Origin",
+    internal: 0,
+  };
+
+  assert_eq!(
+    encode(cors),
+    "HTTP/1.1 200 OK\r\n\
+     Access-Control-Allow-Origin: https://example.com\r\n\
+     Vary: Origin\r\n\
+     \r\n"
+  );
+}
+
+#[derive(ToHeaders)]
+struct Generic<T> {
+  x_value: T,
+}
+
+#[test]
+fn generic() {
+  assert_eq!(
+    encode(Generic { x_value: 5u8 }),
+    "HTTP/1.1 200 OK\r\nX-Value: 5\r\n\r\n"
+  );
+}
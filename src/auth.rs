@@ -0,0 +1,911 @@
+use core::convert::TryFrom;
+
+use crate::params::validate_params;
+use crate::quoted::{is_plain_text, write_ext_value};
+use crate::util::sum_hints;
+use crate::{
+  is_token, is_token_fast, BufMut, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError, Quoted,
+};
+
+/// Whether `value` matches the token68 grammar from RFC 7235:
+/// ```text
+/// token68 = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+/// ```
+const fn is_token68(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'a'..=b'z'
+      | b'A'..=b'Z'
+      | b'0'..=b'9'
+      | b'-'
+      | b'.'
+      | b'_'
+      | b'~'
+      | b'+'
+      | b'/' => (),
+      b'=' if idx != 0 => break,
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  while idx < bytes.len() {
+    if bytes[idx] != b'=' {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  !bytes.is_empty()
+}
+
+/// An `Authorization` header value using the `Bearer` scheme (RFC 6750).
+///
+/// The token must match the token68 grammar so a malformed token, such
+/// as one containing a CRLF, can't be used to corrupt the header block.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Header::new("Authorization", Bearer::new("mF_9.B5f-4.1JqM"))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Authorization: Bearer mF_9.B5f-4.1JqM\r\n");
+/// assert!(Bearer::try_new("abc\r\nX-Injected: 1").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bearer<'data>(&'data str);
+
+impl<'data> Bearer<'data> {
+  /// Create a bearer credential from an access token.
+  ///
+  /// # Errors
+  /// Returns an error if `token` does not match the token68 grammar.
+  pub const fn try_new(token: &'data str) -> Result<Self, InvalidHeaderError> {
+    if !is_token68(token) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(token))
+  }
+
+  /// Create a bearer credential from an access token.
+  ///
+  /// # Panics
+  /// Panics if `token` does not match the token68 grammar.
+  pub const fn new(token: &'data str) -> Self {
+    match Self::try_new(token) {
+      Ok(bearer) => bearer,
+      Err(_) => const_panic!("Bearer token contained invalid character"),
+    }
+  }
+
+  /// The access token.
+  pub const fn token(&self) -> &'data str {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data str> for Bearer<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(token: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(token)
+  }
+}
+
+// Access tokens are secrets so avoid leaking them into logs.
+impl core::fmt::Debug for Bearer<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_tuple("Bearer").field(&"..").finish()
+  }
+}
+
+impl HttpWriteable for Bearer<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(b"Bearer ")?;
+    buffer.try_put_slice(self.0.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = "Bearer ".len() + self.0.len();
+    (len, Some(len))
+  }
+}
+
+/// Whether `value` can be written out as a quoted-string.
+const fn is_quotable(value: &str) -> bool {
+  Quoted::try_new(value.as_bytes()).is_ok()
+}
+
+/// Write out `, name="value"`. The value must be quotable.
+fn write_quoted_param<B: BufMut>(
+  buffer: &mut B,
+  name: &str,
+  value: &str,
+) -> Result<(), InsufficientSpaceError> {
+  buffer.try_put_slice(b", ")?;
+  buffer.try_put_slice(name.as_bytes())?;
+  buffer.try_put_u8(b'=')?;
+  Quoted(value.as_bytes()).write_to(buffer)
+}
+
+/// The hash algorithm used for `Digest` authentication (RFC 7616).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DigestAlgorithm {
+  /// `MD5`
+  Md5,
+  /// `MD5-sess`
+  Md5Sess,
+  /// `SHA-256`
+  Sha256,
+  /// `SHA-256-sess`
+  Sha256Sess,
+  /// `SHA-512-256`
+  Sha512_256,
+  /// `SHA-512-256-sess`
+  Sha512_256Sess,
+}
+
+impl DigestAlgorithm {
+  const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Md5 => "MD5",
+      Self::Md5Sess => "MD5-sess",
+      Self::Sha256 => "SHA-256",
+      Self::Sha256Sess => "SHA-256-sess",
+      Self::Sha512_256 => "SHA-512-256",
+      Self::Sha512_256Sess => "SHA-512-256-sess",
+    }
+  }
+}
+
+/// The quality of protection used for `Digest` authentication.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DigestQop {
+  /// `auth`: authentication only.
+  Auth,
+  /// `auth-int`: authentication with integrity protection of the body.
+  AuthInt,
+}
+
+impl DigestQop {
+  const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Auth => "auth",
+      Self::AuthInt => "auth-int",
+    }
+  }
+}
+
+/// A `WWW-Authenticate` challenge using the `Digest` scheme (RFC 7616).
+///
+/// The realm, nonce, and opaque values are always written out quoted.
+/// Generating the nonce is left to the caller.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let challenge = DigestChallenge::new("api@example.org", "7ypf/xlj9XXw")
+///   .qop(&[DigestQop::Auth, DigestQop::AuthInt])
+///   .algorithm(DigestAlgorithm::Sha256)
+///   .opaque("FQhe/qaU925k");
+///
+/// let mut buffer = Vec::new();
+/// Header::new("WWW-Authenticate", challenge).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "WWW-Authenticate: Digest realm=\"api@example.org\", \
+///   nonce=\"7ypf/xlj9XXw\", opaque=\"FQhe/qaU925k\", \
+///   algorithm=SHA-256, qop=\"auth, auth-int\"\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DigestChallenge<'data> {
+  realm: &'data str,
+  nonce: &'data str,
+  opaque: Option<&'data str>,
+  algorithm: Option<DigestAlgorithm>,
+  qop: &'data [DigestQop],
+  stale: bool,
+  userhash: bool,
+}
+
+impl<'data> DigestChallenge<'data> {
+  /// Create a challenge for `realm` with a server-generated `nonce`.
+  ///
+  /// # Errors
+  /// Returns an error if `realm` or `nonce` contains a control character
+  /// other than HTAB.
+  pub const fn try_new(
+    realm: &'data str,
+    nonce: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_quotable(realm) || !is_quotable(nonce) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      realm,
+      nonce,
+      opaque: None,
+      algorithm: None,
+      qop: &[],
+      stale: false,
+      userhash: false,
+    })
+  }
+
+  /// Create a challenge for `realm` with a server-generated `nonce`.
+  ///
+  /// # Panics
+  /// Panics if `realm` or `nonce` contains a control character other
+  /// than HTAB.
+  pub const fn new(realm: &'data str, nonce: &'data str) -> Self {
+    match Self::try_new(realm, nonce) {
+      Ok(challenge) => challenge,
+      Err(_) => {
+        const_panic!("Digest realm or nonce contained a control character")
+      }
+    }
+  }
+
+  /// Set the `opaque` value that clients must return unchanged.
+  ///
+  /// # Errors
+  /// Returns an error if `opaque` contains a control character other
+  /// than HTAB.
+  pub const fn try_opaque(
+    self,
+    opaque: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_quotable(opaque) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      opaque: Some(opaque),
+      ..self
+    })
+  }
+
+  /// Set the `opaque` value that clients must return unchanged.
+  ///
+  /// # Panics
+  /// Panics if `opaque` contains a control character other than HTAB.
+  pub const fn opaque(self, opaque: &'data str) -> Self {
+    match self.try_opaque(opaque) {
+      Ok(challenge) => challenge,
+      Err(_) => const_panic!("Digest opaque contained a control character"),
+    }
+  }
+
+  /// Set the hash algorithm. Clients assume `MD5` if this is not set.
+  pub const fn algorithm(self, algorithm: DigestAlgorithm) -> Self {
+    Self {
+      algorithm: Some(algorithm),
+      ..self
+    }
+  }
+
+  /// Set the qualities of protection supported by the server.
+  pub const fn qop(self, qop: &'data [DigestQop]) -> Self {
+    Self { qop, ..self }
+  }
+
+  /// Mark the nonce as stale so that clients retry with the new nonce
+  /// without prompting for credentials again.
+  pub const fn stale(self) -> Self {
+    Self {
+      stale: true,
+      ..self
+    }
+  }
+
+  /// Indicate that the server supports hashed usernames.
+  pub const fn userhash(self) -> Self {
+    Self {
+      userhash: true,
+      ..self
+    }
+  }
+
+  /// The realm being authenticated against.
+  pub const fn realm(&self) -> &'data str {
+    self.realm
+  }
+
+  /// The server-generated nonce.
+  pub const fn nonce(&self) -> &'data str {
+    self.nonce
+  }
+}
+
+impl HttpWriteable for DigestChallenge<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    // All quoted values were checked when this was created.
+    buffer.try_put_slice(b"Digest realm=")?;
+    Quoted(self.realm.as_bytes()).write_to(buffer)?;
+    write_quoted_param(buffer, "nonce", self.nonce)?;
+
+    if let Some(opaque) = self.opaque {
+      write_quoted_param(buffer, "opaque", opaque)?;
+    }
+
+    if let Some(algorithm) = self.algorithm {
+      buffer.try_put_slice(b", algorithm=")?;
+      buffer.try_put_slice(algorithm.as_str().as_bytes())?;
+    }
+
+    if let Some((first, rest)) = self.qop.split_first() {
+      buffer.try_put_slice(b", qop=\"")?;
+      buffer.try_put_slice(first.as_str().as_bytes())?;
+      for qop in rest {
+        buffer.try_put_slice(b", ")?;
+        buffer.try_put_slice(qop.as_str().as_bytes())?;
+      }
+      buffer.try_put_u8(b'"')?;
+    }
+
+    if self.stale {
+      buffer.try_put_slice(b", stale=true")?;
+    }
+
+    if self.userhash {
+      buffer.try_put_slice(b", userhash=true")?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut len = "Digest realm=, nonce=".len();
+    let none = (0, Some(0));
+
+    if self.opaque.is_some() {
+      len += ", opaque=".len();
+    }
+    if let Some(algorithm) = self.algorithm {
+      len += ", algorithm=".len() + algorithm.as_str().len();
+    }
+    if !self.qop.is_empty() {
+      len += ", qop=\"\"".len() + (self.qop.len() - 1) * ", ".len();
+      len += self.qop.iter().map(|qop| qop.as_str().len()).sum::<usize>();
+    }
+    if self.stale {
+      len += ", stale=true".len();
+    }
+    if self.userhash {
+      len += ", userhash=true".len();
+    }
+
+    sum_hints([
+      (len, Some(len)),
+      Quoted(self.realm.as_bytes()).size_hint(),
+      Quoted(self.nonce.as_bytes()).size_hint(),
+      self
+        .opaque
+        .map_or(none, |v| Quoted(v.as_bytes()).size_hint()),
+    ])
+  }
+}
+
+/// An `Authorization` header value using the `Digest` scheme (RFC 7616).
+///
+/// Computing the response hash is left to the caller, this only takes
+/// care of writing out the parameters with the correct quoting. The
+/// username is written out as an RFC 8187 `username*` parameter when it
+/// is not printable ASCII.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let response = DigestResponse::new(
+///   "Mufasa",
+///   "http-auth@example.org",
+///   "/dir/index.html",
+///   "7ypf/xlj9XXw",
+///   "753927fa0e85d155564e2e272a28d180",
+/// )
+/// .algorithm(DigestAlgorithm::Sha256)
+/// .qop(DigestQop::Auth, 1, "f2/wE4q74E6z");
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Authorization", response).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Authorization: Digest username=\"Mufasa\", \
+///   realm=\"http-auth@example.org\", uri=\"/dir/index.html\", \
+///   algorithm=SHA-256, nonce=\"7ypf/xlj9XXw\", nc=00000001, \
+///   cnonce=\"f2/wE4q74E6z\", qop=auth, \
+///   response=\"753927fa0e85d155564e2e272a28d180\"\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DigestResponse<'data> {
+  username: &'data str,
+  realm: &'data str,
+  uri: &'data str,
+  nonce: &'data str,
+  response: &'data str,
+  algorithm: Option<DigestAlgorithm>,
+  qop: Option<(DigestQop, u32, &'data str)>,
+  opaque: Option<&'data str>,
+  userhash: bool,
+}
+
+impl<'data> DigestResponse<'data> {
+  /// Create a response to a challenge.
+  ///
+  /// `uri` is the request target and `response` is the hex-encoded
+  /// request digest computed by the caller.
+  ///
+  /// # Errors
+  /// Returns an error if `realm`, `uri`, `nonce`, or `response` contain
+  /// a control character other than HTAB.
+  pub const fn try_new(
+    username: &'data str,
+    realm: &'data str,
+    uri: &'data str,
+    nonce: &'data str,
+    response: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_quotable(realm)
+      || !is_quotable(uri)
+      || !is_quotable(nonce)
+      || !is_quotable(response)
+    {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      username,
+      realm,
+      uri,
+      nonce,
+      response,
+      algorithm: None,
+      qop: None,
+      opaque: None,
+      userhash: false,
+    })
+  }
+
+  /// Create a response to a challenge.
+  ///
+  /// # Panics
+  /// Panics if `realm`, `uri`, `nonce`, or `response` contain a control
+  /// character other than HTAB.
+  pub const fn new(
+    username: &'data str,
+    realm: &'data str,
+    uri: &'data str,
+    nonce: &'data str,
+    response: &'data str,
+  ) -> Self {
+    match Self::try_new(username, realm, uri, nonce, response) {
+      Ok(response) => response,
+      Err(_) => const_panic!("Digest parameter contained a control character"),
+    }
+  }
+
+  /// Set the hash algorithm that was used.
+  pub const fn algorithm(self, algorithm: DigestAlgorithm) -> Self {
+    Self {
+      algorithm: Some(algorithm),
+      ..self
+    }
+  }
+
+  /// Set the quality of protection along with the nonce count and the
+  /// client-generated nonce that went into the response hash.
+  ///
+  /// # Errors
+  /// Returns an error if `cnonce` contains a control character other
+  /// than HTAB.
+  pub const fn try_qop(
+    self,
+    qop: DigestQop,
+    nc: u32,
+    cnonce: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_quotable(cnonce) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      qop: Some((qop, nc, cnonce)),
+      ..self
+    })
+  }
+
+  /// Set the quality of protection along with the nonce count and the
+  /// client-generated nonce that went into the response hash.
+  ///
+  /// # Panics
+  /// Panics if `cnonce` contains a control character other than HTAB.
+  pub const fn qop(self, qop: DigestQop, nc: u32, cnonce: &'data str) -> Self {
+    match self.try_qop(qop, nc, cnonce) {
+      Ok(response) => response,
+      Err(_) => const_panic!("Digest cnonce contained a control character"),
+    }
+  }
+
+  /// Echo back the `opaque` value from the challenge.
+  ///
+  /// # Errors
+  /// Returns an error if `opaque` contains a control character other
+  /// than HTAB.
+  pub const fn try_opaque(
+    self,
+    opaque: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_quotable(opaque) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      opaque: Some(opaque),
+      ..self
+    })
+  }
+
+  /// Echo back the `opaque` value from the challenge.
+  ///
+  /// # Panics
+  /// Panics if `opaque` contains a control character other than HTAB.
+  pub const fn opaque(self, opaque: &'data str) -> Self {
+    match self.try_opaque(opaque) {
+      Ok(response) => response,
+      Err(_) => const_panic!("Digest opaque contained a control character"),
+    }
+  }
+
+  /// Indicate that the username is hashed.
+  pub const fn userhash(self) -> Self {
+    Self {
+      userhash: true,
+      ..self
+    }
+  }
+
+  /// The username being authenticated.
+  pub const fn username(&self) -> &'data str {
+    self.username
+  }
+}
+
+impl HttpWriteable for DigestResponse<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    // All quoted values were checked when this was created.
+    if is_plain_text(self.username) {
+      buffer.try_put_slice(b"Digest username=")?;
+      Quoted(self.username.as_bytes()).write_to(buffer)?;
+    } else {
+      buffer.try_put_slice(b"Digest username*=")?;
+      write_ext_value(buffer, self.username)?;
+    }
+
+    write_quoted_param(buffer, "realm", self.realm)?;
+    write_quoted_param(buffer, "uri", self.uri)?;
+
+    if let Some(algorithm) = self.algorithm {
+      buffer.try_put_slice(b", algorithm=")?;
+      buffer.try_put_slice(algorithm.as_str().as_bytes())?;
+    }
+
+    write_quoted_param(buffer, "nonce", self.nonce)?;
+
+    if let Some((qop, nc, cnonce)) = self.qop {
+      let mut hex = [0; 8];
+      for (idx, digit) in hex.iter_mut().enumerate() {
+        *digit = b"0123456789abcdef"[(nc >> (28 - idx * 4)) as usize & 0xF];
+      }
+
+      buffer.try_put_slice(b", nc=")?;
+      buffer.try_put_slice(&hex)?;
+      write_quoted_param(buffer, "cnonce", cnonce)?;
+      buffer.try_put_slice(b", qop=")?;
+      buffer.try_put_slice(qop.as_str().as_bytes())?;
+    }
+
+    write_quoted_param(buffer, "response", self.response)?;
+
+    if let Some(opaque) = self.opaque {
+      write_quoted_param(buffer, "opaque", opaque)?;
+    }
+
+    if self.userhash {
+      buffer.try_put_slice(b", userhash=true")?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut len = "Digest username=, realm=, uri=, nonce=, response=".len();
+    let none = (0, Some(0));
+
+    // Every byte of a non-ASCII username might be percent-encoded.
+    let username = if is_plain_text(self.username) {
+      Quoted(self.username.as_bytes()).size_hint()
+    } else {
+      let len = self.username.len();
+      let prefix = "*UTF-8''".len();
+      let upper = len.checked_mul(3).and_then(|len| len.checked_add(prefix));
+      (len.saturating_add(prefix), upper)
+    };
+
+    if let Some(algorithm) = self.algorithm {
+      len += ", algorithm=".len() + algorithm.as_str().len();
+    }
+    if let Some((qop, _, _)) = self.qop {
+      len += ", nc=00000000, cnonce=, qop=".len() + qop.as_str().len();
+    }
+    if self.opaque.is_some() {
+      len += ", opaque=".len();
+    }
+    if self.userhash {
+      len += ", userhash=true".len();
+    }
+
+    sum_hints([
+      (len, Some(len)),
+      username,
+      Quoted(self.realm.as_bytes()).size_hint(),
+      Quoted(self.uri.as_bytes()).size_hint(),
+      Quoted(self.nonce.as_bytes()).size_hint(),
+      Quoted(self.response.as_bytes()).size_hint(),
+      self
+        .qop
+        .map_or(none, |(_, _, v)| Quoted(v.as_bytes()).size_hint()),
+      self
+        .opaque
+        .map_or(none, |v| Quoted(v.as_bytes()).size_hint()),
+    ])
+  }
+}
+
+/// A `WWW-Authenticate` or `Proxy-Authenticate` challenge for an
+/// arbitrary authentication scheme (RFC 7235).
+///
+/// Parameter values that are tokens are written out as-is while all
+/// others are quoted. The `realm` parameter is always quoted since some
+/// clients don't accept it in token form. A header listing several
+/// challenges can be written out by wrapping them in
+/// [`Separated`](crate::Separated).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const CHALLENGES: [Challenge; 2] = [
+///   Challenge::new("Newauth", &[("realm", "apps"), ("type", "1")]),
+///   Challenge::new("Basic", &[("realm", "simple"), ("charset", "UTF-8")]),
+/// ];
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(401, "Unauthorized")
+/// )?;
+/// builder.header(Header::new("WWW-Authenticate", Separated::new(&CHALLENGES)))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 401 Unauthorized\r\n\
+///   WWW-Authenticate: Newauth realm=\"apps\", type=1, \
+///   Basic realm=\"simple\", charset=UTF-8\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Challenge<'data> {
+  scheme: &'data str,
+  params: &'data [(&'data str, &'data str)],
+}
+
+impl<'data> Challenge<'data> {
+  /// Create a challenge for `scheme` with the given auth-params.
+  ///
+  /// # Errors
+  /// Returns an error if `scheme` or any parameter name is not a token or
+  /// if any parameter value contains a control character other than HTAB.
+  pub const fn try_new(
+    scheme: &'data str,
+    params: &'data [(&'data str, &'data str)],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_token(scheme) || !validate_params(params) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { scheme, params })
+  }
+
+  /// Create a challenge for `scheme` with the given auth-params.
+  ///
+  /// # Panics
+  /// Panics if `scheme` or any parameter name is not a token or if any
+  /// parameter value contains a control character other than HTAB.
+  pub const fn new(
+    scheme: &'data str,
+    params: &'data [(&'data str, &'data str)],
+  ) -> Self {
+    match Self::try_new(scheme, params) {
+      Ok(challenge) => challenge,
+      Err(_) => const_panic!("Invalid authentication challenge"),
+    }
+  }
+
+  /// The authentication scheme.
+  pub const fn scheme(&self) -> &'data str {
+    self.scheme
+  }
+
+  /// The auth-params in the order they are written out.
+  pub const fn params(&self) -> &'data [(&'data str, &'data str)] {
+    self.params
+  }
+}
+
+impl HttpWriteable for Challenge<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.scheme.as_bytes())?;
+
+    for (idx, &(name, value)) in self.params.iter().enumerate() {
+      buffer.try_put_slice(if idx == 0 { b" " } else { b", " })?;
+      buffer.try_put_slice(name.as_bytes())?;
+      buffer.try_put_u8(b'=')?;
+
+      let realm = name.eq_ignore_ascii_case("realm");
+      if !realm && is_token_fast(value.as_bytes()) {
+        buffer.try_put_slice(value.as_bytes())?;
+      } else {
+        // Parameter values were checked when this was created.
+        Quoted(value.as_bytes()).write_to(buffer)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.scheme.len() + self.params.len() * ", =".len();
+    let len = len.saturating_sub(self.params.len().min(1));
+
+    sum_hints(
+      core::iter::once((len, Some(len))).chain(self.params.iter().map(
+        |(name, value)| {
+          let (lower, upper) = Quoted(value.as_bytes()).size_hint();
+          (
+            name.len() + lower - 2,
+            upper.and_then(|upper| upper.checked_add(name.len())),
+          )
+        },
+      )),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn valid_tokens() {
+    for token in &["a", "abc-._~+/", "YWJj==", "Zm9v=", "0"] {
+      assert!(Bearer::try_new(token).is_ok(), "{}", token);
+    }
+  }
+
+  #[test]
+  fn invalid_tokens() {
+    for token in &["", "=", "==abc", "a=b", "a b", "a\r\nb", "a,b", "\"a\""] {
+      assert!(Bearer::try_new(token).is_err(), "{:?}", token);
+    }
+  }
+
+  #[test]
+  fn debug_hides_token() {
+    assert_eq!(format!("{:?}", Bearer::new("secret")), "Bearer(\"..\")");
+  }
+
+  #[test]
+  fn digest_challenge() {
+    let challenge = DigestChallenge::new("a \"realm\"", "abc")
+      .qop(&[DigestQop::Auth])
+      .stale()
+      .userhash();
+
+    assert_eq!(
+      encode_str(challenge),
+      "Digest realm=\"a \\\"realm\\\"\", nonce=\"abc\", qop=\"auth\", \
+       stale=true, userhash=true"
+    );
+    assert_eq!(
+      encode_str(DigestChallenge::new("r", "n")),
+      "Digest realm=\"r\", nonce=\"n\""
+    );
+  }
+
+  #[test]
+  fn digest_response() {
+    let response = DigestResponse::new("Jäsøn Doe", "r", "/", "n", "abcd")
+      .qop(DigestQop::AuthInt, 0x1ff, "c")
+      .opaque("o")
+      .userhash();
+
+    assert_eq!(
+      encode_str(response),
+      "Digest username*=UTF-8''J%C3%A4s%C3%B8n%20Doe, realm=\"r\", \
+       uri=\"/\", nonce=\"n\", nc=000001ff, cnonce=\"c\", qop=auth-int, \
+       response=\"abcd\", opaque=\"o\", userhash=true"
+    );
+  }
+
+  #[test]
+  fn digest_invalid() {
+    assert!(DigestChallenge::try_new("r\r\n", "n").is_err());
+    assert!(DigestChallenge::new("r", "n").try_opaque("\n").is_err());
+    assert!(DigestResponse::try_new("u", "r", "/\r\n", "n", "x").is_err());
+    assert!(DigestResponse::new("u", "r", "/", "n", "x")
+      .try_qop(DigestQop::Auth, 1, "\0")
+      .is_err());
+  }
+
+  #[test]
+  fn challenge() {
+    let challenge = Challenge::new(
+      "Bearer",
+      &[
+        ("realm", "example"),
+        ("error", "invalid_token"),
+        ("scope", "a b"),
+      ],
+    );
+
+    assert_eq!(
+      encode_str(challenge),
+      "Bearer realm=\"example\", error=invalid_token, scope=\"a b\""
+    );
+    assert_eq!(encode_str(Challenge::new("Negotiate", &[])), "Negotiate");
+    assert!(Challenge::try_new("", &[]).is_err());
+    assert!(Challenge::try_new("Basic realm", &[]).is_err());
+    assert!(Challenge::try_new("Basic", &[("realm", "\r\n")]).is_err());
+  }
+}
@@ -0,0 +1,466 @@
+#[cfg(feature = "std")]
+use std::net::{IpAddr, SocketAddr};
+
+use crate::uri::validate_host;
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidUriError,
+};
+
+/// The `host[:port]` authority of a URI.
+///
+/// An `Authority` can be used as the value of a `Host` header or as the
+/// target of a `CONNECT` request through
+/// [`HttpBuilder::connect_to`](crate::HttpBuilder::connect_to). IPv6
+/// addresses may be given with or without brackets; they are always
+/// bracketed when written out.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let authority = Authority::try_new("example.com", 443)?;
+///
+/// let mut builder = HttpBuilder::request(
+///     vec![],
+///     Method::GET,
+///     Uri::new(b"/"),
+///     Version::HTTP_1_1
+/// )?;
+/// builder.header(Header::new("Host", authority.omit_default_port(443)))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "GET / HTTP/1.1\r\n\
+///   Host: example.com\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Authority<'data> {
+  host: &'data str,
+  port: Option<u16>,
+  bracket: bool,
+}
+
+impl<'data> Authority<'data> {
+  /// Create a new `Authority` for `host` and `port`.
+  ///
+  /// # Errors
+  /// Returns an error if `host` is not a valid host name or IP address.
+  /// This includes hosts that already contain a port.
+  pub fn try_new(host: &'data str, port: u16) -> Result<Self, InvalidUriError> {
+    let bracket = host.contains(':') && !host.starts_with('[');
+
+    let is_valid = if bracket {
+      is_ipv6(host)
+    } else if host.starts_with('[') {
+      host.ends_with(']') && is_ipv6(&host[1..host.len() - 1])
+    } else {
+      validate_host(host.as_bytes())
+    };
+
+    if !is_valid {
+      return Err(InvalidUriError(()));
+    }
+
+    Ok(Self {
+      host,
+      port: Some(port),
+      bracket,
+    })
+  }
+
+  /// Create a new `Authority` for `host` and `port`.
+  ///
+  /// # Panics
+  /// Panics if `host` is not a valid host name or IP address.
+  pub fn new(host: &'data str, port: u16) -> Self {
+    match Self::try_new(host, port) {
+      Ok(authority) => authority,
+      Err(_) => panic!("Invalid authority host"),
+    }
+  }
+
+  /// Leave the port out of the authority if it is `default_port`.
+  ///
+  /// This is usually done with port 80 for `http` and port 443 for
+  /// `https` when writing a `Host` header.
+  pub fn omit_default_port(mut self, default_port: u16) -> Self {
+    if self.port == Some(default_port) {
+      self.port = None;
+    }
+
+    self
+  }
+
+  /// The host, as it was provided.
+  pub fn host(&self) -> &'data str {
+    self.host
+  }
+
+  /// The port, if it hasn't been omitted.
+  pub fn port(&self) -> Option<u16> {
+    self.port
+  }
+}
+
+impl HttpWriteable for Authority<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.bracket {
+      buffer.try_put_u8(b'[')?;
+      buffer.try_put_slice(self.host.as_bytes())?;
+      buffer.try_put_u8(b']')?;
+    } else {
+      buffer.try_put_slice(self.host.as_bytes())?;
+    }
+
+    if let Some(port) = self.port {
+      buffer.try_put_u8(b':')?;
+      port.write_to(buffer)?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let brackets = if self.bracket { 2 } else { 0 };
+    let host = self.host.len() + brackets;
+
+    match self.port {
+      Some(port) => {
+        let (lower, upper) = port.size_hint();
+        (host + 1 + lower, upper.map(|upper| host + 1 + upper))
+      }
+      None => (host, Some(host)),
+    }
+  }
+}
+
+/// A `Host` header value.
+///
+/// This can be created either from a host name and port or from a
+/// `SocketAddr`. The port is left out when it is the default port for
+/// the scheme and IPv6 addresses are always bracketed.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::net::SocketAddr;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Header::new("Host", HostValue::try_new("example.com", 80, 80)?)
+///   .write_to(&mut buffer)?;
+///
+/// let addr: SocketAddr = "[2001:db8::1]:8443".parse()?;
+/// Header::new("Host", HostValue::from_socket_addr(addr, 443))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Host: example.com\r\n\
+///   Host: [2001:db8::1]:8443\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HostValue<'data>(HostRepr<'data>);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum HostRepr<'data> {
+  Authority(Authority<'data>),
+  #[cfg(feature = "std")]
+  Ip(IpAddr, Option<u16>),
+}
+
+impl<'data> HostValue<'data> {
+  /// Create a `Host` value for `host` and `port`, leaving out the port
+  /// if it is `default_port`.
+  ///
+  /// # Errors
+  /// Returns an error if `host` is not a valid host name or IP address.
+  pub fn try_new(
+    host: &'data str,
+    port: u16,
+    default_port: u16,
+  ) -> Result<Self, InvalidUriError> {
+    let authority = Authority::try_new(host, port)?;
+    Ok(Self::from(authority.omit_default_port(default_port)))
+  }
+
+  /// Create a `Host` value for `host` and `port`, leaving out the port
+  /// if it is `default_port`.
+  ///
+  /// # Panics
+  /// Panics if `host` is not a valid host name or IP address.
+  pub fn new(host: &'data str, port: u16, default_port: u16) -> Self {
+    match Self::try_new(host, port, default_port) {
+      Ok(host) => host,
+      Err(_) => panic!("Invalid host"),
+    }
+  }
+
+  /// Create a `Host` value for `addr`, leaving out the port if it is
+  /// `default_port`.
+  #[cfg(feature = "std")]
+  pub fn from_socket_addr(addr: SocketAddr, default_port: u16) -> Self {
+    let port = Some(addr.port()).filter(|&port| port != default_port);
+    Self(HostRepr::Ip(addr.ip(), port))
+  }
+
+  /// The port, if it hasn't been omitted.
+  pub fn port(&self) -> Option<u16> {
+    match self.0 {
+      HostRepr::Authority(authority) => authority.port(),
+      #[cfg(feature = "std")]
+      HostRepr::Ip(_, port) => port,
+    }
+  }
+}
+
+impl<'data> From<Authority<'data>> for HostValue<'data> {
+  fn from(authority: Authority<'data>) -> Self {
+    Self(HostRepr::Authority(authority))
+  }
+}
+
+impl HttpWriteable for HostValue<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    match self.0 {
+      HostRepr::Authority(authority) => authority.write_to(buffer),
+      #[cfg(feature = "std")]
+      HostRepr::Ip(ip, port) => {
+        match ip {
+          IpAddr::V4(ip) => format_args!("{}", ip).write_to(buffer)?,
+          IpAddr::V6(ip) => format_args!("[{}]", ip).write_to(buffer)?,
+        }
+
+        if let Some(port) = port {
+          buffer.try_put_u8(b':')?;
+          port.write_to(buffer)?;
+        }
+
+        Ok(())
+      }
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match self.0 {
+      HostRepr::Authority(authority) => authority.size_hint(),
+      #[cfg(feature = "std")]
+      HostRepr::Ip(ip, port) => {
+        let (lower, upper) = match ip {
+          IpAddr::V4(_) => (7, 15),
+          IpAddr::V6(_) => (4, 41),
+        };
+
+        match port {
+          Some(port) => {
+            let (port_lower, port_upper) = port.size_hint();
+            (
+              lower + 1 + port_lower,
+              port_upper.map(|port_upper| upper + 1 + port_upper),
+            )
+          }
+          None => (lower, Some(upper)),
+        }
+      }
+    }
+  }
+}
+
+/// Whether `addr` is an IPv6 address as described by RFC 3986, without
+/// brackets or a zone identifier.
+fn is_ipv6(addr: &str) -> bool {
+  let (head, tail) = match addr.find("::") {
+    Some(idx) => (Some(&addr[..idx]), &addr[idx + 2..]),
+    None => (None, addr),
+  };
+
+  if tail.contains("::") {
+    return false;
+  }
+
+  let head = match head {
+    Some(head) => ipv6_groups(head, false),
+    None => Some(0),
+  };
+
+  match (head, ipv6_groups(tail, true)) {
+    // `::` stands in for at least one group.
+    (Some(head), Some(tail)) if addr.contains("::") => head + tail <= 7,
+    (Some(head), Some(tail)) => head + tail == 8,
+    _ => false,
+  }
+}
+
+/// Count the 16-bit groups within a `:`-separated run of IPv6 groups. An
+/// IPv4 address is allowed in place of the last two groups when `last` is
+/// set.
+fn ipv6_groups(part: &str, last: bool) -> Option<usize> {
+  if part.is_empty() {
+    return Some(0);
+  }
+
+  let count = part.split(':').count();
+  let mut groups = 0;
+
+  for (idx, group) in part.split(':').enumerate() {
+    if last && idx + 1 == count && group.contains('.') {
+      if !is_ipv4(group) {
+        return None;
+      }
+
+      groups += 2;
+    } else if (1..=4).contains(&group.len())
+      && group.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+      groups += 1;
+    } else {
+      return None;
+    }
+  }
+
+  Some(groups)
+}
+
+/// Whether `addr` is a dotted-decimal IPv4 address.
+fn is_ipv4(addr: &str) -> bool {
+  let mut octets = 0;
+
+  for octet in addr.split('.') {
+    let valid = match octet.as_bytes() {
+      [b'0'] => true,
+      [b'1'..=b'9', rest @ ..] => {
+        rest.len() <= 2
+          && rest.iter().all(u8::is_ascii_digit)
+          && octet.parse::<u8>().is_ok()
+      }
+      _ => false,
+    };
+
+    if !valid {
+      return false;
+    }
+
+    octets += 1;
+  }
+
+  octets == 4
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validation() {
+    assert!(Authority::try_new("example.com", 80).is_ok());
+    assert!(Authority::try_new("[::1]", 80).is_ok());
+    assert!(Authority::try_new("2001:db8::7", 80).is_ok());
+    assert!(Authority::try_new("::ffff:127.0.0.1", 80).is_ok());
+
+    assert!(Authority::try_new("", 80).is_err());
+    assert!(Authority::try_new("a b", 80).is_err());
+    assert!(Authority::try_new("example.com:80", 80).is_err());
+    assert!(Authority::try_new("user@example.com", 80).is_err());
+  }
+
+  #[test]
+  fn ipv6_validation() {
+    let valid = [
+      "::",
+      "::1",
+      "1::",
+      "1:2:3:4:5:6:7:8",
+      "1:2:3:4:5:6:7::",
+      "::2:3:4:5:6:7:8",
+      "fe80::1:2",
+      "::ffff:192.0.2.1",
+      "1:2:3:4:5:6:192.0.2.1",
+      "[2001:db8::1]",
+    ];
+    let invalid = [
+      "abc:80",
+      "::::",
+      "1:2:3",
+      ":1::",
+      "1::2::3",
+      "1:2:3:4:5:6:7:8:9",
+      "1:2:3:4:5:6:7:8::",
+      "12345::",
+      "::g",
+      "::1.2.3",
+      "::1.2.3.256",
+      "::01.2.3.4",
+      "1.2.3.4::",
+      "[::1]:80",
+      "[abc:80]",
+      "[]",
+    ];
+
+    for host in &valid {
+      assert!(Authority::try_new(host, 80).is_ok(), "{}", host);
+    }
+    for host in &invalid {
+      assert!(Authority::try_new(host, 80).is_err(), "{}", host);
+    }
+  }
+
+  #[test]
+  fn size_hint_is_exact() {
+    let authorities = [
+      Authority::new("example.com", 8080),
+      Authority::new("::1", 1).omit_default_port(1),
+      Authority::new("[::1]", 65535),
+    ];
+
+    for authority in &authorities {
+      let mut buffer = Vec::new();
+      authority.write_to(&mut buffer).unwrap();
+
+      let (lower, upper) = authority.size_hint();
+      assert!(lower <= buffer.len());
+      assert!(upper.unwrap() >= buffer.len());
+    }
+  }
+
+  #[test]
+  fn host_value() {
+    let values = [
+      (HostValue::new("example.com", 8080, 80), "example.com:8080"),
+      (HostValue::new("::1", 443, 443), "[::1]"),
+      (
+        HostValue::from_socket_addr("127.0.0.1:80".parse().unwrap(), 80),
+        "127.0.0.1",
+      ),
+      (
+        HostValue::from_socket_addr("[fe80::1%2]:65535".parse().unwrap(), 80),
+        "[fe80::1]:65535",
+      ),
+    ];
+
+    for (value, expected) in &values {
+      let mut buffer = Vec::new();
+      value.write_to(&mut buffer).unwrap();
+      assert_eq!(buffer, expected.as_bytes());
+
+      let (lower, upper) = value.size_hint();
+      assert!(lower <= buffer.len());
+      assert!(upper.unwrap() >= buffer.len());
+    }
+
+    assert!(HostValue::try_new("a/b", 80, 80).is_err());
+  }
+}
@@ -0,0 +1,99 @@
+use crate::{BufMut, InsufficientSpaceError};
+
+pub(crate) const STANDARD: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+pub(crate) const URL_SAFE: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Base64-encode `data` directly into `buffer`.
+///
+/// If `pad` is true then the output is padded out to a multiple of 4
+/// bytes with `=`.
+pub(crate) fn write_base64<B: BufMut>(
+  buffer: &mut B,
+  data: &[u8],
+  alphabet: &[u8; 64],
+  pad: bool,
+) -> Result<(), InsufficientSpaceError> {
+  if buffer.remaining_mut() < encoded_len(data.len(), pad) {
+    return Err(InsufficientSpaceError::default());
+  }
+
+  let mut chunks = data.chunks_exact(3);
+  for chunk in &mut chunks {
+    let n = (chunk[0] as u32) << 16 | (chunk[1] as u32) << 8 | chunk[2] as u32;
+
+    buffer.put_slice(&[
+      alphabet[(n >> 18) as usize & 63],
+      alphabet[(n >> 12) as usize & 63],
+      alphabet[(n >> 6) as usize & 63],
+      alphabet[n as usize & 63],
+    ]);
+  }
+
+  match *chunks.remainder() {
+    [a] => {
+      let n = (a as u32) << 16;
+      buffer.put_slice(&[
+        alphabet[(n >> 18) as usize & 63],
+        alphabet[(n >> 12) as usize & 63],
+      ]);
+      if pad {
+        buffer.put_slice(b"==");
+      }
+    }
+    [a, b] => {
+      let n = (a as u32) << 16 | (b as u32) << 8;
+      buffer.put_slice(&[
+        alphabet[(n >> 18) as usize & 63],
+        alphabet[(n >> 12) as usize & 63],
+        alphabet[(n >> 6) as usize & 63],
+      ]);
+      if pad {
+        buffer.put_u8(b'=');
+      }
+    }
+    _ => (),
+  }
+
+  Ok(())
+}
+
+/// The number of bytes that `write_base64` will write out for `len` input
+/// bytes.
+pub(crate) const fn encoded_len(len: usize, pad: bool) -> usize {
+  match (len % 3, pad) {
+    (0, _) => len / 3 * 4,
+    (_, true) => len / 3 * 4 + 4,
+    (rem, false) => len / 3 * 4 + rem + 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(data: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut buffer = vec![];
+    write_base64(&mut buffer, data, alphabet, pad).unwrap();
+    assert_eq!(buffer.len(), encoded_len(data.len(), pad));
+    String::from_utf8(buffer).unwrap()
+  }
+
+  #[test]
+  fn rfc4648_test_vectors() {
+    assert_eq!(encode(b"", STANDARD, true), "");
+    assert_eq!(encode(b"f", STANDARD, true), "Zg==");
+    assert_eq!(encode(b"fo", STANDARD, true), "Zm8=");
+    assert_eq!(encode(b"foo", STANDARD, true), "Zm9v");
+    assert_eq!(encode(b"foob", STANDARD, true), "Zm9vYg==");
+    assert_eq!(encode(b"fooba", STANDARD, true), "Zm9vYmE=");
+    assert_eq!(encode(b"foobar", STANDARD, true), "Zm9vYmFy");
+  }
+
+  #[test]
+  fn url_safe_unpadded() {
+    assert_eq!(encode(b"\xfb\xff", URL_SAFE, false), "-_8");
+    assert_eq!(encode(b"f", URL_SAFE, false), "Zg");
+  }
+}
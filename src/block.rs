@@ -0,0 +1,111 @@
+use crate::{Header, HttpWriteable};
+
+/// A set of HTTP headers that have been encoded in advance.
+///
+/// Servers often send the same set of static headers with every
+/// response. A `HeaderBlock` allows those headers to be validated and
+/// encoded once and then written out to each message with a single copy
+/// using [`HttpBuilder::header_block`](crate::HttpBuilder::header_block).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut block = HeaderBlock::new();
+/// block
+///   .header(Header::new("Server", "httpencode"))
+///   .header(Header::new("Cache-Control", "no-cache"));
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header_block(&block)?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Server: httpencode\r\n\
+///   Cache-Control: no-cache\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct HeaderBlock {
+  data: Vec<u8>,
+  count: usize,
+}
+
+impl HeaderBlock {
+  /// Create an empty `HeaderBlock`.
+  pub const fn new() -> Self {
+    Self {
+      data: Vec::new(),
+      count: 0,
+    }
+  }
+
+  /// Encode a header and add it to the end of this block.
+  pub fn header<'data, V, H>(&mut self, header: H) -> &mut Self
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    header
+      .into()
+      .write_to(&mut self.data)
+      .expect("Vec<u8> ran out of space");
+    self.count += 1;
+
+    self
+  }
+
+  /// Get the encoded headers, including the trailing CRLF after each
+  /// one.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.data
+  }
+
+  /// The number of headers within this block.
+  pub fn len(&self) -> usize {
+    self.count
+  }
+
+  /// Whether this block contains any headers.
+  pub fn is_empty(&self) -> bool {
+    self.count == 0
+  }
+}
+
+impl<'data, V> core::iter::FromIterator<Header<'data, V>> for HeaderBlock
+where
+  V: HttpWriteable,
+{
+  fn from_iter<I: IntoIterator<Item = Header<'data, V>>>(iter: I) -> Self {
+    let mut block = Self::new();
+    for header in iter {
+      block.header(header);
+    }
+    block
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn collect_headers() {
+    let block: HeaderBlock = vec![Header::new("A", "1"), Header::new("B", "2")]
+      .into_iter()
+      .collect();
+
+    assert_eq!(block.as_bytes(), b"A: 1\r\nB: 2\r\n");
+    assert_eq!(block.len(), 2);
+    assert!(HeaderBlock::new().is_empty());
+  }
+}
@@ -0,0 +1,155 @@
+use crate::{
+  BufMut, CheckedField, FallibleBufMut, Header, HttpBuilder,
+  InsufficientSpaceError, TrailerWriter, TransferEncoding, CRLF,
+};
+
+pub(crate) const TRANSFER_ENCODING: CheckedField =
+  CheckedField::new("Transfer-Encoding");
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+impl<B: BufMut> HttpBuilder<B> {
+  /// Write out a `Transfer-Encoding` header ending in `chunked`, finish
+  /// off the HTTP header, and start writing out a chunked body.
+  ///
+  /// `encoding` lists any codings applied before `chunked`. `chunked` is
+  /// always added to it so that the header matches how the body is
+  /// framed. Use
+  /// [`ValidatingBuilder::chunked`](crate::ValidatingBuilder::chunked) to
+  /// also reject messages that already have a `Content-Length`.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// let mut body = builder.chunked(TransferEncoding::CHUNKED)?;
+  /// body.chunk(b"Hello ")?.chunk(b"World!")?;
+  /// let output = body.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Transfer-Encoding: chunked\r\n\
+  ///   \r\n\
+  ///   6\r\nHello \r\n\
+  ///   6\r\nWorld!\r\n\
+  ///   0\r\n\r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn chunked(
+    mut self,
+    encoding: TransferEncoding,
+  ) -> Result<ChunkedWriter<B>, InsufficientSpaceError> {
+    let encoding = encoding.chunked();
+    self.header(Header::checked_new(TRANSFER_ENCODING, encoding))?;
+
+    Ok(ChunkedWriter::new(self.finish()?, false))
+  }
+}
+
+/// Writes out a message body using the chunked transfer coding.
+///
+/// This is created by [`HttpBuilder::chunked`](crate::HttpBuilder::chunked)
+/// once the header has been written. Each call to
+/// [`chunk`](Self::chunk) writes out one chunk and
+/// [`finish`](Self::finish) writes out the final zero-sized chunk that
+/// ends the body. Use [`trailers`](Self::trailers) instead of `finish` to
+/// send trailer fields after the body.
+#[derive(Debug)]
+pub struct ChunkedWriter<B: BufMut> {
+  buffer: B,
+  // Whether this was started from a ValidatingBuilder.
+  validated: bool,
+}
+
+impl<B: BufMut> ChunkedWriter<B> {
+  pub(crate) fn new(buffer: B, validated: bool) -> Self {
+    Self { buffer, validated }
+  }
+
+  /// Write out `data` as a single chunk.
+  ///
+  /// Empty chunks are skipped since a zero-sized chunk would end the body.
+  pub fn chunk(
+    &mut self,
+    data: &[u8],
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    if data.is_empty() {
+      return Ok(self);
+    }
+
+    let mut size = [0; 16];
+    let mut len = data.len();
+    let mut idx = size.len();
+    while len != 0 {
+      idx -= 1;
+      size[idx] = HEX[len & 0xF];
+      len >>= 4;
+    }
+
+    self.buffer.try_put_slice(&size[idx..])?;
+    self.buffer.try_put_slice(&CRLF)?;
+    self.buffer.try_put_slice(data)?;
+    self.buffer.try_put_slice(&CRLF)?;
+
+    Ok(self)
+  }
+
+  /// Write out the final zero-sized chunk and return the `BufMut`
+  /// instance that was being written to.
+  pub fn finish(mut self) -> Result<B, InsufficientSpaceError> {
+    self.buffer.try_put_slice(b"0\r\n\r\n")?;
+    Ok(self.buffer)
+  }
+
+  /// Write out the final zero-sized chunk and start writing out trailer
+  /// fields.
+  ///
+  /// See [`Trailer`](crate::Trailer) for an example.
+  pub fn trailers(
+    mut self,
+  ) -> Result<TrailerWriter<B>, InsufficientSpaceError> {
+    self.buffer.try_put_slice(b"0\r\n")?;
+    Ok(TrailerWriter::new(self.buffer, self.validated))
+  }
+
+  /// Return the buffer without ending the body.
+  pub fn into_inner(self) -> B {
+    self.buffer
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn chunk_sizes() {
+    let mut writer = ChunkedWriter::new(Vec::new(), false);
+    writer.chunk(b"").unwrap();
+    writer.chunk(&[b'a'; 0x1F]).unwrap();
+
+    let output = writer.finish().unwrap();
+    let mut expected = b"1f\r\n".to_vec();
+    expected.extend_from_slice(&[b'a'; 0x1F]);
+    expected.extend_from_slice(b"\r\n0\r\n\r\n");
+
+    assert_eq!(output, expected);
+  }
+
+  #[test]
+  fn insufficient_space() {
+    let mut buffer = [0u8; 8];
+    let mut writer = ChunkedWriter::new(&mut buffer[..], false);
+
+    writer.chunk(b"abc").unwrap();
+    assert!(writer.chunk(b"abc").is_err());
+  }
+}
@@ -0,0 +1,498 @@
+use core::convert::TryFrom;
+
+use crate::{
+  is_token, BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError, Separated, Weighted,
+};
+
+/// A content coding, as used within `Accept-Encoding` and
+/// `Content-Encoding` headers.
+///
+/// Most users should use the provided constants. Other codings can be
+/// created with [`new`](ContentCoding::new) as long as they are tokens.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ContentCoding<'data>(&'data str);
+
+impl<'data> ContentCoding<'data> {
+  /// gzip (RFC 1952).
+  pub const GZIP: Self = Self("gzip");
+  /// deflate (RFC 1950).
+  pub const DEFLATE: Self = Self("deflate");
+  /// compress (LZW).
+  pub const COMPRESS: Self = Self("compress");
+  /// Brotli (RFC 7932).
+  pub const BR: Self = Self("br");
+  /// Zstandard (RFC 8878).
+  pub const ZSTD: Self = Self("zstd");
+  /// No encoding at all.
+  pub const IDENTITY: Self = Self("identity");
+  /// Any coding not explicitly listed.
+  pub const ANY: Self = Self("*");
+
+  /// Create a custom content coding.
+  ///
+  /// # Errors
+  /// Returns an error if `name` is not a valid token.
+  pub const fn try_new(name: &'data str) -> Result<Self, InvalidHeaderError> {
+    if !is_token(name) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(name))
+  }
+
+  /// Create a custom content coding.
+  ///
+  /// # Panics
+  /// Panics if `name` is not a valid token.
+  pub const fn new(name: &'data str) -> Self {
+    match Self::try_new(name) {
+      Ok(coding) => coding,
+      Err(_) => const_panic!("Content coding contained invalid character"),
+    }
+  }
+
+  /// Get the string representation of this coding.
+  pub const fn as_str(&self) -> &'data str {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data str> for ContentCoding<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(name: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(name)
+  }
+}
+
+impl HttpWriteable for ContentCoding<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.0.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.0.len(), Some(self.0.len()))
+  }
+}
+
+/// A transfer coding, as used within `TE` and `Transfer-Encoding`
+/// headers.
+///
+/// Most users should use the provided constants. Other codings can be
+/// created with [`new`](TransferCoding::new) as long as they are tokens.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TransferCoding<'data>(&'data str);
+
+impl<'data> TransferCoding<'data> {
+  /// gzip (RFC 1952).
+  pub const GZIP: Self = Self("gzip");
+  /// deflate (RFC 1950).
+  pub const DEFLATE: Self = Self("deflate");
+  /// compress (LZW).
+  pub const COMPRESS: Self = Self("compress");
+  /// chunked (RFC 7230 section 4.1).
+  pub const CHUNKED: Self = Self("chunked");
+
+  /// Create a custom transfer coding.
+  ///
+  /// # Errors
+  /// Returns an error if `name` is not a valid token.
+  pub const fn try_new(name: &'data str) -> Result<Self, InvalidHeaderError> {
+    if !is_token(name) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(name))
+  }
+
+  /// Create a custom transfer coding.
+  ///
+  /// # Panics
+  /// Panics if `name` is not a valid token.
+  pub const fn new(name: &'data str) -> Self {
+    match Self::try_new(name) {
+      Ok(coding) => coding,
+      Err(_) => const_panic!("Transfer coding contained invalid character"),
+    }
+  }
+
+  /// Get the string representation of this coding.
+  pub const fn as_str(&self) -> &'data str {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data str> for TransferCoding<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(name: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(name)
+  }
+}
+
+impl HttpWriteable for TransferCoding<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.0.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.0.len(), Some(self.0.len()))
+  }
+}
+
+/// An `Accept-Encoding` header value listing the content codings a
+/// client accepts along with their quality values.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const ACCEPT_ENCODING: AcceptEncoding = AcceptEncoding::new(&[
+///   Weighted::new(ContentCoding::BR),
+///   Weighted::new(ContentCoding::GZIP).q(QValue::new(800)),
+///   Weighted::new(ContentCoding::IDENTITY).q(QValue::new(100)),
+/// ]);
+///
+/// let mut builder = HttpBuilder::request(
+///     vec![],
+///     Method::GET,
+///     Uri::new(b"/"),
+///     Version::HTTP_1_1
+/// )?;
+/// builder.header(Header::new("Accept-Encoding", ACCEPT_ENCODING))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "GET / HTTP/1.1\r\n\
+///   Accept-Encoding: br, gzip;q=0.8, identity;q=0.1\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AcceptEncoding<'data> {
+  codings: &'data [Weighted<ContentCoding<'data>>],
+}
+
+impl<'data> AcceptEncoding<'data> {
+  /// Create an `Accept-Encoding` value from a list of codings.
+  ///
+  /// An empty list means that no coding other than `identity` is
+  /// acceptable.
+  pub const fn new(codings: &'data [Weighted<ContentCoding<'data>>]) -> Self {
+    Self { codings }
+  }
+
+  /// The codings in the order they are written out.
+  pub const fn codings(&self) -> &'data [Weighted<ContentCoding<'data>>] {
+    self.codings
+  }
+}
+
+impl HttpWriteable for AcceptEncoding<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    Separated::new(self.codings).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    Separated::new(self.codings).size_hint()
+  }
+}
+
+/// A `TE` header value listing the transfer codings a client accepts
+/// along with their quality values.
+///
+/// Whether the client accepts trailer fields is set separately with
+/// [`trailers`](Te::trailers) since the `trailers` keyword cannot have a
+/// quality value.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const TE: Te = Te::new(&[
+///   Weighted::new(TransferCoding::GZIP).q(QValue::new(500)),
+/// ])
+/// .trailers();
+///
+/// let mut buffer = Vec::new();
+/// Header::new("TE", TE).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"TE: trailers, gzip;q=0.5\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Te<'data> {
+  codings: &'data [Weighted<TransferCoding<'data>>],
+  trailers: bool,
+}
+
+impl<'data> Te<'data> {
+  /// Create a `TE` value from a list of codings.
+  pub const fn new(codings: &'data [Weighted<TransferCoding<'data>>]) -> Self {
+    Self {
+      codings,
+      trailers: false,
+    }
+  }
+
+  /// Indicate that trailer fields are accepted.
+  pub const fn trailers(self) -> Self {
+    Self {
+      trailers: true,
+      ..self
+    }
+  }
+
+  /// The codings in the order they are written out.
+  pub const fn codings(&self) -> &'data [Weighted<TransferCoding<'data>>] {
+    self.codings
+  }
+
+  /// Whether trailer fields are accepted.
+  pub const fn accepts_trailers(&self) -> bool {
+    self.trailers
+  }
+}
+
+impl HttpWriteable for Te<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.trailers {
+      buffer.try_put_slice(b"trailers")?;
+
+      if !self.codings.is_empty() {
+        buffer.try_put_slice(b", ")?;
+      }
+    }
+
+    Separated::new(self.codings).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper) = Separated::new(self.codings).size_hint();
+    let trailers = match (self.trailers, self.codings.is_empty()) {
+      (false, _) => 0,
+      (true, true) => "trailers".len(),
+      (true, false) => "trailers, ".len(),
+    };
+
+    (
+      lower.saturating_add(trailers),
+      upper.and_then(|upper| upper.checked_add(trailers)),
+    )
+  }
+}
+
+/// Whether `coding` is `chunked`, ignoring case.
+const fn is_chunked(coding: &str) -> bool {
+  let bytes = coding.as_bytes();
+  let chunked = TransferCoding::CHUNKED.0.as_bytes();
+
+  if bytes.len() != chunked.len() {
+    return false;
+  }
+
+  let mut idx = 0;
+  while idx < bytes.len() {
+    if bytes[idx].to_ascii_lowercase() != chunked[idx] {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  true
+}
+
+/// A `Transfer-Encoding` header value listing the transfer codings that
+/// were applied to the message body, in the order they were applied.
+///
+/// `chunked` may only be applied once and must come last so it can't be
+/// part of the list of codings and is added with
+/// [`chunked`](TransferEncoding::chunked) instead. See
+/// [`HttpBuilder::chunked`](crate::HttpBuilder::chunked) for writing out
+/// a chunked body.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const TRANSFER_ENCODING: TransferEncoding =
+///   TransferEncoding::new(&[TransferCoding::GZIP]).chunked();
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Transfer-Encoding", TRANSFER_ENCODING).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Transfer-Encoding: gzip, chunked\r\n");
+/// assert!(TransferEncoding::try_new(&[TransferCoding::CHUNKED]).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TransferEncoding<'data> {
+  codings: &'data [TransferCoding<'data>],
+  chunked: bool,
+}
+
+impl<'data> TransferEncoding<'data> {
+  /// `Transfer-Encoding: chunked`
+  pub const CHUNKED: Self = Self {
+    codings: &[],
+    chunked: true,
+  };
+
+  /// Create a `Transfer-Encoding` value from a list of codings.
+  ///
+  /// # Errors
+  /// Returns an error if `codings` contains `chunked`.
+  pub const fn try_new(
+    codings: &'data [TransferCoding<'data>],
+  ) -> Result<Self, InvalidHeaderError> {
+    let mut idx = 0;
+    while idx < codings.len() {
+      if is_chunked(codings[idx].0) {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self {
+      codings,
+      chunked: false,
+    })
+  }
+
+  /// Create a `Transfer-Encoding` value from a list of codings.
+  ///
+  /// # Panics
+  /// Panics if `codings` contains `chunked`.
+  pub const fn new(codings: &'data [TransferCoding<'data>]) -> Self {
+    match Self::try_new(codings) {
+      Ok(encoding) => encoding,
+      Err(_) => const_panic!("Use TransferEncoding::chunked to add chunked"),
+    }
+  }
+
+  /// Apply `chunked` after all other codings.
+  pub const fn chunked(self) -> Self {
+    Self {
+      chunked: true,
+      ..self
+    }
+  }
+
+  /// The codings applied before `chunked`, in order.
+  pub const fn codings(&self) -> &'data [TransferCoding<'data>] {
+    self.codings
+  }
+
+  /// Whether `chunked` is applied last.
+  pub const fn is_chunked(&self) -> bool {
+    self.chunked
+  }
+}
+
+impl HttpWriteable for TransferEncoding<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    Separated::new(self.codings).write_to(buffer)?;
+
+    if self.chunked {
+      if !self.codings.is_empty() {
+        buffer.try_put_slice(b", ")?;
+      }
+
+      buffer.try_put_slice(b"chunked")?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper) = Separated::new(self.codings).size_hint();
+    let chunked = match (self.chunked, self.codings.is_empty()) {
+      (false, _) => 0,
+      (true, true) => "chunked".len(),
+      (true, false) => ", chunked".len(),
+    };
+
+    (
+      lower.saturating_add(chunked),
+      upper.and_then(|upper| upper.checked_add(chunked)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode;
+  use crate::QValue;
+
+  #[test]
+  fn accept_encoding() {
+    let codings = [
+      Weighted::new(ContentCoding::ZSTD),
+      Weighted::new(ContentCoding::new("x-custom")).q(QValue::new(250)),
+      Weighted::new(ContentCoding::ANY).q(QValue::ZERO),
+    ];
+
+    assert_eq!(
+      encode(AcceptEncoding::new(&codings)),
+      b"zstd, x-custom;q=0.25, *;q=0"
+    );
+    assert_eq!(encode(AcceptEncoding::new(&[])), b"");
+  }
+
+  #[test]
+  fn te() {
+    let codings = [Weighted::new(TransferCoding::DEFLATE)];
+
+    assert_eq!(encode(Te::new(&[]).trailers()), b"trailers");
+    assert_eq!(encode(Te::new(&codings)), b"deflate");
+    assert_eq!(encode(Te::new(&codings).trailers()), b"trailers, deflate");
+  }
+
+  #[test]
+  fn transfer_encoding() {
+    let codings = [TransferCoding::GZIP, TransferCoding::new("x-custom")];
+
+    assert_eq!(encode(TransferEncoding::CHUNKED), b"chunked");
+    assert_eq!(encode(TransferEncoding::new(&codings)), b"gzip, x-custom");
+    assert_eq!(
+      encode(TransferEncoding::new(&codings).chunked()),
+      b"gzip, x-custom, chunked"
+    );
+    assert!(
+      TransferEncoding::try_new(&[TransferCoding::new("Chunked")]).is_err()
+    );
+  }
+
+  #[test]
+  fn invalid_codings() {
+    assert!(ContentCoding::try_new("").is_err());
+    assert!(ContentCoding::try_new("gzip;q=1").is_err());
+    assert!(TransferCoding::try_new("a, b").is_err());
+  }
+}
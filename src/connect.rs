@@ -0,0 +1,109 @@
+use crate::{
+  Authority, BufMut, FallibleBufMut, HttpBuilder, HttpEncodeError,
+  HttpWriteable, InvalidMessageError, Method, Version, CRLF,
+};
+
+impl<B: BufMut> HttpBuilder<B> {
+  /// Start a `CONNECT` request for a tunnel to `host` and `port`.
+  ///
+  /// The request target is written in authority-form (`host:port`) as
+  /// required for `CONNECT` requests. IPv6 addresses may be given with
+  /// or without brackets. See also
+  /// [`connect_to`](crate::HttpBuilder::connect_to).
+  ///
+  /// # Errors
+  /// Returns an error if `host` is not a valid host name or IP address
+  /// or if there is not enough space in the buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder =
+  ///   HttpBuilder::connect(vec![], "example.com", 443, Version::HTTP_1_1)?;
+  /// builder.header(Header::new("Host", "example.com:443"))?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "CONNECT example.com:443 HTTP/1.1\r\n\
+  ///   Host: example.com:443\r\n\
+  ///   \r\n"
+  /// );
+  ///
+  /// assert!(HttpBuilder::connect(vec![], "a b", 80, Version::HTTP_1_1).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn connect(
+    buffer: B,
+    host: &str,
+    port: u16,
+    version: Version,
+  ) -> Result<Self, HttpEncodeError> {
+    Self::connect_to(buffer, Authority::try_new(host, port)?, version)
+  }
+
+  /// Start a `CONNECT` request for a tunnel to `authority`.
+  ///
+  /// # Errors
+  /// Returns an error if the port of `authority` has been omitted, since
+  /// `CONNECT` requires one, or if there is not enough space in the
+  /// buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let authority = Authority::try_new("::1", 8080)?;
+  ///
+  /// let mut builder =
+  ///   HttpBuilder::connect_to(vec![], authority, Version::HTTP_1_1)?;
+  /// builder.header(Header::new("Host", authority))?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "CONNECT [::1]:8080 HTTP/1.1\r\n\
+  ///   Host: [::1]:8080\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn connect_to(
+    mut buffer: B,
+    authority: Authority,
+    version: Version,
+  ) -> Result<Self, HttpEncodeError> {
+    if authority.port().is_none() {
+      return Err(
+        InvalidMessageError("CONNECT request target requires a port").into(),
+      );
+    }
+
+    let start = buffer.remaining_mut();
+
+    Method::CONNECT.write_to(&mut buffer)?;
+    buffer.try_put_u8(b' ')?;
+    authority.write_to(&mut buffer)?;
+    buffer.try_put_u8(b' ')?;
+    version.write_to(&mut buffer)?;
+    buffer.try_put_slice(&CRLF)?;
+
+    Ok(Self::started(buffer, start, None, version))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn connect_requires_port() {
+    let authority = Authority::new("example.com", 443).omit_default_port(443);
+    assert!(
+      HttpBuilder::connect_to(vec![], authority, Version::HTTP_1_1).is_err()
+    );
+  }
+}
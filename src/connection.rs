@@ -0,0 +1,179 @@
+use crate::{
+  is_token, BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError,
+};
+
+/// Fields that only apply to a single HTTP/1.x connection and must not be
+/// forwarded, even when they are not listed in `Connection`.
+pub(crate) const CONNECTION_SPECIFIC: &[&str] = &[
+  "Connection",
+  "Keep-Alive",
+  "Proxy-Connection",
+  "Transfer-Encoding",
+  "Upgrade",
+];
+
+/// A `Connection` header value listing connection options.
+///
+/// Options are either one of the well-known options such as `close` or
+/// the names of additional hop-by-hop header fields that the next
+/// recipient must remove before forwarding the message.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let options = ConnectionOptions::new(&["upgrade", "X-Hop"]);
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Connection", options).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Connection: upgrade, X-Hop\r\n");
+/// assert!(options.is_hop_by_hop("x-hop"));
+/// assert!(options.is_hop_by_hop("Transfer-Encoding"));
+/// assert!(!options.is_hop_by_hop("Content-Type"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ConnectionOptions<'data> {
+  options: &'data [&'data str],
+}
+
+impl<'data> ConnectionOptions<'data> {
+  /// `Connection: close`
+  pub const CLOSE: Self = Self::new(&["close"]);
+  /// `Connection: keep-alive`
+  pub const KEEP_ALIVE: Self = Self::new(&["keep-alive"]);
+  /// `Connection: upgrade`
+  pub const UPGRADE: Self = Self::new(&["upgrade"]);
+
+  /// Create a list of connection options.
+  ///
+  /// # Errors
+  /// Returns an error if `options` is empty or if any option is not a
+  /// valid token.
+  pub const fn try_new(
+    options: &'data [&'data str],
+  ) -> Result<Self, InvalidHeaderError> {
+    if options.is_empty() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let mut idx = 0;
+    while idx < options.len() {
+      if !is_token(options[idx]) {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self { options })
+  }
+
+  /// Create a list of connection options.
+  ///
+  /// # Panics
+  /// Panics if `options` is empty or if any option is not a valid token.
+  pub const fn new(options: &'data [&'data str]) -> Self {
+    match Self::try_new(options) {
+      Ok(options) => options,
+      Err(_) => const_panic!("Connection option was not a token"),
+    }
+  }
+
+  /// The options in the order they are written out.
+  pub const fn options(&self) -> &'data [&'data str] {
+    self.options
+  }
+
+  /// Whether `option` is listed, ignoring case.
+  pub fn contains(&self, option: &str) -> bool {
+    self
+      .options
+      .iter()
+      .any(|listed| listed.eq_ignore_ascii_case(option))
+  }
+
+  /// Whether the header `field` must be removed before forwarding a
+  /// message sent with these options. This is the case for every listed
+  /// option as well as for the fields that are always connection-specific
+  /// such as `Transfer-Encoding`.
+  pub fn is_hop_by_hop(&self, field: &str) -> bool {
+    self.contains(field)
+      || CONNECTION_SPECIFIC
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(field))
+  }
+}
+
+impl HttpWriteable for ConnectionOptions<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    for (idx, option) in self.options.iter().enumerate() {
+      if idx != 0 {
+        buffer.try_put_slice(b", ")?;
+      }
+
+      buffer.try_put_slice(option.as_bytes())?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self
+      .options
+      .iter()
+      .map(|option| option.len() + 2)
+      .sum::<usize>()
+      - 2;
+
+    (len, Some(len))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(options: ConnectionOptions) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    options.write_to(&mut buffer).unwrap();
+
+    assert_eq!(options.size_hint(), (buffer.len(), Some(buffer.len())));
+
+    buffer
+  }
+
+  #[test]
+  fn options() {
+    assert_eq!(encode(ConnectionOptions::CLOSE), b"close");
+    assert_eq!(
+      encode(ConnectionOptions::new(&["keep-alive", "te"])),
+      b"keep-alive, te"
+    );
+  }
+
+  #[test]
+  fn hop_by_hop() {
+    let options = ConnectionOptions::new(&["close", "X-Trace"]);
+
+    assert!(options.contains("CLOSE"));
+    assert!(!options.contains("keep-alive"));
+    assert!(options.is_hop_by_hop("x-trace"));
+    assert!(options.is_hop_by_hop("keep-alive"));
+    assert!(!options.is_hop_by_hop("Content-Length"));
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(ConnectionOptions::try_new(&[]).is_err());
+    assert!(ConnectionOptions::try_new(&[""]).is_err());
+    assert!(ConnectionOptions::try_new(&["a, b"]).is_err());
+    assert!(ConnectionOptions::try_new(&["close\r\n"]).is_err());
+  }
+}
@@ -0,0 +1,388 @@
+use crate::{
+  is_token, BufMut, FallibleBufMut, HttpDate, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError,
+};
+
+/// Whether `byte` is a cookie-octet as defined in RFC 6265.
+const fn is_cookie_octet(byte: u8) -> bool {
+  matches!(byte, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+/// Whether `value` is a valid cookie-value: a run of cookie-octets that
+/// may optionally be surrounded by double quotes.
+const fn is_cookie_value(value: &str) -> bool {
+  let mut bytes = value.as_bytes();
+
+  if bytes.len() >= 2 && bytes[0] == b'"' && bytes[bytes.len() - 1] == b'"' {
+    let (_, rest) = bytes.split_at(1);
+    let (rest, _) = rest.split_at(rest.len() - 1);
+    bytes = rest;
+  }
+
+  let mut idx = 0;
+  while idx < bytes.len() {
+    if !is_cookie_octet(bytes[idx]) {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  true
+}
+
+/// Whether `value` can be used as the value of a `Domain` or `Path`
+/// attribute. These may contain any character except for CTLs and `;`.
+const fn is_attribute_value(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      0x00..=0x1F | 0x7F | b';' => return false,
+      _ => (),
+    }
+
+    idx += 1;
+  }
+
+  true
+}
+
+/// The value of the `SameSite` attribute of a cookie.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SameSite {
+  /// Only send the cookie with same-site requests.
+  Strict,
+  /// Also send the cookie when navigating to the site from elsewhere.
+  Lax,
+  /// Send the cookie with all requests. Browsers require that such
+  /// cookies are also marked as [`secure`](SetCookie::secure).
+  None,
+}
+
+impl SameSite {
+  const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Strict => "Strict",
+      Self::Lax => "Lax",
+      Self::None => "None",
+    }
+  }
+}
+
+/// A `Set-Cookie` header value.
+///
+/// The cookie name must be a token and the value must be made up of
+/// RFC 6265 cookie-octets, so neither can be used to inject extra
+/// attributes or headers. Attributes are added using the builder
+/// methods and are always written out in the same order.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const SESSION: SetCookie = SetCookie::new("session", "abc123")
+///   .path("/")
+///   .max_age(3600)
+///   .secure()
+///   .http_only()
+///   .same_site(SameSite::Lax);
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Set-Cookie", SESSION))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Set-Cookie: session=abc123; Max-Age=3600; Path=/; Secure; HttpOnly; SameSite=Lax\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SetCookie<'data> {
+  name: &'data str,
+  value: &'data str,
+  expires: Option<HttpDate>,
+  max_age: Option<u64>,
+  domain: Option<&'data str>,
+  path: Option<&'data str>,
+  secure: bool,
+  http_only: bool,
+  same_site: Option<SameSite>,
+}
+
+impl<'data> SetCookie<'data> {
+  /// Create a cookie with the given name and value and no attributes.
+  ///
+  /// # Errors
+  /// Returns an error if `name` is not a token or if `value` contains
+  /// anything other than cookie-octets, optionally surrounded by double
+  /// quotes.
+  pub const fn try_new(
+    name: &'data str,
+    value: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_token(name) || !is_cookie_value(value) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      name,
+      value,
+      expires: None,
+      max_age: None,
+      domain: None,
+      path: None,
+      secure: false,
+      http_only: false,
+      same_site: None,
+    })
+  }
+
+  /// Create a cookie with the given name and value and no attributes.
+  ///
+  /// # Panics
+  /// Panics if `name` is not a token or if `value` contains anything
+  /// other than cookie-octets, optionally surrounded by double quotes.
+  pub const fn new(name: &'data str, value: &'data str) -> Self {
+    match Self::try_new(name, value) {
+      Ok(cookie) => cookie,
+      Err(_) => const_panic!("Invalid cookie name or value"),
+    }
+  }
+
+  /// Set the `Expires` attribute.
+  pub const fn expires(self, expires: HttpDate) -> Self {
+    Self {
+      expires: Some(expires),
+      ..self
+    }
+  }
+
+  /// Set the `Max-Age` attribute, in seconds.
+  pub const fn max_age(self, secs: u64) -> Self {
+    Self {
+      max_age: Some(secs),
+      ..self
+    }
+  }
+
+  /// Set the `Domain` attribute.
+  ///
+  /// # Errors
+  /// Returns an error if `domain` contains a control character or `;`.
+  pub const fn try_domain(
+    self,
+    domain: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_attribute_value(domain) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      domain: Some(domain),
+      ..self
+    })
+  }
+
+  /// Set the `Domain` attribute.
+  ///
+  /// # Panics
+  /// Panics if `domain` contains a control character or `;`.
+  pub const fn domain(self, domain: &'data str) -> Self {
+    match self.try_domain(domain) {
+      Ok(cookie) => cookie,
+      Err(_) => const_panic!("Invalid cookie domain"),
+    }
+  }
+
+  /// Set the `Path` attribute.
+  ///
+  /// # Errors
+  /// Returns an error if `path` contains a control character or `;`.
+  pub const fn try_path(
+    self,
+    path: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_attribute_value(path) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      path: Some(path),
+      ..self
+    })
+  }
+
+  /// Set the `Path` attribute.
+  ///
+  /// # Panics
+  /// Panics if `path` contains a control character or `;`.
+  pub const fn path(self, path: &'data str) -> Self {
+    match self.try_path(path) {
+      Ok(cookie) => cookie,
+      Err(_) => const_panic!("Invalid cookie path"),
+    }
+  }
+
+  /// Add the `Secure` attribute.
+  pub const fn secure(self) -> Self {
+    Self {
+      secure: true,
+      ..self
+    }
+  }
+
+  /// Add the `HttpOnly` attribute.
+  pub const fn http_only(self) -> Self {
+    Self {
+      http_only: true,
+      ..self
+    }
+  }
+
+  /// Set the `SameSite` attribute.
+  pub const fn same_site(self, same_site: SameSite) -> Self {
+    Self {
+      same_site: Some(same_site),
+      ..self
+    }
+  }
+
+  /// The name of the cookie.
+  pub const fn name(&self) -> &'data str {
+    self.name
+  }
+
+  /// The value of the cookie.
+  pub const fn value(&self) -> &'data str {
+    self.value
+  }
+}
+
+impl HttpWriteable for SetCookie<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.name.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    buffer.try_put_slice(self.value.as_bytes())?;
+
+    if let Some(expires) = self.expires {
+      buffer.try_put_slice(b"; Expires=")?;
+      expires.write_to(buffer)?;
+    }
+
+    if let Some(max_age) = self.max_age {
+      buffer.try_put_slice(b"; Max-Age=")?;
+      max_age.write_to(buffer)?;
+    }
+
+    if let Some(domain) = self.domain {
+      buffer.try_put_slice(b"; Domain=")?;
+      buffer.try_put_slice(domain.as_bytes())?;
+    }
+
+    if let Some(path) = self.path {
+      buffer.try_put_slice(b"; Path=")?;
+      buffer.try_put_slice(path.as_bytes())?;
+    }
+
+    if self.secure {
+      buffer.try_put_slice(b"; Secure")?;
+    }
+
+    if self.http_only {
+      buffer.try_put_slice(b"; HttpOnly")?;
+    }
+
+    if let Some(same_site) = self.same_site {
+      buffer.try_put_slice(b"; SameSite=")?;
+      buffer.try_put_slice(same_site.as_str().as_bytes())?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut len = self.name.len() + self.value.len() + 1;
+    let mut max_age = (0, Some(0));
+
+    if let Some(expires) = self.expires {
+      len += "; Expires=".len() + expires.size_hint().0;
+    }
+    if let Some(secs) = self.max_age {
+      len += "; Max-Age=".len();
+      max_age = secs.size_hint();
+    }
+    if let Some(domain) = self.domain {
+      len += "; Domain=".len() + domain.len();
+    }
+    if let Some(path) = self.path {
+      len += "; Path=".len() + path.len();
+    }
+    if self.secure {
+      len += "; Secure".len();
+    }
+    if self.http_only {
+      len += "; HttpOnly".len();
+    }
+    if let Some(same_site) = self.same_site {
+      len += "; SameSite=".len() + same_site.as_str().len();
+    }
+
+    (
+      len + max_age.0,
+      max_age.1.and_then(|upper| len.checked_add(upper)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn all_attributes() {
+    let cookie = SetCookie::new("id", "\"a1\"")
+      .same_site(SameSite::Strict)
+      .http_only()
+      .secure()
+      .path("/docs")
+      .domain("example.com")
+      .max_age(0)
+      .expires(HttpDate::from_unix_secs(784_111_777));
+
+    assert_eq!(
+      encode_str(cookie),
+      "id=\"a1\"; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Max-Age=0; \
+       Domain=example.com; Path=/docs; Secure; HttpOnly; SameSite=Strict"
+    );
+    assert_eq!(encode_str(SetCookie::new("a", "")), "a=");
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(SetCookie::try_new("", "x").is_err());
+    assert!(SetCookie::try_new("a b", "x").is_err());
+    assert!(SetCookie::try_new("a=", "x").is_err());
+
+    for value in &["a;b", "a b", "a,b", "a\\b", "\"", "a\"", "\r\n", "\u{e9}"] {
+      assert!(SetCookie::try_new("a", value).is_err(), "{:?}", value);
+    }
+
+    let cookie = SetCookie::new("a", "b");
+    assert!(cookie.try_path("/; Secure").is_err());
+    assert!(cookie.try_domain("example.com\r\nX: y").is_err());
+  }
+}
@@ -0,0 +1,329 @@
+use crate::{
+  BufMut, CheckedField, Header, HttpBuilder, InsufficientSpaceError,
+  InvalidHeaderError, Method, Separated, Uri,
+};
+
+const ALLOW_ORIGIN: CheckedField =
+  CheckedField::new("Access-Control-Allow-Origin");
+const ALLOW_CREDENTIALS: CheckedField =
+  CheckedField::new("Access-Control-Allow-Credentials");
+const ALLOW_METHODS: CheckedField =
+  CheckedField::new("Access-Control-Allow-Methods");
+const ALLOW_HEADERS: CheckedField =
+  CheckedField::new("Access-Control-Allow-Headers");
+const EXPOSE_HEADERS: CheckedField =
+  CheckedField::new("Access-Control-Expose-Headers");
+const MAX_AGE: CheckedField = CheckedField::new("Access-Control-Max-Age");
+const VARY: CheckedField = CheckedField::new("Vary");
+
+/// A CORS policy that writes out the `Access-Control-*` response headers.
+///
+/// The policy either allows any origin or a fixed list of origins. The
+/// `Origin` of the request is passed in when writing out the headers so
+/// that only allowed origins are echoed back. `Vary: Origin` is written
+/// out whenever the response depends on the request origin, so caches
+/// don't serve a response meant for one origin to another.
+///
+/// Browsers don't allow `Access-Control-Allow-Origin: *` for requests
+/// with credentials so when credentials are allowed the request origin is
+/// always echoed back instead.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const CORS: Cors = Cors::origins(&["https://app.example.com"])
+///   .methods(&[Method::GET, Method::POST])
+///   .allow_headers(&[CheckedField::new("Content-Type")])
+///   .credentials()
+///   .max_age(600);
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(204, "No Content")
+/// )?;
+/// CORS.write_preflight(&mut builder, Some("https://app.example.com"))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 204 No Content\r\n\
+///   Access-Control-Allow-Origin: https://app.example.com\r\n\
+///   Access-Control-Allow-Credentials: true\r\n\
+///   Access-Control-Allow-Methods: GET, POST\r\n\
+///   Access-Control-Allow-Headers: Content-Type\r\n\
+///   Access-Control-Max-Age: 600\r\n\
+///   Vary: Origin\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Cors<'data> {
+  origins: Option<&'data [&'data str]>,
+  methods: &'data [Method<'data>],
+  allow_headers: &'data [CheckedField<'data>],
+  expose_headers: &'data [CheckedField<'data>],
+  credentials: bool,
+  max_age: Option<u64>,
+}
+
+impl<'data> Cors<'data> {
+  /// Create a policy that allows requests from any origin.
+  pub const fn any_origin() -> Self {
+    Self {
+      origins: None,
+      methods: &[],
+      allow_headers: &[],
+      expose_headers: &[],
+      credentials: false,
+      max_age: None,
+    }
+  }
+
+  /// Create a policy that only allows requests from `origins`, such as
+  /// `https://example.com`.
+  ///
+  /// # Errors
+  /// Returns an error if any origin is empty or contains a character
+  /// that is not allowed within a URI.
+  pub const fn try_origins(
+    origins: &'data [&'data str],
+  ) -> Result<Self, InvalidHeaderError> {
+    let mut idx = 0;
+    while idx < origins.len() {
+      if Uri::try_new_strict(origins[idx].as_bytes()).is_err() {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self {
+      origins: Some(origins),
+      ..Self::any_origin()
+    })
+  }
+
+  /// Create a policy that only allows requests from `origins`, such as
+  /// `https://example.com`.
+  ///
+  /// # Panics
+  /// Panics if any origin is empty or contains a character that is not
+  /// allowed within a URI.
+  pub const fn origins(origins: &'data [&'data str]) -> Self {
+    match Self::try_origins(origins) {
+      Ok(cors) => cors,
+      Err(_) => const_panic!("CORS origin contained invalid character"),
+    }
+  }
+
+  /// Set the methods listed in `Access-Control-Allow-Methods`.
+  pub const fn methods(self, methods: &'data [Method<'data>]) -> Self {
+    Self { methods, ..self }
+  }
+
+  /// Set the request headers listed in `Access-Control-Allow-Headers`.
+  pub const fn allow_headers(
+    self,
+    fields: &'data [CheckedField<'data>],
+  ) -> Self {
+    Self {
+      allow_headers: fields,
+      ..self
+    }
+  }
+
+  /// Set the response headers listed in `Access-Control-Expose-Headers`.
+  pub const fn expose_headers(
+    self,
+    fields: &'data [CheckedField<'data>],
+  ) -> Self {
+    Self {
+      expose_headers: fields,
+      ..self
+    }
+  }
+
+  /// Allow requests with credentials (cookies or `Authorization`).
+  pub const fn credentials(self) -> Self {
+    Self {
+      credentials: true,
+      ..self
+    }
+  }
+
+  /// Set how many seconds the results of a preflight request can be
+  /// cached for.
+  pub const fn max_age(self, secs: u64) -> Self {
+    Self {
+      max_age: Some(secs),
+      ..self
+    }
+  }
+
+  /// Whether requests from `origin` are allowed by this policy.
+  pub fn allows_origin(&self, origin: &str) -> bool {
+    match self.origins {
+      None => Uri::try_new_strict(origin.as_bytes()).is_ok(),
+      Some(origins) => origins
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(origin)),
+    }
+  }
+
+  /// Write out the CORS headers for a response to an actual (non
+  /// preflight) request with the given `Origin` header.
+  pub fn write_headers<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+    origin: Option<&str>,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.write_origin(builder, origin)? && !self.expose_headers.is_empty() {
+      let fields = self.expose_headers.iter().map(|field| field.as_str());
+      let fields = Separated::new(fields);
+      builder.header(Header::checked_new(EXPOSE_HEADERS, fields))?;
+    }
+
+    self.write_vary(builder)
+  }
+
+  /// Write out the CORS headers for a response to a preflight `OPTIONS`
+  /// request with the given `Origin` header.
+  pub fn write_preflight<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+    origin: Option<&str>,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.write_origin(builder, origin)? {
+      if !self.methods.is_empty() {
+        let methods = Separated::new(self.methods);
+        builder.header(Header::checked_new(ALLOW_METHODS, methods))?;
+      }
+
+      if !self.allow_headers.is_empty() {
+        let fields = self.allow_headers.iter().map(|field| field.as_str());
+        let fields = Separated::new(fields);
+        builder.header(Header::checked_new(ALLOW_HEADERS, fields))?;
+      }
+
+      if let Some(max_age) = self.max_age {
+        builder.header(Header::checked_new(MAX_AGE, max_age))?;
+      }
+    }
+
+    self.write_vary(builder)
+  }
+
+  /// Write out `Access-Control-Allow-Origin` and
+  /// `Access-Control-Allow-Credentials`, returning whether the origin
+  /// was allowed.
+  fn write_origin<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+    origin: Option<&str>,
+  ) -> Result<bool, InsufficientSpaceError> {
+    let origin = match origin {
+      Some(origin) if self.allows_origin(origin) => origin,
+      _ => return Ok(false),
+    };
+
+    if self.origins.is_none() && !self.credentials {
+      builder.header(Header::checked_new(ALLOW_ORIGIN, "*"))?;
+    } else {
+      // The origin is made up of URI characters since it was allowed.
+      builder.header(Header::checked_new(ALLOW_ORIGIN, origin))?;
+    }
+
+    if self.credentials {
+      builder.header(Header::checked_new(ALLOW_CREDENTIALS, "true"))?;
+    }
+
+    Ok(true)
+  }
+
+  /// Write out `Vary: Origin` if the response depends on the origin.
+  fn write_vary<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.origins.is_some() || self.credentials {
+      builder.header(Header::checked_new(VARY, "Origin"))?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Status, Version};
+
+  fn headers(cors: Cors, preflight: bool, origin: Option<&str>) -> String {
+    let status = Status::with_reason(200, "OK");
+    let mut builder =
+      HttpBuilder::response(vec![], Version::HTTP_1_1, status).unwrap();
+
+    if preflight {
+      cors.write_preflight(&mut builder, origin).unwrap();
+    } else {
+      cors.write_headers(&mut builder, origin).unwrap();
+    }
+
+    let output = String::from_utf8(builder.finish().unwrap()).unwrap();
+    output["HTTP/1.1 200 OK\r\n".len()..].to_owned()
+  }
+
+  #[test]
+  fn any_origin() {
+    const EXPOSE: [CheckedField; 2] =
+      [CheckedField::new("X-Total"), CheckedField::new("Link")];
+    let cors = Cors::any_origin().expose_headers(&EXPOSE);
+
+    assert_eq!(
+      headers(cors, false, Some("https://a.example")),
+      "Access-Control-Allow-Origin: *\r\n\
+       Access-Control-Expose-Headers: X-Total, Link\r\n\r\n"
+    );
+    assert_eq!(headers(cors, false, None), "\r\n");
+  }
+
+  #[test]
+  fn credentials_echo_origin() {
+    let cors = Cors::any_origin().credentials();
+
+    assert_eq!(
+      headers(cors, false, Some("https://a.example")),
+      "Access-Control-Allow-Origin: https://a.example\r\n\
+       Access-Control-Allow-Credentials: true\r\n\
+       Vary: Origin\r\n\r\n"
+    );
+    assert_eq!(
+      headers(cors, false, Some("https://a.example\r\nX-Injected: 1")),
+      "Vary: Origin\r\n\r\n"
+    );
+  }
+
+  #[test]
+  fn origin_list() {
+    let cors = Cors::origins(&["https://a.example", "https://b.example"])
+      .methods(&[Method::PUT]);
+
+    assert_eq!(
+      headers(cors, true, Some("https://B.example")),
+      "Access-Control-Allow-Origin: https://B.example\r\n\
+       Access-Control-Allow-Methods: PUT\r\n\
+       Vary: Origin\r\n\r\n"
+    );
+    assert_eq!(
+      headers(cors, true, Some("https://c.example")),
+      "Vary: Origin\r\n\r\n"
+    );
+    assert_eq!(headers(cors, true, None), "Vary: Origin\r\n\r\n");
+    assert!(Cors::try_origins(&["https://a.example\r\n"]).is_err());
+    assert!(Cors::try_origins(&[""]).is_err());
+  }
+}
@@ -0,0 +1,566 @@
+use core::fmt;
+use core::time::Duration;
+
+use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
+#[cfg(feature = "std")]
+use crate::{CheckedField, CheckedValue, Header};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The length of an IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`.
+const IMF_FIXDATE_LEN: usize = 29;
+
+/// The largest delta-seconds value that caches must be able to handle
+/// (RFC 9111 section 1.2.2). Larger values are sent as this instead.
+const MAX_DELTA_SECS: u64 = 2_147_483_648;
+
+/// Limit `secs` to the largest delta-seconds value that caches must be
+/// able to handle.
+const fn clamp_delta_secs(secs: u64) -> u64 {
+  if secs > MAX_DELTA_SECS {
+    MAX_DELTA_SECS
+  } else {
+    secs
+  }
+}
+
+/// The last second that can be represented with a 4-digit year:
+/// `Fri, 31 Dec 9999 23:59:59 GMT`.
+const MAX_SECS: u64 = 253_402_300_799;
+
+#[cfg(feature = "std")]
+const DATE: CheckedField = CheckedField::new("Date");
+
+const DAYS: [&[u8; 3]; 7] =
+  [b"Sun", b"Mon", b"Tue", b"Wed", b"Thu", b"Fri", b"Sat"];
+const MONTHS: [&[u8; 3]; 12] = [
+  b"Jan", b"Feb", b"Mar", b"Apr", b"May", b"Jun", b"Jul", b"Aug", b"Sep",
+  b"Oct", b"Nov", b"Dec",
+];
+
+/// Format `secs` seconds since the unix epoch as an IMF-fixdate.
+///
+/// Times past the end of year 9999 are clamped to the last second of
+/// that year.
+const fn imf_fixdate(secs: u64) -> [u8; IMF_FIXDATE_LEN] {
+  let secs = if secs > MAX_SECS { MAX_SECS } else { secs };
+
+  let days = secs / 86400;
+  let rem = secs % 86400;
+  let (hour, min, sec) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+  // 1970-01-01 was a Thursday.
+  let weekday = ((days + 4) % 7) as usize;
+
+  // Convert days since the epoch to a civil date. See
+  // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+  let z = days + 719_468;
+  let era = z / 146_097;
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  let mut out = *b"Thu, 01 Jan 1970 00:00:00 GMT";
+
+  let name = DAYS[weekday];
+  out[0] = name[0];
+  out[1] = name[1];
+  out[2] = name[2];
+
+  out[5] = b'0' + (day / 10) as u8;
+  out[6] = b'0' + (day % 10) as u8;
+
+  let name = MONTHS[(month - 1) as usize];
+  out[8] = name[0];
+  out[9] = name[1];
+  out[10] = name[2];
+
+  out[12] = b'0' + (year / 1000) as u8;
+  out[13] = b'0' + (year / 100 % 10) as u8;
+  out[14] = b'0' + (year / 10 % 10) as u8;
+  out[15] = b'0' + (year % 10) as u8;
+
+  out[17] = b'0' + (hour / 10) as u8;
+  out[18] = b'0' + (hour % 10) as u8;
+  out[20] = b'0' + (min / 10) as u8;
+  out[21] = b'0' + (min % 10) as u8;
+  out[23] = b'0' + (sec / 10) as u8;
+  out[24] = b'0' + (sec % 10) as u8;
+
+  out
+}
+
+/// A point in time that is written out as an IMF-fixdate such as
+/// `Sun, 06 Nov 1994 08:49:37 GMT`.
+///
+/// This is the format required for the `Date`, `Expires`, and
+/// `Last-Modified` headers. Formatting doesn't allocate or need `std` so
+/// it can be used on targets that only have some other source of the
+/// current time. Times past the end of year 9999 are written out as the
+/// last second of that year.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// let modified = HttpDate::from_unix_secs(784_111_777);
+/// builder.header(Header::new("Last-Modified", modified))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Last-Modified: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HttpDate {
+  secs: u64,
+}
+
+impl HttpDate {
+  /// Create a date from the number of seconds since the unix epoch.
+  pub const fn from_unix_secs(secs: u64) -> Self {
+    Self { secs }
+  }
+
+  /// The number of seconds since the unix epoch.
+  pub const fn unix_secs(&self) -> u64 {
+    self.secs
+  }
+}
+
+impl HttpWriteable for HttpDate {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(&imf_fixdate(self.secs))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (IMF_FIXDATE_LEN, Some(IMF_FIXDATE_LEN))
+  }
+}
+
+/// Convert a `SystemTime` to the second it falls within.
+///
+/// Times before the unix epoch are written out as the epoch itself.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::time::{Duration, UNIX_EPOCH};
+/// let mtime = UNIX_EPOCH + Duration::from_millis(784_111_777_500);
+/// let header = Header::new("Last-Modified", HttpDate::from(mtime));
+///
+/// let mut buffer = Vec::new();
+/// header.write_to(&mut buffer).unwrap();
+/// assert_eq!(buffer, b"Last-Modified: Sun, 06 Nov 1994 08:49:37 GMT\r\n");
+/// ```
+#[cfg(feature = "std")]
+impl From<SystemTime> for HttpDate {
+  fn from(time: SystemTime) -> Self {
+    let secs = time
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or(0);
+
+    Self::from_unix_secs(secs)
+  }
+}
+
+/// A `Retry-After` header value, for `429 Too Many Requests` and
+/// `503 Service Unavailable` responses.
+///
+/// Delays are written out as a whole number of seconds. Any fraction of
+/// a second is rounded up so that clients never retry too early, and
+/// delays above 2147483648 (2^31) seconds are written out as 2147483648
+/// the same as [`DeltaSeconds`].
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// let delay = RetryAfter::from(Duration::from_millis(1500));
+/// Header::new("Retry-After", delay).write_to(&mut buffer)?;
+///
+/// let date = RetryAfter::from(HttpDate::from_unix_secs(784_111_777));
+/// Header::new("Retry-After", date).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Retry-After: 2\r\n\
+///   Retry-After: Sun, 06 Nov 1994 08:49:37 GMT\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RetryAfter {
+  /// Retry after waiting for this long.
+  Delay(Duration),
+  /// Retry after this point in time.
+  Date(HttpDate),
+}
+
+impl RetryAfter {
+  /// The delay in whole seconds, rounded up.
+  fn delay_secs(delay: Duration) -> u64 {
+    let round_up = if delay.subsec_nanos() != 0 { 1 } else { 0 };
+    clamp_delta_secs(delay.as_secs().saturating_add(round_up))
+  }
+}
+
+impl From<Duration> for RetryAfter {
+  fn from(delay: Duration) -> Self {
+    Self::Delay(delay)
+  }
+}
+
+impl From<HttpDate> for RetryAfter {
+  fn from(date: HttpDate) -> Self {
+    Self::Date(date)
+  }
+}
+
+impl HttpWriteable for RetryAfter {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    match *self {
+      Self::Delay(delay) => Self::delay_secs(delay).write_to(buffer),
+      Self::Date(date) => date.write_to(buffer),
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match *self {
+      Self::Delay(delay) => Self::delay_secs(delay).size_hint(),
+      Self::Date(date) => date.size_hint(),
+    }
+  }
+}
+
+/// An `Age` header value: the number of seconds since a cached response
+/// was generated or validated by the origin server.
+///
+/// The age is written out as a whole number of seconds, rounded down,
+/// and saturates at 2147483648 (2^31) as required by RFC 9111.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Header::new("Age", Age::new(Duration::from_millis(60_900)))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Age: 60\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Age {
+  secs: u64,
+}
+
+impl Age {
+  /// Create an `Age` value from the time that a response has been
+  /// cached for.
+  pub const fn new(age: Duration) -> Self {
+    Self {
+      secs: clamp_delta_secs(age.as_secs()),
+    }
+  }
+
+  /// The number of seconds that will be written out.
+  pub const fn secs(&self) -> u64 {
+    self.secs
+  }
+}
+
+impl From<Duration> for Age {
+  fn from(age: Duration) -> Self {
+    Self::new(age)
+  }
+}
+
+impl HttpWriteable for Age {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    self.secs.write_to(buffer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.secs.size_hint()
+  }
+}
+
+/// A duration written out as delta-seconds: a whole number of seconds,
+/// as used by `Retry-After`, `Access-Control-Max-Age`, the `max-age`
+/// cache directives, and the `timeout` parameter of `Keep-Alive`.
+///
+/// Any fraction of a second is dropped and values above 2147483648
+/// (2^31) are written out as 2147483648, as recommended by RFC 9111.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// let max_age = DeltaSeconds(Duration::from_secs(600));
+/// Header::new("Access-Control-Max-Age", max_age).write_to(&mut buffer)?;
+/// Header::new("Keep-Alive", format_args!("timeout={}", max_age))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Access-Control-Max-Age: 600\r\n\
+///   Keep-Alive: timeout=600\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DeltaSeconds(pub Duration);
+
+impl DeltaSeconds {
+  /// The number of seconds that will be written out.
+  pub const fn secs(&self) -> u64 {
+    clamp_delta_secs(self.0.as_secs())
+  }
+}
+
+impl From<Duration> for DeltaSeconds {
+  fn from(duration: Duration) -> Self {
+    Self(duration)
+  }
+}
+
+impl fmt::Display for DeltaSeconds {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.secs())
+  }
+}
+
+impl HttpWriteable for DeltaSeconds {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    self.secs().write_to(buffer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.secs().size_hint()
+  }
+}
+
+/// A `Date` header value that is only reformatted when the current
+/// second changes.
+///
+/// Servers are expected to send a `Date` header with every response.
+/// Formatting the current time for each one is wasted work when many
+/// responses are sent within the same second so `CachedDate` keeps the
+/// encoded value around until it goes stale.
+///
+/// `CachedDate` is meant to be owned by a single thread (e.g. within a
+/// `thread_local!` or a per-connection struct).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut date = CachedDate::new();
+///
+/// let mut builder = response(vec![], Version::HTTP_1_1, Status::OK)?;
+/// builder.header(date.header())?;
+/// let output = builder.finish()?;
+///
+/// assert!(output.starts_with(b"HTTP/1.1 200"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct CachedDate {
+  secs: u64,
+  value: [u8; IMF_FIXDATE_LEN],
+}
+
+#[cfg(feature = "std")]
+impl CachedDate {
+  /// Create a new `CachedDate` holding the current time.
+  pub fn new() -> Self {
+    let secs = now();
+
+    Self {
+      secs,
+      value: imf_fixdate(secs),
+    }
+  }
+
+  /// Reformat the date if the current time has moved on to a different
+  /// second since it was last formatted.
+  pub fn update(&mut self) {
+    let secs = now();
+
+    if secs != self.secs {
+      self.secs = secs;
+      self.value = imf_fixdate(secs);
+    }
+  }
+
+  /// Get the current IMF-fixdate without checking whether it is
+  /// stale.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.value
+  }
+
+  /// Update the date if needed and return it as a `Date` header.
+  pub fn header(&mut self) -> Header<'static, CheckedValue<'_>> {
+    self.update();
+
+    // An IMF-fixdate never contains CR or LF.
+    let value = unsafe { CheckedValue::new_unchecked(&self.value) };
+    Header::checked_new(DATE, value)
+  }
+}
+
+#[cfg(feature = "std")]
+impl Default for CachedDate {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(feature = "std")]
+fn now() -> u64 {
+  HttpDate::from(SystemTime::now()).unix_secs()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode;
+
+  macro_rules! imf_fixdate_tests {
+    {
+      $( $secs:expr => $expected:literal; )*
+    } => {
+      $(
+        assert_eq!(&imf_fixdate($secs), $expected);
+      )*
+    }
+  }
+
+  #[test]
+  fn imf_fixdate_known_values() {
+    imf_fixdate_tests! {
+      0           => b"Thu, 01 Jan 1970 00:00:00 GMT";
+      784_111_777 => b"Sun, 06 Nov 1994 08:49:37 GMT";
+      951_782_400 => b"Tue, 29 Feb 2000 00:00:00 GMT";
+      1_234_567_890 => b"Fri, 13 Feb 2009 23:31:30 GMT";
+      4_107_542_399 => b"Sun, 28 Feb 2100 23:59:59 GMT";
+      MAX_SECS    => b"Fri, 31 Dec 9999 23:59:59 GMT";
+      u64::MAX    => b"Fri, 31 Dec 9999 23:59:59 GMT";
+    }
+  }
+
+  #[test]
+  fn http_date() {
+    let date = HttpDate::from_unix_secs(1_234_567_890);
+    let mut buffer = Vec::new();
+    date.write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"Fri, 13 Feb 2009 23:31:30 GMT");
+    assert_eq!(date.size_hint(), (buffer.len(), Some(buffer.len())));
+  }
+
+  #[test]
+  fn http_date_from_system_time() {
+    let before = UNIX_EPOCH - Duration::from_secs(1);
+    let after = UNIX_EPOCH + Duration::from_millis(1_999);
+
+    assert_eq!(HttpDate::from(before).unix_secs(), 0);
+    assert_eq!(HttpDate::from(after).unix_secs(), 1);
+  }
+
+  #[test]
+  fn retry_after() {
+    let cases = [
+      (RetryAfter::from(Duration::from_secs(120)), &b"120"[..]),
+      (RetryAfter::from(Duration::from_nanos(1)), b"1"),
+      (
+        RetryAfter::from(Duration::from_secs(MAX_DELTA_SECS)),
+        b"2147483648",
+      ),
+      (
+        RetryAfter::from(Duration::new(MAX_DELTA_SECS - 1, 1)),
+        b"2147483648",
+      ),
+      (RetryAfter::from(Duration::new(u64::MAX, 1)), b"2147483648"),
+      (RetryAfter::from(Duration::MAX), b"2147483648"),
+      (
+        RetryAfter::from(HttpDate::from_unix_secs(0)),
+        b"Thu, 01 Jan 1970 00:00:00 GMT",
+      ),
+    ];
+
+    for (value, expected) in &cases {
+      assert_eq!(encode(value), *expected);
+    }
+  }
+
+  #[test]
+  fn age_saturates() {
+    assert_eq!(Age::new(Duration::from_millis(999)).secs(), 0);
+    assert_eq!(Age::new(Duration::from_secs(86_400)).secs(), 86_400);
+    assert_eq!(Age::new(Duration::from_secs(1 << 31)).secs(), 1 << 31);
+    assert_eq!(Age::new(Duration::from_secs(u64::MAX)).secs(), 1 << 31);
+
+    let mut buffer = Vec::new();
+    Age::new(Duration::MAX).write_to(&mut buffer).unwrap();
+    assert_eq!(buffer, b"2147483648");
+  }
+
+  #[test]
+  fn delta_seconds() {
+    let cases = [
+      (Duration::from_millis(1_999), &b"1"[..]),
+      (Duration::from_secs(0), b"0"),
+      (Duration::from_secs(86_400), b"86400"),
+      (Duration::MAX, b"2147483648"),
+    ];
+
+    for (duration, expected) in &cases {
+      let value = DeltaSeconds(*duration);
+
+      assert_eq!(encode(value), *expected);
+      assert_eq!(value.to_string().as_bytes(), *expected);
+    }
+  }
+}
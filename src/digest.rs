@@ -0,0 +1,141 @@
+use crate::structured::{byte_sequence_len, is_key, write_byte_sequence};
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError,
+};
+
+/// A single entry of a `Content-Digest` or `Repr-Digest` header
+/// (RFC 9530).
+///
+/// The digest is computed by the caller and written out as a structured
+/// field byte sequence, e.g. `sha-256=:<base64>:`. The base64 encoding is
+/// done directly into the output buffer. Several digests can be written
+/// out by wrapping them in [`Separated`](crate::Separated).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let digest = [0x6f; 32];
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Content-Digest", ContentDigest::sha256(&digest))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Content-Digest: sha-256=:b29vb29vb29vb29vb29vb29vb29vb29vb29vb29vb28=:\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ContentDigest<'data> {
+  algorithm: &'data str,
+  digest: &'data [u8],
+}
+
+impl<'data> ContentDigest<'data> {
+  /// Create a digest entry for a registered algorithm such as `sha-256`
+  /// or `sha-512`.
+  ///
+  /// # Errors
+  /// Returns an error if `algorithm` is not a valid structured field key.
+  pub const fn try_new(
+    algorithm: &'data str,
+    digest: &'data [u8],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_key(algorithm) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { algorithm, digest })
+  }
+
+  /// Create a digest entry for a registered algorithm such as `sha-256`
+  /// or `sha-512`.
+  ///
+  /// # Panics
+  /// Panics if `algorithm` is not a valid structured field key.
+  pub const fn new(algorithm: &'data str, digest: &'data [u8]) -> Self {
+    match Self::try_new(algorithm, digest) {
+      Ok(digest) => digest,
+      Err(_) => const_panic!("Invalid digest algorithm"),
+    }
+  }
+
+  /// Create a `sha-256` digest entry.
+  pub const fn sha256(digest: &'data [u8; 32]) -> Self {
+    Self {
+      algorithm: "sha-256",
+      digest,
+    }
+  }
+
+  /// Create a `sha-512` digest entry.
+  pub const fn sha512(digest: &'data [u8; 64]) -> Self {
+    Self {
+      algorithm: "sha-512",
+      digest,
+    }
+  }
+
+  /// The algorithm identifier.
+  pub const fn algorithm(&self) -> &'data str {
+    self.algorithm
+  }
+
+  /// The raw digest bytes.
+  pub const fn digest(&self) -> &'data [u8] {
+    self.digest
+  }
+}
+
+impl HttpWriteable for ContentDigest<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.algorithm.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    write_byte_sequence(buffer, self.digest)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.algorithm.len() + 1 + byte_sequence_len(self.digest.len());
+    (len, Some(len))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Separated;
+
+  #[test]
+  fn digests() {
+    let digests = [
+      ContentDigest::new("sha-256", b"\xfb\xff"),
+      ContentDigest::sha512(&[0; 64]),
+    ];
+
+    let value = Separated::new(&digests);
+    let mut buffer = Vec::new();
+    value.write_to(&mut buffer).unwrap();
+
+    assert_eq!(
+      std::str::from_utf8(&buffer).unwrap(),
+      "sha-256=:+/8=:, sha-512=:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\
+       AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==:"
+    );
+    assert_eq!(value.size_hint(), (buffer.len(), Some(buffer.len())));
+  }
+
+  #[test]
+  fn invalid_algorithm() {
+    assert!(ContentDigest::try_new("SHA-256", b"").is_err());
+    assert!(ContentDigest::try_new("sha 256", b"").is_err());
+    assert!(ContentDigest::try_new("", b"").is_err());
+  }
+}
@@ -0,0 +1,228 @@
+use crate::quoted::{is_plain_text, write_ext_value};
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError, Quoted,
+};
+
+/// The type of a `Content-Disposition` header.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DispositionType {
+  /// Display the content within the page.
+  Inline,
+  /// Download the content instead of displaying it.
+  Attachment,
+  /// A part of a `multipart/form-data` body.
+  FormData,
+}
+
+impl DispositionType {
+  const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Inline => "inline",
+      Self::Attachment => "attachment",
+      Self::FormData => "form-data",
+    }
+  }
+}
+
+/// A `Content-Disposition` header value.
+///
+/// Any string can be used as the filename. Filenames that are printable
+/// ASCII are written out as a quoted `filename` parameter. Otherwise
+/// they are written out as an RFC 8187 UTF-8 `filename*` parameter
+/// along with an ASCII `filename` fallback, for clients that don't
+/// understand `filename*`, where every other character is replaced by
+/// `_`.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// let disposition = ContentDisposition::attachment().filename("résumé.pdf");
+/// builder.header(Header::new("Content-Disposition", disposition))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Content-Disposition: attachment; filename=\"r_sum_.pdf\"; \
+///   filename*=UTF-8''r%C3%A9sum%C3%A9.pdf\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ContentDisposition<'data> {
+  kind: DispositionType,
+  name: Option<&'data str>,
+  filename: Option<&'data str>,
+}
+
+impl<'data> ContentDisposition<'data> {
+  /// Create an `inline` disposition.
+  pub const fn inline() -> Self {
+    Self {
+      kind: DispositionType::Inline,
+      name: None,
+      filename: None,
+    }
+  }
+
+  /// Create an `attachment` disposition.
+  pub const fn attachment() -> Self {
+    Self {
+      kind: DispositionType::Attachment,
+      ..Self::inline()
+    }
+  }
+
+  /// Create a `form-data` disposition for the form field `name`.
+  ///
+  /// # Errors
+  /// Returns an error if `name` contains a control character other than
+  /// HTAB.
+  pub const fn try_form_data(
+    name: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if Quoted::try_new(name.as_bytes()).is_err() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      kind: DispositionType::FormData,
+      name: Some(name),
+      filename: None,
+    })
+  }
+
+  /// Create a `form-data` disposition for the form field `name`.
+  ///
+  /// # Panics
+  /// Panics if `name` contains a control character other than HTAB.
+  pub const fn form_data(name: &'data str) -> Self {
+    match Self::try_form_data(name) {
+      Ok(disposition) => disposition,
+      Err(_) => const_panic!("Form field name contained a control character"),
+    }
+  }
+
+  /// Set the filename that the content should be saved as.
+  pub const fn filename(self, filename: &'data str) -> Self {
+    Self {
+      filename: Some(filename),
+      ..self
+    }
+  }
+
+  /// The type of this disposition.
+  pub const fn kind(&self) -> DispositionType {
+    self.kind
+  }
+}
+
+impl HttpWriteable for ContentDisposition<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.kind.as_str().as_bytes())?;
+
+    if let Some(name) = self.name {
+      buffer.try_put_slice(b"; name=")?;
+      // The name was checked when this was created.
+      Quoted(name.as_bytes()).write_to(buffer)?;
+    }
+
+    let filename = match self.filename {
+      Some(filename) => filename,
+      None => return Ok(()),
+    };
+
+    buffer.try_put_slice(b"; filename=")?;
+
+    if is_plain_text(filename) {
+      return Quoted(filename.as_bytes()).write_to(buffer);
+    }
+
+    buffer.try_put_u8(b'"')?;
+    for c in filename.chars() {
+      match c {
+        '"' | '\\' => buffer.try_put_slice(&[b'\\', c as u8])?,
+        ' '..='~' => buffer.try_put_u8(c as u8)?,
+        _ => buffer.try_put_u8(b'_')?,
+      }
+    }
+    buffer.try_put_u8(b'"')?;
+
+    buffer.try_put_slice(b"; filename*=")?;
+    write_ext_value(buffer, filename)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut lower = self.kind.as_str().len();
+
+    if let Some(name) = self.name {
+      lower += "; name=".len() + name.len() + 2;
+    }
+
+    if let Some(filename) = self.filename {
+      lower += "; filename=".len() + filename.chars().count() + 2;
+    }
+
+    // Every character of the name or fallback filename might need to be
+    // escaped and every byte of the filename might be percent-encoded.
+    let name = self.name.map(str::len).unwrap_or(0);
+    let filename = self.filename.map(str::len).unwrap_or(0);
+    let upper = name
+      .checked_add(filename)
+      .and_then(|len| len.checked_mul(2))
+      .and_then(|len| filename.checked_mul(3)?.checked_add(len))
+      .and_then(|len| len.checked_add(lower))
+      .and_then(|len| len.checked_add("; filename*=UTF-8''".len()));
+
+    (lower, upper)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn plain() {
+    assert_eq!(encode_str(ContentDisposition::inline()), "inline");
+    assert_eq!(
+      encode_str(ContentDisposition::attachment().filename("a \"b\".txt")),
+      r#"attachment; filename="a \"b\".txt""#
+    );
+    assert_eq!(
+      encode_str(ContentDisposition::form_data("file").filename("x.png")),
+      r#"form-data; name="file"; filename="x.png""#
+    );
+  }
+
+  #[test]
+  fn dual_filename() {
+    assert_eq!(
+      encode_str(ContentDisposition::attachment().filename("€ rates\".txt")),
+      "attachment; filename=\"_ rates\\\".txt\"; \
+       filename*=UTF-8''%E2%82%AC%20rates%22.txt"
+    );
+    assert_eq!(
+      encode_str(ContentDisposition::inline().filename("a\r\nb")),
+      "inline; filename=\"a__b\"; filename*=UTF-8''a%0D%0Ab"
+    );
+  }
+
+  #[test]
+  fn invalid_name() {
+    assert!(ContentDisposition::try_form_data("a\r\nb").is_err());
+  }
+}
@@ -1,6 +1,7 @@
 use crate::{
-  InsufficientSpaceError, InvalidHeaderError, InvalidMethodError,
-  InvalidUriError,
+  HttpEncodeError, InsufficientSpaceError, InvalidHeaderError,
+  InvalidMessageError, InvalidMethodError, InvalidStatusError, InvalidUriError,
+  InvalidVersionError,
 };
 
 use core::fmt::{Display, Formatter, Result};
@@ -29,6 +30,60 @@ impl Display for InsufficientSpaceError {
   }
 }
 
+impl Display for InvalidMessageError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.write_str(self.0)
+  }
+}
+
+impl Display for InvalidStatusError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.write_str("Status code out of range or invalid reason phrase")
+  }
+}
+
+impl Display for InvalidVersionError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.write_str("Protocol name contained invalid character")
+  }
+}
+
+impl Display for HttpEncodeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    match self {
+      Self::InvalidMethod(e) => e.fmt(f),
+      Self::InvalidUri(e) => e.fmt(f),
+      Self::InvalidHeader(e) => e.fmt(f),
+      Self::InsufficientSpace(e) => e.fmt(f),
+      Self::InvalidMessage(e) => e.fmt(f),
+      Self::InvalidStatus(e) => e.fmt(f),
+      Self::InvalidVersion(e) => e.fmt(f),
+    }
+  }
+}
+
+macro_rules! impl_from_error {
+  ($( $variant:ident => $error:ident; )*) => {
+    $(
+      impl From<$error> for HttpEncodeError {
+        fn from(error: $error) -> Self {
+          Self::$variant(error)
+        }
+      }
+    )*
+  }
+}
+
+impl_from_error! {
+  InvalidMethod     => InvalidMethodError;
+  InvalidUri        => InvalidUriError;
+  InvalidHeader     => InvalidHeaderError;
+  InsufficientSpace => InsufficientSpaceError;
+  InvalidMessage    => InvalidMessageError;
+  InvalidStatus     => InvalidStatusError;
+  InvalidVersion    => InvalidVersionError;
+}
+
 #[cfg(feature = "std")]
 mod with_std {
   use super::*;
@@ -38,4 +93,21 @@ mod with_std {
   impl Error for InvalidMethodError {}
   impl Error for InvalidUriError {}
   impl Error for InsufficientSpaceError {}
+  impl Error for InvalidMessageError {}
+  impl Error for InvalidStatusError {}
+  impl Error for InvalidVersionError {}
+
+  impl Error for HttpEncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+      Some(match self {
+        Self::InvalidMethod(e) => e,
+        Self::InvalidUri(e) => e,
+        Self::InvalidHeader(e) => e,
+        Self::InsufficientSpace(e) => e,
+        Self::InvalidMessage(e) => e,
+        Self::InvalidStatus(e) => e,
+        Self::InvalidVersion(e) => e,
+      })
+    }
+  }
 }
@@ -0,0 +1,103 @@
+use crate::{
+  base64, BufMut, CheckedField, FallibleBufMut, Header, HttpBuilder,
+  InsufficientSpaceError, Method, Uri, Version, CRLF,
+};
+
+const CONNECTION: CheckedField = CheckedField::new("Connection");
+const UPGRADE: CheckedField = CheckedField::new("Upgrade");
+const HTTP2_SETTINGS: CheckedField = CheckedField::new("HTTP2-Settings");
+
+/// The client connection preface that starts every HTTP/2 connection, as
+/// described in RFC 7540 section 3.5.
+pub const H2_PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Write out the HTTP/2 client connection preface.
+///
+/// This is sent by clients that start HTTP/2 with prior knowledge and
+/// by clients once the server has accepted an
+/// [`h2c upgrade`](crate::HttpBuilder::h2c_upgrade). It must be followed
+/// by a `SETTINGS` frame.
+///
+/// # Errors
+/// Returns an error if there is not enough space in the buffer for the
+/// whole preface. Nothing is written in that case.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// write_h2_preface(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_h2_preface<B: BufMut>(
+  buffer: &mut B,
+) -> Result<(), InsufficientSpaceError> {
+  buffer.try_put_slice(H2_PREFACE)
+}
+
+impl<B: BufMut> HttpBuilder<B> {
+  /// Start an HTTP/1.1 request that asks the server to upgrade the
+  /// connection to HTTP/2 over cleartext (`h2c`) as described in
+  /// RFC 7540 section 3.2.
+  ///
+  /// `settings` is the payload of an HTTP/2 `SETTINGS` frame. It is
+  /// base64url-encoded into the `HTTP2-Settings` header.
+  ///
+  /// The `Host` header and any other headers still need to be written
+  /// afterwards.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// // SETTINGS_MAX_CONCURRENT_STREAMS = 100
+  /// let settings = [0x00, 0x03, 0x00, 0x00, 0x00, 0x64];
+  ///
+  /// let mut builder = HttpBuilder::h2c_upgrade(
+  ///     vec![],
+  ///     Method::GET,
+  ///     Uri::new(b"/"),
+  ///     &settings
+  /// )?;
+  /// builder.header(Header::new("Host", "example.com"))?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "GET / HTTP/1.1\r\n\
+  ///   Connection: Upgrade, HTTP2-Settings\r\n\
+  ///   Upgrade: h2c\r\n\
+  ///   HTTP2-Settings: AAMAAABk\r\n\
+  ///   Host: example.com\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn h2c_upgrade(
+    buffer: B,
+    method: Method,
+    request_target: Uri,
+    settings: &[u8],
+  ) -> Result<Self, InsufficientSpaceError> {
+    let mut builder =
+      Self::request(buffer, method, request_target, Version::HTTP_1_1)?;
+
+    builder
+      .header(Header::checked_new(CONNECTION, "Upgrade, HTTP2-Settings"))?;
+    builder.header(Header::checked_new(UPGRADE, "h2c"))?;
+
+    let buffer = &mut builder.buffer;
+    buffer.try_put_slice(HTTP2_SETTINGS.as_str().as_bytes())?;
+    buffer.try_put_slice(b": ")?;
+    base64::write_base64(buffer, settings, base64::URL_SAFE, false)?;
+    buffer.try_put_slice(&CRLF)?;
+    builder.headers += 1;
+
+    Ok(builder)
+  }
+}
@@ -1,11 +1,18 @@
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
 use crate::{
-  is_token, BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
-  InvalidHeaderError, CRLF,
+  is_token, is_token_fast, BufMut, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError, CRLF,
 };
 
 /// Field name wrapper allowing a field to be checked for validity at
 /// compile time.
 ///
+/// Field names are compared and hashed case-insensitively.
+///
 /// # Example
 /// ```
 /// # use httpencode::*;
@@ -14,12 +21,16 @@ use crate::{
 ///
 /// let header = Header::checked_new(CONTENT_TYPE, "text/plain");
 /// ```
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub struct CheckedField<'data>(&'data str);
+#[derive(Copy, Clone, Debug)]
+pub struct CheckedField<'data>(pub(crate) &'data str);
 
 impl<'data> CheckedField<'data> {
   /// Create a `CheckedField` from the provided field name.
   ///
+  /// If this method is not being used in a const context then
+  /// `CheckedField::try_from` should be preferred as it will likely be
+  /// faster.
+  ///
   /// # Errors
   /// Returns an error if `name` is not a valid HTTP header field name.
   /// See the docs for
@@ -51,6 +62,186 @@ impl<'data> CheckedField<'data> {
   pub const fn as_str(&self) -> &'data str {
     self.0
   }
+
+  /// Compare this field name with `other`, ignoring ASCII case.
+  ///
+  /// Field names in HTTP are case-insensitive so this is usually the
+  /// comparison that is wanted. Comparing a `CheckedField` with a string
+  /// using `==` does the same.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// const CONTENT_TYPE: CheckedField = CheckedField::new("Content-Type");
+  /// const IS_EQ: bool = CONTENT_TYPE.eq_ignore_case("content-type");
+  ///
+  /// assert!(IS_EQ);
+  /// assert!(CONTENT_TYPE == "CONTENT-TYPE");
+  /// ```
+  pub const fn eq_ignore_case(&self, other: &str) -> bool {
+    let (a, b) = (self.0.as_bytes(), other.as_bytes());
+    if a.len() != b.len() {
+      return false;
+    }
+
+    let mut idx = 0;
+    while idx < a.len() {
+      if !a[idx].eq_ignore_ascii_case(&b[idx]) {
+        return false;
+      }
+
+      idx += 1;
+    }
+
+    true
+  }
+
+  /// Get this field name converted to lowercase, as required by HTTP/2
+  /// and HTTP/3.
+  ///
+  /// The name is only copied if it contains uppercase characters. The
+  /// result is always a valid field name.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let field = CheckedField::new("Content-Type");
+  ///
+  /// assert_eq!(field.to_lowercase(), "content-type");
+  /// ```
+  #[cfg(feature = "std")]
+  pub fn to_lowercase(&self) -> Cow<'data, str> {
+    if !self.0.bytes().any(|b| b.is_ascii_uppercase()) {
+      return Cow::Borrowed(self.0);
+    }
+
+    Cow::Owned(self.0.to_ascii_lowercase())
+  }
+
+  /// Get this field name converted to the conventional `Title-Case`
+  /// used by HTTP/1.x, where the first character and every character
+  /// following a `-` is uppercase and all others are lowercase.
+  ///
+  /// The name is only copied if its casing needs to change. The result
+  /// is always a valid field name.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let field = CheckedField::new("x-forwarded-FOR");
+  ///
+  /// assert_eq!(field.to_canonical_case(), "X-Forwarded-For");
+  /// ```
+  #[cfg(feature = "std")]
+  pub fn to_canonical_case(&self) -> Cow<'data, str> {
+    fn canonical(prev: Option<u8>, b: u8) -> u8 {
+      match prev {
+        None | Some(b'-') => b.to_ascii_uppercase(),
+        Some(_) => b.to_ascii_lowercase(),
+      }
+    }
+
+    let bytes = self.0.as_bytes();
+    let is_canonical = bytes.iter().enumerate().all(|(idx, &b)| {
+      let prev = idx.checked_sub(1).map(|idx| bytes[idx]);
+      canonical(prev, b) == b
+    });
+
+    if is_canonical {
+      return Cow::Borrowed(self.0);
+    }
+
+    let mut prev = None;
+    let name = bytes
+      .iter()
+      .map(|&b| {
+        let c = canonical(prev, b);
+        prev = Some(b);
+        char::from(c)
+      })
+      .collect();
+
+    Cow::Owned(name)
+  }
+}
+
+/// Field names are compared case-insensitively.
+impl PartialEq for CheckedField<'_> {
+  fn eq(&self, other: &CheckedField) -> bool {
+    self.eq_ignore_case(other.0)
+  }
+}
+
+impl Eq for CheckedField<'_> {}
+
+/// Field names are hashed case-insensitively so that fields which compare
+/// equal also hash the same.
+impl Hash for CheckedField<'_> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    for byte in self.0.bytes() {
+      state.write_u8(byte.to_ascii_lowercase());
+    }
+
+    // Matches how str is hashed so that prefixes hash differently.
+    state.write_u8(0xff);
+  }
+}
+
+/// Field names are compared case-insensitively.
+impl PartialEq<str> for CheckedField<'_> {
+  fn eq(&self, other: &str) -> bool {
+    self.eq_ignore_case(other)
+  }
+}
+
+/// Field names are compared case-insensitively.
+impl PartialEq<&'_ str> for CheckedField<'_> {
+  fn eq(&self, other: &&str) -> bool {
+    self.eq_ignore_case(other)
+  }
+}
+
+/// Field names are compared case-insensitively.
+impl PartialEq<CheckedField<'_>> for str {
+  fn eq(&self, other: &CheckedField) -> bool {
+    other.eq_ignore_case(self)
+  }
+}
+
+/// Field names are compared case-insensitively.
+impl PartialEq<CheckedField<'_>> for &'_ str {
+  fn eq(&self, other: &CheckedField) -> bool {
+    other.eq_ignore_case(self)
+  }
+}
+
+impl<'data> TryFrom<&'data str> for CheckedField<'data> {
+  type Error = InvalidHeaderError;
+
+  /// Equivalent to [`CheckedField::try_new`](crate::CheckedField::try_new)
+  /// but faster since it doesn't need to be `const`.
+  fn try_from(name: &'data str) -> Result<Self, Self::Error> {
+    if !is_token_fast(name.as_bytes()) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(name))
+  }
+}
+
+impl<'data> TryFrom<&'data [u8]> for CheckedField<'data> {
+  type Error = InvalidHeaderError;
+
+  /// Create a `CheckedField` from a byte string.
+  ///
+  /// A valid field name is always ASCII so any byte string that is not
+  /// valid UTF-8 is rejected.
+  fn try_from(name: &'data [u8]) -> Result<Self, Self::Error> {
+    match core::str::from_utf8(name) {
+      Ok(name) => Self::try_from(name),
+      Err(_) => Err(InvalidHeaderError(())),
+    }
+  }
 }
 
 /// Pre-checked HTTP field value.
@@ -88,9 +279,10 @@ impl<'data> CheckedValue<'data> {
   ///
   /// # Errors
   /// Returns an error if `value` contains a CRLF not immediately
-  /// followed by linear whitespace (`' '` or `'\t'`).
+  /// followed by linear whitespace (`' '` or `'\t'`) or if it contains
+  /// a bare LF that is not part of a CRLF.
   pub const fn try_new(value: &'data [u8]) -> Result<Self, InvalidHeaderError> {
-    if !Self::check_valid_const(value) {
+    if !Self::check_valid_const(value, true) {
       return Err(InvalidHeaderError(()));
     }
 
@@ -101,7 +293,8 @@ impl<'data> CheckedValue<'data> {
   ///
   /// # Panics
   /// Panics if `value` contains a CRLF not immediately followed by
-  /// linear whitespace (`' '` or `'\t'`).
+  /// linear whitespace (`' '` or `'\t'`) or if it contains a bare LF
+  /// that is not part of a CRLF.
   pub const fn new(value: &'data [u8]) -> Self {
     match Self::try_new(value) {
       Ok(value) => value,
@@ -109,6 +302,48 @@ impl<'data> CheckedValue<'data> {
     }
   }
 
+  /// Create a `CheckedValue` from the provided byte slice, also rejecting
+  /// any lone CR that is not part of a CRLF.
+  ///
+  /// Some lenient receivers treat a lone CR as a line terminator, so this
+  /// should be preferred when the value comes from an untrusted source.
+  ///
+  /// # Errors
+  /// Returns an error if `value` contains a CRLF not immediately
+  /// followed by linear whitespace (`' '` or `'\t'`) or if it contains
+  /// a CR or LF that is not part of a CRLF.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(CheckedValue::try_new(b"lone\rcr").is_ok());
+  /// assert!(CheckedValue::try_new_strict(b"lone\rcr").is_err());
+  /// assert!(CheckedValue::try_new_strict(b"folded\r\n value").is_ok());
+  /// ```
+  pub const fn try_new_strict(
+    value: &'data [u8],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !Self::check_valid_const(value, false) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(value))
+  }
+
+  /// Create a `CheckedValue` from the provided byte slice, also rejecting
+  /// any lone CR that is not part of a CRLF.
+  ///
+  /// # Panics
+  /// Panics if `value` contains a CRLF not immediately followed by
+  /// linear whitespace (`' '` or `'\t'`) or if it contains a CR or LF
+  /// that is not part of a CRLF.
+  pub const fn new_strict(value: &'data [u8]) -> Self {
+    match Self::try_new_strict(value) {
+      Ok(value) => value,
+      Err(_) => const_panic!("Header contained invalid character"),
+    }
+  }
+
   /// Create a `CheckedValue` without checking to see that `value` meets
   /// the requirements for a valid HTTP header value.
   ///
@@ -126,52 +361,167 @@ impl<'data> CheckedValue<'data> {
     self.0
   }
 
-  const fn check_valid_const(value: &[u8]) -> bool {
-    let mut prev = 0;
-    while let Some(idx) = Self::memchr_const(b'\r', value, prev) {
-      prev = match value.len() - idx {
-        0 | 1 => break,
-        2 => match value[1] {
-          b'\n' => return false,
-          _ => break,
-        },
-        _ => match (value[1], value[2]) {
-          (b'\n', b' ') | (b'\n', b'\t') => 3,
-          (b'\n', _) => return false,
-          _ => 1,
-        },
-      } + idx;
+  const fn check_valid_const(value: &[u8], allow_lone_cr: bool) -> bool {
+    let mut idx = 0;
+
+    while idx < value.len() {
+      match value[idx] {
+        b'\r' if idx + 1 < value.len() && value[idx + 1] == b'\n' => {
+          // A CRLF is only allowed as part of an obs-fold.
+          if idx + 2 >= value.len() {
+            return false;
+          }
+
+          match value[idx + 2] {
+            b' ' | b'\t' => idx += 2,
+            _ => return false,
+          }
+        }
+        b'\r' if !allow_lone_cr => return false,
+        b'\n' => return false,
+        _ => (),
+      }
+
+      idx += 1;
     }
 
     true
   }
+}
 
-  const fn memchr_const(
-    needle: u8,
-    haystack: &[u8],
-    start: usize,
-  ) -> Option<usize> {
-    let mut idx = start;
+impl HttpWriteable for CheckedValue<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.0)
+  }
 
-    while idx < haystack.len() {
-      if haystack[idx] == needle {
-        return Some(idx);
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.0.len(), Some(self.0.len()))
+  }
+}
+
+/// HTTP field value that is guaranteed not to contain any CR, LF, or NUL
+/// characters.
+///
+/// Unlike [`CheckedValue`](crate::CheckedValue) or plain byte strings
+/// this never relies on obs-fold (a CRLF followed by whitespace) to keep
+/// the message valid. RFC 9110 deprecates line folding and many receivers
+/// reject it, so this type refuses such values outright instead.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Server", StrictValue::try_from("httpencode")?))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Server: httpencode\r\n\
+///   \r\n"
+/// );
+///
+/// assert!(StrictValue::try_from("folded\r\n value").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StrictValue<'data>(&'data [u8]);
+
+impl<'data> StrictValue<'data> {
+  /// Create a `StrictValue` from the provided byte slice.
+  ///
+  /// If this method is not being used in a const context then
+  /// `StrictValue::try_from` should be preferred as it will likely be
+  /// faster.
+  ///
+  /// # Errors
+  /// Returns an error if `value` contains a CR, LF, or NUL character.
+  pub const fn try_new(value: &'data [u8]) -> Result<Self, InvalidHeaderError> {
+    let mut idx = 0;
+    while idx < value.len() {
+      if let b'\r' | b'\n' | b'\0' = value[idx] {
+        return Err(InvalidHeaderError(()));
       }
 
       idx += 1;
     }
 
-    None
+    Ok(Self(value))
+  }
+
+  /// Create a `StrictValue` from the provided byte slice.
+  ///
+  /// # Panics
+  /// Panics if `value` contains a CR, LF, or NUL character.
+  pub const fn new(value: &'data [u8]) -> Self {
+    match Self::try_new(value) {
+      Ok(value) => value,
+      Err(_) => const_panic!("Header contained invalid character"),
+    }
+  }
+
+  /// Create a `StrictValue` without checking that `value` contains no CR,
+  /// LF, or NUL characters.
+  ///
+  /// # Safety
+  /// Breaking the requirements of this function won't cause memory
+  /// unsafety. However, any HTTP headers emitted using an invalid value
+  /// may not be syntactically valid.
+  pub const unsafe fn new_unchecked(value: &'data [u8]) -> Self {
+    Self(value)
+  }
+
+  /// Access the underlying byte slice of of this value.
+  pub const fn as_bytes(&self) -> &'data [u8] {
+    self.0
   }
 }
 
-impl HttpWriteable for CheckedValue<'_> {
+impl<'data> TryFrom<&'data [u8]> for StrictValue<'data> {
+  type Error = InvalidHeaderError;
+
+  /// Equivalent to [`StrictValue::try_new`](crate::StrictValue::try_new)
+  /// but faster since it doesn't need to be `const`.
+  fn try_from(value: &'data [u8]) -> Result<Self, Self::Error> {
+    if memchr::memchr3(b'\r', b'\n', b'\0', value).is_some() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(value))
+  }
+}
+
+impl<'data> TryFrom<&'data str> for StrictValue<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(value: &'data str) -> Result<Self, Self::Error> {
+    Self::try_from(value.as_bytes())
+  }
+}
+
+impl HttpWriteable for StrictValue<'_> {
   fn write_to<B: BufMut>(
     &self,
     buffer: &mut B,
   ) -> Result<(), InsufficientSpaceError> {
     buffer.try_put_slice(self.0)
   }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.0.len(), Some(self.0.len()))
+  }
 }
 
 /// A key-value pair representing an HTTP header.
@@ -185,7 +535,7 @@ impl HttpWriteable for CheckedValue<'_> {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Header<'data, V> {
   pub(crate) field: CheckedField<'data>,
   pub(crate) value: V,
@@ -212,6 +562,67 @@ impl<'data, V> Header<'data, V> {
   pub const fn checked_new(field: CheckedField<'data>, value: V) -> Self {
     Self { field, value }
   }
+
+  /// The field name of this header.
+  pub const fn field(&self) -> CheckedField<'data> {
+    self.field
+  }
+
+  /// The value of this header.
+  pub const fn value(&self) -> &V {
+    &self.value
+  }
+}
+
+/// Equivalent to [`Header::new`](crate::Header::new).
+///
+/// This is not `TryFrom` since that is already implemented in terms of
+/// this `From` impl. Use [`Header::try_new`](crate::Header::try_new) if
+/// the field name might be invalid.
+///
+/// # Panics
+/// Panics if `field` is not a valid HTTP header field name.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(("X-Foo", "bar"))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(output, b"HTTP/1.1 200 OK\r\nX-Foo: bar\r\n\r\n");
+/// # Ok(())
+/// # }
+/// ```
+impl<'data, V> From<(&'data str, V)> for Header<'data, V> {
+  fn from((field, value): (&'data str, V)) -> Self {
+    match CheckedField::try_from(field) {
+      Ok(field) => Self::checked_new(field, value),
+      Err(_) => panic!("Header field contained invalid character"),
+    }
+  }
+}
+
+/// Equivalent to [`Header::checked_new`](crate::Header::checked_new).
+impl<'data, V> From<(CheckedField<'data>, V)> for Header<'data, V> {
+  fn from((field, value): (CheckedField<'data>, V)) -> Self {
+    Self::checked_new(field, value)
+  }
+}
+
+impl<'data, V> TryFrom<(&'data [u8], V)> for Header<'data, V> {
+  type Error = InvalidHeaderError;
+
+  /// Create a header from a field name given as bytes, validating it at
+  /// runtime.
+  fn try_from((field, value): (&'data [u8], V)) -> Result<Self, Self::Error> {
+    Ok(Self::checked_new(CheckedField::try_from(field)?, value))
+  }
 }
 
 impl<'data, V: HttpWriteable> Header<'data, V> {
@@ -226,23 +637,59 @@ impl<'data, V: HttpWriteable> Header<'data, V> {
     field: &'data str,
     value: V,
   ) -> Result<Self, InvalidHeaderError> {
-    let field = match CheckedField::try_new(field) {
-      Ok(field) => field,
-      Err(e) => return Err(e),
-    };
+    let field = CheckedField::try_from(field)?;
 
     Ok(Self { field, value })
   }
+}
 
-  pub(crate) fn write_to<B: BufMut>(
+/// Writes out a complete header line, including the trailing CRLF.
+///
+/// This allows a single header to be encoded outside of any builder, e.g.
+/// when assembling a trailer section or a MIME part by hand.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Header::new("Content-Type", "text/plain").write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Content-Type: text/plain\r\n");
+/// # Ok(())
+/// # }
+/// ```
+impl<V: HttpWriteable> HttpWriteable for Header<'_, V> {
+  fn write_to<B: BufMut>(
     &self,
     buf: &mut B,
   ) -> Result<(), InsufficientSpaceError> {
-    buf.try_put_slice(self.field.as_str().as_bytes())?;
-    buf.try_put_slice(b": ")?;
+    let field = self.field.as_str().as_bytes();
+    let (lower, _) = self.value.size_hint();
+
+    // Check that the whole header (field + ": " + value + CRLF) could fit
+    // up front. This also means that there's no need to check the field
+    // name separately.
+    if buf.remaining_mut() < (field.len() + 4).saturating_add(lower) {
+      return Err(InsufficientSpaceError::default());
+    }
+
+    buf.put_slice(field);
+    buf.put_slice(b": ");
     self.value.write_to(buf)?;
     buf.try_put_slice(&CRLF)
   }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let fixed = self.field.as_str().len() + 4;
+    let (lower, upper) = self.value.size_hint();
+
+    (
+      fixed.saturating_add(lower),
+      upper.and_then(|upper| upper.checked_add(fixed)),
+    )
+  }
 }
 
 #[cfg(test)]
@@ -254,6 +701,128 @@ mod tests {
     let _ = CheckedField::new("Content-Type");
   }
 
+  #[test]
+  fn checked_field_try_from() {
+    assert!(CheckedField::try_from("Content-Type").is_ok());
+    assert!(CheckedField::try_from(&b"Content-Type"[..]).is_ok());
+
+    assert!(CheckedField::try_from("Spaced Header").is_err());
+    assert!(CheckedField::try_from(&b"\xC3\xA9"[..]).is_err());
+  }
+
+  #[test]
+  fn header_eq_and_standalone_encoding() {
+    let header = Header::new("X-Test", CheckedValue::new(b"value"));
+
+    assert_eq!(header, Header::new("X-Test", CheckedValue::new(b"value")));
+    assert_ne!(header, Header::new("X-Test", CheckedValue::new(b"other")));
+
+    let mut buffer = Vec::new();
+    header.write_to(&mut buffer).unwrap();
+    assert_eq!(buffer, b"X-Test: value\r\n");
+    assert_eq!(header.size_hint(), (buffer.len(), Some(buffer.len())));
+
+    let mut small = &mut [0u8; 8][..];
+    assert!(header.write_to(&mut small).is_err());
+  }
+
+  #[test]
+  fn incorrect_size_hint() {
+    struct Hinted(&'static str, (usize, Option<usize>));
+
+    impl HttpWriteable for Hinted {
+      fn write_to<B: BufMut>(
+        &self,
+        buf: &mut B,
+      ) -> Result<(), InsufficientSpaceError> {
+        self.0.write_to(buf)
+      }
+
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        self.1
+      }
+    }
+
+    // An upper bound that is too low is ignored.
+    let header = Header::new("X-Test", Hinted("value", (0, Some(0))));
+    let mut small = &mut [0u8; 12][..];
+    assert!(header.write_to(&mut small).is_err());
+
+    // A lower bound that is too high fails early.
+    let header = Header::new("X-Test", Hinted("value", (64, None)));
+    let mut buffer = &mut [0u8; 15][..];
+    assert!(header.write_to(&mut buffer).is_err());
+  }
+
+  #[test]
+  fn header_from_tuple() {
+    let field = CheckedField::new("X-Foo");
+
+    assert_eq!(Header::from(("X-Foo", 1)), Header::checked_new(field, 1));
+    assert_eq!(Header::from((field, 1)), Header::checked_new(field, 1));
+    assert_eq!(
+      Header::try_from((&b"X-Foo"[..], 1)).unwrap(),
+      Header::checked_new(field, 1)
+    );
+    assert!(Header::try_from((&b"X Foo"[..], 1)).is_err());
+  }
+
+  #[test]
+  #[should_panic]
+  fn header_from_tuple_invalid() {
+    let _ = Header::from(("X Foo", 1));
+  }
+
+  #[test]
+  fn checked_field_eq_ignore_case() {
+    let field = CheckedField::new("Content-Type");
+
+    assert!(field.eq_ignore_case("content-type"));
+    assert!(field.eq_ignore_case("CONTENT-TYPE"));
+    assert!(!field.eq_ignore_case("Content-Typo"));
+    assert!(!field.eq_ignore_case("Content-Type "));
+
+    assert!(field == "content-TYPE");
+    assert!("content-type" == field);
+    assert!(field != *"Content-Length");
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn checked_field_eq_matches_hash() {
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash(field: CheckedField) -> u64 {
+      let mut hasher = DefaultHasher::new();
+      field.hash(&mut hasher);
+      hasher.finish()
+    }
+
+    let field = CheckedField::new("Content-Type");
+    let upper = CheckedField::new("CONTENT-TYPE");
+
+    assert_eq!(field, upper);
+    assert_eq!(hash(field), hash(upper));
+    assert_ne!(field, CheckedField::new("Content-Length"));
+    assert_ne!(hash(CheckedField::new("ab")), hash(CheckedField::new("a")));
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn checked_field_casing() {
+    let lower = CheckedField::new("etag");
+    let canonical = CheckedField::new("WWW-Authenticate");
+
+    assert!(matches!(lower.to_lowercase(), Cow::Borrowed("etag")));
+    assert_eq!(lower.to_canonical_case(), "Etag");
+    assert_eq!(canonical.to_lowercase(), "www-authenticate");
+    assert_eq!(canonical.to_canonical_case(), "Www-Authenticate");
+
+    let borrowed = CheckedField::new("Content-Length");
+    assert!(matches!(borrowed.to_canonical_case(), Cow::Borrowed(_)));
+    assert_eq!(CheckedField::new("a--b").to_canonical_case(), "A--B");
+  }
+
   #[test]
   #[should_panic]
   fn checked_field_new_invalid() {
@@ -315,12 +884,57 @@ mod tests {
     assert!(CheckedValue::try_new(b"\r\n\r\n").is_err());
   }
 
+  #[test]
+  fn checked_value_bare_lf() {
+    assert!(CheckedValue::try_new(b"\n").is_err());
+    assert!(CheckedValue::try_new(b"bare\nlf").is_err());
+    assert!(CheckedValue::try_new(b"a\r\n b\nc").is_err());
+    assert!(CheckedValue::try_new(b"\r\n\n").is_err());
+
+    // This was previously accepted since only the start of the value was
+    // being checked after the first CR.
+    assert!(CheckedValue::try_new(b"ab\r\nc").is_err());
+  }
+
+  #[test]
+  fn checked_value_strict() {
+    assert!(CheckedValue::try_new_strict(b"\r").is_err());
+    assert!(CheckedValue::try_new_strict(b"\r a").is_err());
+    assert!(CheckedValue::try_new_strict(b"\n").is_err());
+    assert!(CheckedValue::try_new_strict(b"\r\n\r\n").is_err());
+
+    assert!(CheckedValue::try_new_strict(b"plain").is_ok());
+    assert!(CheckedValue::try_new_strict(b"\r\n\t").is_ok());
+  }
+
   #[test]
   #[should_panic]
   fn checked_value_invalid_crlf() {
     let _ = CheckedValue::new(b"\r\n");
   }
 
+  #[test]
+  fn strict_value() {
+    let valid: &[&[u8]] = &[b"", b"text/plain", b"tab\tseparated", b"\xff"];
+    let invalid: &[&[u8]] = &[b"\r\n ", b"bare\nlf", b"lone\rcr", b"\0"];
+
+    for &value in valid {
+      assert_eq!(StrictValue::try_new(value).unwrap().as_bytes(), value);
+      assert_eq!(StrictValue::try_from(value).unwrap().as_bytes(), value);
+    }
+
+    for &value in invalid {
+      assert!(StrictValue::try_new(value).is_err());
+      assert!(StrictValue::try_from(value).is_err());
+    }
+  }
+
+  #[test]
+  #[should_panic]
+  fn strict_value_invalid_panics() {
+    let _ = StrictValue::new(b"folded\r\n value");
+  }
+
   checked_value_valid! {
     contains_nul  => b"\0";
     empty         => b"";
@@ -0,0 +1,371 @@
+use crate::{CheckedField, CheckedValue, Header, HttpWriteable};
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct Entry {
+  field: String,
+  // The value exactly as it will be written out.
+  value: Vec<u8>,
+}
+
+impl Entry {
+  fn new<V: HttpWriteable>(header: Header<'_, V>) -> Self {
+    let mut value = Vec::with_capacity(header.value.size_hint().0);
+    // A Vec never runs out of space so this only fails if the value's
+    // formatting returned an error.
+    header
+      .value
+      .write_to(&mut value)
+      .expect("Header value could not be formatted");
+
+    Self {
+      field: header.field.as_str().to_owned(),
+      value,
+    }
+  }
+
+  fn header(&self) -> Header<'_, CheckedValue<'_>> {
+    // Both come from a valid header. The value is exactly what that
+    // header would have written out so it can be copied as-is.
+    let value = unsafe { CheckedValue::new_unchecked(&self.value) };
+
+    Header::checked_new(CheckedField(&self.field), value)
+  }
+}
+
+/// An ordered collection of HTTP headers that can be modified before they
+/// are written out.
+///
+/// [`HttpBuilder`](crate::HttpBuilder) writes headers out as soon as they
+/// are given to it. When headers need to be accumulated and changed by
+/// several layers of an application before the message is written, they
+/// can be collected in a `Headers` instead and then written out all at
+/// once using [`HttpBuilder::headers`](crate::HttpBuilder::headers).
+///
+/// Headers are kept in the order they were added. Values are encoded as
+/// soon as they are added so they do not need to outlive the collection.
+/// Field names are compared case-insensitively.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut headers = Headers::new();
+/// headers
+///   .append(Header::new("Content-Type", "text/plain"))
+///   .append(Header::new("X-Debug", 1))
+///   .append(Header::new("Content-Length", 5));
+///
+/// // Some middleware later decides to strip debug headers.
+/// headers.remove("x-debug");
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.headers(&headers)?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Content-Type: text/plain\r\n\
+///   Content-Length: 5\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Headers {
+  entries: Vec<Entry>,
+}
+
+impl Headers {
+  /// Create an empty collection of headers.
+  pub const fn new() -> Self {
+    Self {
+      entries: Vec::new(),
+    }
+  }
+
+  /// Add a header after all existing headers, even if there are already
+  /// headers with the same field name.
+  ///
+  /// # Panics
+  /// Panics if writing out the value of `header` fails, which can only
+  /// happen if a formatted value such as
+  /// [`DisplayValue`](crate::DisplayValue) returns an error.
+  pub fn append<'data, V, H>(&mut self, header: H) -> &mut Self
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    self.entries.push(Entry::new(header.into()));
+    self
+  }
+
+  /// Add a header at position `index`, shifting all headers after it
+  /// back by one.
+  ///
+  /// # Panics
+  /// Panics if `index` is greater than the number of headers or if
+  /// writing out the value of `header` fails, as with
+  /// [`append`](Headers::append).
+  pub fn insert<'data, V, H>(&mut self, index: usize, header: H) -> &mut Self
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    self.entries.insert(index, Entry::new(header.into()));
+    self
+  }
+
+  /// Replace every existing header with the same field name with
+  /// `header`.
+  ///
+  /// The new header takes the place of the first existing header with
+  /// the same field name, or is added after all other headers if there
+  /// is none. This allows a later layer of an application to override a
+  /// header that was set earlier.
+  ///
+  /// # Panics
+  /// Panics if writing out the value of `header` fails, as with
+  /// [`append`](Headers::append).
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let mut headers = Headers::new();
+  /// headers
+  ///   .set(Header::new("Content-Type", "text/plain"))
+  ///   .set(Header::new("Cache-Control", "no-cache"))
+  ///   .set(Header::new("content-type", "text/html"));
+  ///
+  /// let fields: Vec<_> = headers.iter().map(|h| h.field().as_str()).collect();
+  /// assert_eq!(fields, ["content-type", "Cache-Control"]);
+  /// ```
+  pub fn set<'data, V, H>(&mut self, header: H) -> &mut Self
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    let entry = Entry::new(header.into());
+    let field = &entry.field;
+    let matches = |other: &Entry| other.field.eq_ignore_ascii_case(field);
+
+    match self.entries.iter().position(matches) {
+      Some(index) => {
+        let mut rest = self.entries.split_off(index + 1);
+        rest.retain(|other| !matches(other));
+
+        self.entries[index] = entry;
+        self.entries.append(&mut rest);
+      }
+      None => self.entries.push(entry),
+    }
+
+    self
+  }
+
+  /// Remove every header with the given field name and return how many
+  /// were removed.
+  pub fn remove(&mut self, field: &str) -> usize {
+    let len = self.entries.len();
+    self
+      .entries
+      .retain(|entry| !entry.field.eq_ignore_ascii_case(field));
+    len - self.entries.len()
+  }
+
+  /// Get the first header with the given field name.
+  pub fn get(&self, field: &str) -> Option<Header<'_, CheckedValue<'_>>> {
+    self
+      .entries
+      .iter()
+      .find(|entry| entry.field.eq_ignore_ascii_case(field))
+      .map(Entry::header)
+  }
+
+  /// Get every header with the given field name, in order.
+  pub fn get_all<'a>(
+    &'a self,
+    field: &'a str,
+  ) -> impl Iterator<Item = Header<'a, CheckedValue<'a>>> + 'a {
+    self
+      .entries
+      .iter()
+      .filter(move |entry| entry.field.eq_ignore_ascii_case(field))
+      .map(Entry::header)
+  }
+
+  /// Whether there is at least one header with the given field name.
+  pub fn contains(&self, field: &str) -> bool {
+    self.get(field).is_some()
+  }
+
+  /// Iterate over all headers in the order they will be written out.
+  pub fn iter(&self) -> HeadersIter<'_> {
+    HeadersIter {
+      entries: self.entries.iter(),
+    }
+  }
+
+  /// The number of headers within this collection.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Whether this collection contains any headers.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Remove all headers.
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
+}
+
+impl<'a> IntoIterator for &'a Headers {
+  type Item = Header<'a, CheckedValue<'a>>;
+  type IntoIter = HeadersIter<'a>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'data, V> core::iter::FromIterator<Header<'data, V>> for Headers
+where
+  V: HttpWriteable,
+{
+  fn from_iter<I: IntoIterator<Item = Header<'data, V>>>(iter: I) -> Self {
+    let mut headers = Self::new();
+    for header in iter {
+      headers.append(header);
+    }
+    headers
+  }
+}
+
+/// An iterator over the headers within a [`Headers`](crate::Headers)
+/// collection.
+#[derive(Clone, Debug)]
+pub struct HeadersIter<'a> {
+  entries: core::slice::Iter<'a, Entry>,
+}
+
+impl<'a> Iterator for HeadersIter<'a> {
+  type Item = Header<'a, CheckedValue<'a>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.entries.next().map(Entry::header)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.entries.size_hint()
+  }
+}
+
+impl DoubleEndedIterator for HeadersIter<'_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.entries.next_back().map(Entry::header)
+  }
+}
+
+impl ExactSizeIterator for HeadersIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(headers: &Headers) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for header in headers {
+      header.write_to(&mut buffer).unwrap();
+    }
+    buffer
+  }
+
+  #[test]
+  fn preserves_order() {
+    let mut headers = Headers::new();
+    headers
+      .append(Header::new("B", "2"))
+      .append(Header::new("A", "1"))
+      .insert(1, Header::new("C", "3"))
+      .insert(0, Header::new("a", "0"));
+
+    assert_eq!(encode(&headers), b"a: 0\r\nB: 2\r\nC: 3\r\nA: 1\r\n");
+    assert_eq!(headers.len(), 4);
+  }
+
+  #[test]
+  fn lookup_is_case_insensitive() {
+    let headers: Headers = vec![
+      Header::new("Accept", "text/html"),
+      Header::new("X-Other", "x"),
+      Header::new("accept", "text/plain"),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      headers.get("ACCEPT").unwrap().value.as_bytes(),
+      b"text/html"
+    );
+    assert_eq!(headers.get_all("accept").count(), 2);
+    assert!(headers.contains("x-other"));
+    assert!(headers.get("Missing").is_none());
+  }
+
+  #[test]
+  fn remove_all() {
+    let mut headers = Headers::new();
+    headers
+      .append(Header::new("Via", "a"))
+      .append(Header::new("Host", "example.com"))
+      .append(Header::new("via", "b"));
+
+    assert_eq!(headers.remove("VIA"), 2);
+    assert_eq!(headers.remove("VIA"), 0);
+    assert_eq!(encode(&headers), b"Host: example.com\r\n");
+  }
+
+  #[test]
+  fn set_replaces_in_place() {
+    let mut headers = Headers::new();
+    headers
+      .append(Header::new("A", "1"))
+      .append(Header::new("B", "2"))
+      .append(Header::new("a", "3"))
+      .append(Header::new("C", "4"))
+      .set(Header::new("A", "5"))
+      .set(Header::new("D", "6"));
+
+    assert_eq!(encode(&headers), b"A: 5\r\nB: 2\r\nC: 4\r\nD: 6\r\n");
+  }
+
+  #[test]
+  fn values_are_encoded_on_insertion() {
+    let mut headers = Headers::new();
+    headers.append(Header::new("X-Folded", "a\r\nb"));
+
+    assert_eq!(encode(&headers), b"X-Folded: a\r\n\tb\r\n");
+  }
+
+  #[test]
+  #[should_panic]
+  fn append_formatting_error() {
+    struct Failing;
+
+    impl core::fmt::Display for Failing {
+      fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+        Err(core::fmt::Error)
+      }
+    }
+
+    Headers::new().append(Header::new("X-Fail", crate::DisplayValue(Failing)));
+  }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+
+use crate::{BufMut, HttpDate, HttpWriteable, InsufficientSpaceError};
+
+impl From<DateTime<Utc>> for HttpDate {
+  /// Convert a `DateTime<Utc>` to the second it falls within.
+  ///
+  /// Times before the unix epoch are written out as the epoch itself.
+  fn from(time: DateTime<Utc>) -> Self {
+    let secs = time.timestamp().max(0) as u64;
+    HttpDate::from_unix_secs(secs)
+  }
+}
+
+impl HttpWriteable for DateTime<Utc> {
+  /// Write out this time as an IMF-fixdate, as used within the `Date`,
+  /// `Expires`, and `Last-Modified` headers.
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    HttpDate::from(*self).write_to(buffer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    HttpDate::from(*self).size_hint()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn imf_fixdate() {
+    let time = Utc.timestamp_opt(784_111_777, 500_000_000).unwrap();
+    let mut buffer = Vec::new();
+    time.write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"Sun, 06 Nov 1994 08:49:37 GMT");
+  }
+
+  #[test]
+  fn before_epoch() {
+    let time = Utc.timestamp_opt(-1, 0).unwrap();
+    assert_eq!(HttpDate::from(time), HttpDate::from_unix_secs(0));
+  }
+}
@@ -0,0 +1,51 @@
+use ::cookie::Cookie;
+
+use crate::{BufMut, HttpWriteable, InsufficientSpaceError};
+
+impl HttpWriteable for Cookie<'_> {
+  /// Write out this cookie along with all of its attributes, as used
+  /// within a `Set-Cookie` header.
+  ///
+  /// The `cookie` crate doesn't validate cookie names or values so any
+  /// line breaks within them are folded the same way as for `&str`.
+  /// Use [`SetCookie`](crate::SetCookie) to have them rejected instead.
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    format_args!("{}", self).write_to(buffer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ::cookie::SameSite;
+
+  fn encode(cookie: &Cookie) -> String {
+    let mut buffer = Vec::new();
+    cookie.write_to(&mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+  }
+
+  #[test]
+  fn with_attributes() {
+    let cookie = Cookie::build(("session", "abc123"))
+      .path("/")
+      .secure(true)
+      .http_only(true)
+      .same_site(SameSite::Lax)
+      .build();
+
+    assert_eq!(
+      encode(&cookie),
+      "session=abc123; HttpOnly; SameSite=Lax; Secure; Path=/"
+    );
+  }
+
+  #[test]
+  fn line_breaks_are_folded() {
+    let cookie = Cookie::new("a", "b\r\nX-Injected: 1");
+    assert_eq!(encode(&cookie), "a=b\r\n\tX-Injected: 1");
+  }
+}
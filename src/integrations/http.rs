@@ -0,0 +1,112 @@
+use core::convert::TryFrom;
+
+use http::uri::PathAndQuery;
+
+use crate::{InvalidUriError, Method, Status, Uri};
+
+impl<'a> TryFrom<&'a PathAndQuery> for Uri<'a> {
+  type Error = InvalidUriError;
+
+  /// Use `path` as an origin-form (or asterisk-form) request target.
+  fn try_from(path: &'a PathAndQuery) -> Result<Self, Self::Error> {
+    Uri::try_new(path.as_str().as_bytes())
+  }
+}
+
+impl<'a> TryFrom<&'a http::Uri> for Uri<'a> {
+  type Error = InvalidUriError;
+
+  /// Use the request target stored within `uri` without copying it.
+  ///
+  /// `http::Uri` doesn't store the full URI contiguously so this uses the
+  /// path and query (origin-form) if present or the authority on its own
+  /// (authority-form) otherwise. The scheme and authority of URIs that
+  /// have a path are not included.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # use std::convert::TryFrom;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let uri: http::Uri = "https://example.com/index.html?q=1".parse()?;
+  /// assert_eq!(Uri::try_from(&uri)?.as_bytes(), b"/index.html?q=1");
+  ///
+  /// let uri: http::Uri = "example.com:443".parse()?;
+  /// assert_eq!(Uri::try_from(&uri)?.as_bytes(), b"example.com:443");
+  /// # Ok(())
+  /// # }
+  /// ```
+  fn try_from(uri: &'a http::Uri) -> Result<Self, Self::Error> {
+    if let Some(path) = uri.path_and_query() {
+      return Uri::try_from(path);
+    }
+
+    match uri.authority() {
+      Some(authority) => Uri::try_new(authority.as_str().as_bytes()),
+      None => Err(InvalidUriError(())),
+    }
+  }
+}
+
+impl<'a> From<&'a http::Method> for Method<'a> {
+  fn from(method: &'a http::Method) -> Self {
+    // http::Method only allows token characters so this can only fail if
+    // the two crates disagree on what a token is.
+    Method::try_new(method.as_str()).expect("http::Method was not a token")
+  }
+}
+
+impl From<http::StatusCode> for Status<'static> {
+  /// Convert a status code using its canonical reason phrase.
+  ///
+  /// If the `no-reason-phrase` feature is enabled then the reason phrase
+  /// is left blank, the same as [`Status::new`](crate::Status::new).
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let status = Status::from(http::StatusCode::NOT_FOUND);
+  ///
+  /// assert_eq!(status.code(), 404);
+  /// assert!(status.reason() == "Not Found" || status.reason() == "");
+  /// ```
+  fn from(code: http::StatusCode) -> Self {
+    match code.canonical_reason() {
+      Some(reason) if cfg!(not(feature = "no-reason-phrase")) => {
+        Status::with_reason(code.as_u16(), reason)
+      }
+      _ => Status::new(code.as_u16()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn asterisk() {
+    let uri: http::Uri = "*".parse().unwrap();
+    assert!(Uri::try_from(&uri).unwrap().is_asterisk());
+  }
+
+  #[test]
+  fn path_and_query() {
+    let path = PathAndQuery::from_static("/a/b?c=d");
+    assert_eq!(Uri::try_from(&path).unwrap().as_bytes(), b"/a/b?c=d");
+  }
+
+  #[test]
+  fn method() {
+    let method = http::Method::from_bytes(b"PROPFIND").unwrap();
+    assert_eq!(Method::from(&method), Method::PROPFIND);
+    assert_eq!(Method::from(&http::Method::GET), Method::GET);
+  }
+
+  #[test]
+  fn status_code() {
+    let status = Status::from(http::StatusCode::from_u16(599).unwrap());
+    assert_eq!(status.code(), 599);
+    assert_eq!(status.reason(), "");
+  }
+}
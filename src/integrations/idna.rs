@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+use crate::uri::validate_host;
+use crate::InvalidUriError;
+
+/// Convert a host name into the ASCII form needed within a request
+/// target or a `Host` header.
+///
+/// Internationalized domain names are punycode-encoded following UTS #46
+/// so that they pass validation in [`Uri`](crate::Uri) and
+/// [`HttpBuilder::connect`](crate::HttpBuilder::connect). Hosts that are
+/// already ASCII and valid, including IP literals, are returned as-is
+/// without allocating.
+///
+/// # Errors
+/// Returns an error if `host` is not a valid domain name.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let host = to_ascii_host("bücher.example")?;
+/// assert_eq!(host, "xn--bcher-kva.example");
+///
+/// let mut builder =
+///   HttpBuilder::connect(vec![], &host, 443, Version::HTTP_1_1)?;
+/// builder.header(Header::new("Host", &*host))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_ascii_host(host: &str) -> Result<Cow<'_, str>, InvalidUriError> {
+  if host.is_ascii() && validate_host(host.as_bytes()) {
+    return Ok(Cow::Borrowed(host));
+  }
+
+  let encoded = idna::domain_to_ascii(host).map_err(|_| InvalidUriError(()))?;
+
+  if !validate_host(encoded.as_bytes()) {
+    return Err(InvalidUriError(()));
+  }
+
+  Ok(Cow::Owned(encoded))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ascii_hosts_are_borrowed() {
+    assert!(matches!(to_ascii_host("example.com"), Ok(Cow::Borrowed(_))));
+    assert!(matches!(to_ascii_host("[::1]"), Ok(Cow::Borrowed(_))));
+  }
+
+  #[test]
+  fn invalid_hosts() {
+    assert!(to_ascii_host("").is_err());
+    assert!(to_ascii_host("a b.com").is_err());
+    assert!(to_ascii_host("exa\r\nmple.com").is_err());
+  }
+}
@@ -0,0 +1,105 @@
+use ::mime::Mime;
+
+use crate::util::sum_hints;
+use crate::{
+  is_token_fast, BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  Quoted,
+};
+
+impl HttpWriteable for Mime {
+  /// Write out this media type, as used within the `Content-Type` and
+  /// `Accept` headers.
+  ///
+  /// Parameter values are written out as tokens where possible and as
+  /// quoted-strings otherwise, regardless of how they were originally
+  /// parsed.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mime: mime::Mime = "multipart/form-data; boundary=\"a b\"".parse()?;
+  ///
+  /// let mut buffer = Vec::new();
+  /// Header::new("Content-Type", mime::APPLICATION_JSON)
+  ///   .write_to(&mut buffer)?;
+  /// Header::new("Content-Type", mime).write_to(&mut buffer)?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&buffer)?,
+  ///   "Content-Type: application/json\r\n\
+  ///   Content-Type: multipart/form-data; boundary=\"a b\"\r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.essence_str().as_bytes())?;
+
+    for (name, value) in self.params() {
+      let value = value.as_str().as_bytes();
+
+      buffer.try_put_slice(b"; ")?;
+      buffer.try_put_slice(name.as_str().as_bytes())?;
+      buffer.try_put_u8(b'=')?;
+
+      if is_token_fast(value) {
+        buffer.try_put_slice(value)?;
+      } else {
+        // The mime parser rejects control characters within parameter
+        // values so they can always be quoted.
+        Quoted(value).write_to(buffer)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.essence_str().len();
+    let params = self.params().map(|(name, value)| {
+      let value = value.as_str().as_bytes();
+      let len = name.as_str().len() + 3;
+
+      if is_token_fast(value) {
+        let len = len + value.len();
+        (len, Some(len))
+      } else {
+        sum_hints([(len, Some(len)), Quoted(value).size_hint()])
+      }
+    });
+
+    sum_hints(params.chain([(len, Some(len))]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn constants() {
+    assert_eq!(encode_str(&::mime::TEXT_HTML), "text/html");
+    assert_eq!(
+      encode_str(&::mime::TEXT_PLAIN_UTF_8),
+      "text/plain; charset=utf-8"
+    );
+    assert_eq!(encode_str(&::mime::IMAGE_SVG), "image/svg+xml");
+  }
+
+  #[test]
+  fn params() {
+    let mime: Mime = "Text/Plain;charset=\"utf-8\";  format=\"a\\b\""
+      .parse()
+      .unwrap();
+
+    assert_eq!(
+      encode_str(&mime),
+      r#"text/plain; charset=utf-8; format="a\\b""#
+    );
+  }
+}
@@ -1,4 +1,20 @@
 //! Integrations for various external crates.
 
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "cookie")]
+mod cookie;
+#[cfg(feature = "http")]
+mod http;
 #[cfg(feature = "httparse")]
 mod httparse;
+#[cfg(feature = "idna")]
+pub(crate) mod idna;
+#[cfg(feature = "mime")]
+mod mime;
+#[cfg(feature = "serde")]
+pub(crate) mod serde;
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "uuid")]
+mod uuid;
@@ -0,0 +1,739 @@
+use core::convert::TryFrom;
+use core::fmt::{self, Display};
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::{
+  BufMut, CheckedField, Header, HttpBuilder, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError,
+};
+
+/// An error that occurred while serializing a value with a
+/// [`HeaderSerializer`](crate::HeaderSerializer).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HeaderSerializeError {
+  /// A struct field or map key was not a valid header field name.
+  InvalidHeader(InvalidHeaderError),
+  /// There was not enough space in the buffer.
+  InsufficientSpace(InsufficientSpaceError),
+  /// The value had a shape that cannot be represented as headers, such
+  /// as a nested struct.
+  Unsupported(&'static str),
+  /// A custom error raised by a `Serialize` implementation.
+  Custom(String),
+}
+
+impl Display for HeaderSerializeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidHeader(e) => e.fmt(f),
+      Self::InsufficientSpace(e) => e.fmt(f),
+      Self::Unsupported(message) => f.write_str(message),
+      Self::Custom(message) => f.write_str(message),
+    }
+  }
+}
+
+impl std::error::Error for HeaderSerializeError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::InvalidHeader(e) => Some(e),
+      Self::InsufficientSpace(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl ser::Error for HeaderSerializeError {
+  fn custom<T: Display>(message: T) -> Self {
+    Self::Custom(message.to_string())
+  }
+}
+
+impl From<InvalidHeaderError> for HeaderSerializeError {
+  fn from(error: InvalidHeaderError) -> Self {
+    Self::InvalidHeader(error)
+  }
+}
+
+impl From<InsufficientSpaceError> for HeaderSerializeError {
+  fn from(error: InsufficientSpaceError) -> Self {
+    Self::InsufficientSpace(error)
+  }
+}
+
+macro_rules! unsupported {
+  ($message:literal; $( $method:ident($( $ty:ty ),*) -> $ret:ty; )*) => {
+    $(
+      fn $method(self, $( _: $ty ),*) -> Result<$ret, Self::Error> {
+        Err(HeaderSerializeError::Unsupported($message))
+      }
+    )*
+  };
+}
+
+/// A serde `Serializer` that writes out the fields of a flat struct or
+/// map as headers on an [`HttpBuilder`](crate::HttpBuilder).
+///
+/// Each struct field or map entry becomes one header. Field names are
+/// used as-is so they usually need a `#[serde(rename = "...")]` or a
+/// `#[serde(rename_all = "kebab-case")]` attribute. Values are written
+/// out as follows:
+/// - Numbers use the same encoding as the integer writables.
+/// - Strings and bytes are folded the same way as `&str` and `&[u8]`.
+/// - `None` and unit values are skipped entirely.
+/// - Sequences write out one header per element, as with
+///   [`HttpBuilder::header_each`](crate::HttpBuilder::header_each).
+/// - Unit enum variants are written out as the variant name.
+///
+/// Nested structs, maps, and enum variants with data cannot be
+/// represented as a header value and result in an error.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use serde::Serialize;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "kebab-case")]
+/// struct Metadata<'a> {
+///   content_type: &'a str,
+///   content_length: u64,
+///   etag: Option<&'a str>,
+///   via: Vec<&'a str>,
+/// }
+///
+/// let metadata = Metadata {
+///   content_type: "text/plain",
+///   content_length: 5,
+///   etag: None,
+///   via: vec!["1.1 a", "1.1 b"],
+/// };
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// metadata.serialize(HeaderSerializer::new(&mut builder))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   content-type: text/plain\r\n\
+///   content-length: 5\r\n\
+///   via: 1.1 a\r\n\
+///   via: 1.1 b\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct HeaderSerializer<'a, B: BufMut> {
+  builder: &'a mut HttpBuilder<B>,
+}
+
+impl<'a, B: BufMut> HeaderSerializer<'a, B> {
+  /// Create a serializer that writes out headers to `builder`.
+  pub fn new(builder: &'a mut HttpBuilder<B>) -> Self {
+    Self { builder }
+  }
+
+  fn write_entry<T>(
+    &mut self,
+    field: &str,
+    value: &T,
+  ) -> Result<(), HeaderSerializeError>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(ValueSerializer {
+      builder: &mut *self.builder,
+      field: CheckedField::try_from(field)?,
+      nested: false,
+    })
+  }
+}
+
+impl<'a, B: BufMut> ser::Serializer for HeaderSerializer<'a, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  type SerializeSeq = Impossible<(), Self::Error>;
+  type SerializeTuple = Impossible<(), Self::Error>;
+  type SerializeTupleStruct = Impossible<(), Self::Error>;
+  type SerializeTupleVariant = Impossible<(), Self::Error>;
+  type SerializeMap = MapSerializer<'a, B>;
+  type SerializeStruct = Self;
+  type SerializeStructVariant = Impossible<(), Self::Error>;
+
+  unsupported! {
+    "headers can only be serialized from a struct or map";
+    serialize_bool(bool) -> ();
+    serialize_i8(i8) -> ();
+    serialize_i16(i16) -> ();
+    serialize_i32(i32) -> ();
+    serialize_i64(i64) -> ();
+    serialize_u8(u8) -> ();
+    serialize_u16(u16) -> ();
+    serialize_u32(u32) -> ();
+    serialize_u64(u64) -> ();
+    serialize_f32(f32) -> ();
+    serialize_f64(f64) -> ();
+    serialize_char(char) -> ();
+    serialize_str(&str) -> ();
+    serialize_bytes(&[u8]) -> ();
+    serialize_unit_variant(&'static str, u32, &'static str) -> ();
+    serialize_seq(Option<usize>) -> Self::SerializeSeq;
+    serialize_tuple(usize) -> Self::SerializeTuple;
+    serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+    serialize_tuple_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeTupleVariant;
+    serialize_struct_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeStructVariant;
+  }
+
+  fn serialize_none(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_some<T>(self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_newtype_struct<T>(
+    self,
+    _: &'static str,
+    value: &T,
+  ) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self,
+    _: &'static str,
+    _: u32,
+    _: &'static str,
+    _: &T,
+  ) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    Err(HeaderSerializeError::Unsupported(
+      "headers can only be serialized from a struct or map",
+    ))
+  }
+
+  fn serialize_map(
+    self,
+    _: Option<usize>,
+  ) -> Result<Self::SerializeMap, Self::Error> {
+    Ok(MapSerializer {
+      inner: self,
+      key: None,
+    })
+  }
+
+  fn serialize_struct(
+    self,
+    _: &'static str,
+    _: usize,
+  ) -> Result<Self::SerializeStruct, Self::Error> {
+    Ok(self)
+  }
+}
+
+impl<B: BufMut> ser::SerializeStruct for HeaderSerializer<'_, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  fn serialize_field<T>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.write_entry(key, value)
+  }
+
+  fn end(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+#[doc(hidden)]
+pub struct MapSerializer<'a, B: BufMut> {
+  inner: HeaderSerializer<'a, B>,
+  key: Option<String>,
+}
+
+impl<B: BufMut> ser::SerializeMap for MapSerializer<'_, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.key = Some(key.serialize(KeySerializer)?);
+    Ok(())
+  }
+
+  fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    let key = self
+      .key
+      .take()
+      .expect("serialize_value called before serialize_key");
+
+    self.inner.write_entry(&key, value)
+  }
+
+  fn end(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+/// Serializes map keys into header field names.
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+  type Ok = String;
+  type Error = HeaderSerializeError;
+
+  type SerializeSeq = Impossible<String, Self::Error>;
+  type SerializeTuple = Impossible<String, Self::Error>;
+  type SerializeTupleStruct = Impossible<String, Self::Error>;
+  type SerializeTupleVariant = Impossible<String, Self::Error>;
+  type SerializeMap = Impossible<String, Self::Error>;
+  type SerializeStruct = Impossible<String, Self::Error>;
+  type SerializeStructVariant = Impossible<String, Self::Error>;
+
+  unsupported! {
+    "header field names must be strings";
+    serialize_bool(bool) -> String;
+    serialize_i8(i8) -> String;
+    serialize_i16(i16) -> String;
+    serialize_i32(i32) -> String;
+    serialize_i64(i64) -> String;
+    serialize_u8(u8) -> String;
+    serialize_u16(u16) -> String;
+    serialize_u32(u32) -> String;
+    serialize_u64(u64) -> String;
+    serialize_f32(f32) -> String;
+    serialize_f64(f64) -> String;
+    serialize_bytes(&[u8]) -> String;
+    serialize_none() -> String;
+    serialize_unit() -> String;
+    serialize_unit_struct(&'static str) -> String;
+    serialize_seq(Option<usize>) -> Self::SerializeSeq;
+    serialize_tuple(usize) -> Self::SerializeTuple;
+    serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+    serialize_tuple_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeTupleVariant;
+    serialize_map(Option<usize>) -> Self::SerializeMap;
+    serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+    serialize_struct_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeStructVariant;
+  }
+
+  fn serialize_char(self, value: char) -> Result<String, Self::Error> {
+    Ok(value.to_string())
+  }
+
+  fn serialize_str(self, value: &str) -> Result<String, Self::Error> {
+    Ok(value.to_owned())
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _: &'static str,
+    _: u32,
+    variant: &'static str,
+  ) -> Result<String, Self::Error> {
+    Ok(variant.to_owned())
+  }
+
+  fn serialize_some<T>(self, value: &T) -> Result<String, Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_struct<T>(
+    self,
+    _: &'static str,
+    value: &T,
+  ) -> Result<String, Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self,
+    _: &'static str,
+    _: u32,
+    _: &'static str,
+    _: &T,
+  ) -> Result<String, Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    Err(HeaderSerializeError::Unsupported(
+      "header field names must be strings",
+    ))
+  }
+}
+
+/// Serializes a single value as one or more headers with the same field
+/// name.
+#[doc(hidden)]
+pub struct ValueSerializer<'a, 'f, B: BufMut> {
+  builder: &'a mut HttpBuilder<B>,
+  field: CheckedField<'f>,
+  // Whether this is an element of a sequence. Sequences of sequences
+  // cannot be written out as headers.
+  nested: bool,
+}
+
+impl<B: BufMut> ValueSerializer<'_, '_, B> {
+  fn write<V: HttpWriteable>(
+    self,
+    value: V,
+  ) -> Result<(), HeaderSerializeError> {
+    self
+      .builder
+      .header(Header::checked_new(self.field, value))?;
+    Ok(())
+  }
+
+  fn sequence(self) -> Result<Self, HeaderSerializeError> {
+    if self.nested {
+      return Err(HeaderSerializeError::Unsupported(
+        "header values cannot be nested sequences",
+      ));
+    }
+
+    Ok(self)
+  }
+
+  fn element<T>(&mut self, value: &T) -> Result<(), HeaderSerializeError>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(ValueSerializer {
+      builder: &mut *self.builder,
+      field: self.field,
+      nested: true,
+    })
+  }
+}
+
+impl<'a, 'f, B: BufMut> ser::Serializer for ValueSerializer<'a, 'f, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  type SerializeSeq = Self;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Self;
+  type SerializeTupleVariant = Impossible<(), Self::Error>;
+  type SerializeMap = Impossible<(), Self::Error>;
+  type SerializeStruct = Impossible<(), Self::Error>;
+  type SerializeStructVariant = Impossible<(), Self::Error>;
+
+  fn serialize_bool(self, value: bool) -> Result<(), Self::Error> {
+    self.write(if value { "true" } else { "false" })
+  }
+
+  fn serialize_i8(self, value: i8) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_i16(self, value: i16) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_i32(self, value: i32) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_i64(self, value: i64) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_i128(self, value: i128) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_u8(self, value: u8) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_u16(self, value: u16) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_u32(self, value: u32) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_u64(self, value: u64) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_u128(self, value: u128) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_f32(self, value: f32) -> Result<(), Self::Error> {
+    self.write(format_args!("{}", value))
+  }
+
+  fn serialize_f64(self, value: f64) -> Result<(), Self::Error> {
+    self.write(format_args!("{}", value))
+  }
+
+  fn serialize_char(self, value: char) -> Result<(), Self::Error> {
+    self.write(&*value.encode_utf8(&mut [0; 4]))
+  }
+
+  fn serialize_str(self, value: &str) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_bytes(self, value: &[u8]) -> Result<(), Self::Error> {
+    self.write(value)
+  }
+
+  fn serialize_none(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_some<T>(self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+    Ok(())
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _: &'static str,
+    _: u32,
+    variant: &'static str,
+  ) -> Result<(), Self::Error> {
+    self.write(variant)
+  }
+
+  fn serialize_newtype_struct<T>(
+    self,
+    _: &'static str,
+    value: &T,
+  ) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T>(
+    self,
+    _: &'static str,
+    _: u32,
+    _: &'static str,
+    _: &T,
+  ) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    Err(HeaderSerializeError::Unsupported(
+      "header values cannot be enum variants with data",
+    ))
+  }
+
+  fn serialize_seq(
+    self,
+    _: Option<usize>,
+  ) -> Result<Self::SerializeSeq, Self::Error> {
+    self.sequence()
+  }
+
+  fn serialize_tuple(
+    self,
+    _: usize,
+  ) -> Result<Self::SerializeTuple, Self::Error> {
+    self.sequence()
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _: &'static str,
+    _: usize,
+  ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+    self.sequence()
+  }
+
+  unsupported! {
+    "header values cannot be maps, structs, or enum variants with data";
+    serialize_tuple_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeTupleVariant;
+    serialize_map(Option<usize>) -> Self::SerializeMap;
+    serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+    serialize_struct_variant(&'static str, u32, &'static str, usize)
+      -> Self::SerializeStructVariant;
+  }
+}
+
+impl<B: BufMut> ser::SerializeSeq for ValueSerializer<'_, '_, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+impl<B: BufMut> ser::SerializeTuple for ValueSerializer<'_, '_, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+impl<B: BufMut> ser::SerializeTupleStruct for ValueSerializer<'_, '_, B> {
+  type Ok = ();
+  type Error = HeaderSerializeError;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+  where
+    T: Serialize + ?Sized,
+  {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Status, Version};
+  use std::collections::BTreeMap;
+
+  fn encode<T: Serialize>(value: &T) -> Result<String, HeaderSerializeError> {
+    let mut builder = HttpBuilder::response(
+      vec![],
+      Version::HTTP_1_1,
+      Status::with_reason(200, "OK"),
+    )
+    .unwrap();
+    value.serialize(HeaderSerializer::new(&mut builder))?;
+
+    let output = builder.finish().unwrap();
+    let output = String::from_utf8(output).unwrap();
+    Ok(output["HTTP/1.1 200 OK\r\n".len()..].to_owned())
+  }
+
+  #[test]
+  fn map() {
+    let mut map = BTreeMap::new();
+    map.insert("Accept", vec!["text/html"]);
+    map.insert("Via", vec!["a", "b"]);
+    map.insert("X-Empty", vec![]);
+
+    assert_eq!(
+      encode(&map).unwrap(),
+      "Accept: text/html\r\nVia: a\r\nVia: b\r\n\r\n"
+    );
+  }
+
+  #[test]
+  fn scalars() {
+    let mut map = BTreeMap::new();
+    map.insert("A", Some(-5i64));
+    map.insert("B", None);
+
+    assert_eq!(encode(&map).unwrap(), "A: -5\r\n\r\n");
+    assert_eq!(
+      encode(
+        &[("X-Folded", "a\r\nb")]
+          .iter()
+          .copied()
+          .collect::<BTreeMap<_, _>>()
+      )
+      .unwrap(),
+      "X-Folded: a\r\n\tb\r\n\r\n"
+    );
+  }
+
+  #[test]
+  fn unsupported() {
+    let mut nested = BTreeMap::new();
+    nested.insert("Via", vec![vec!["a"]]);
+
+    let mut invalid = BTreeMap::new();
+    invalid.insert("Not Valid", 1);
+
+    assert!(matches!(
+      encode(&nested),
+      Err(HeaderSerializeError::Unsupported(_))
+    ));
+    assert!(matches!(
+      encode(&invalid),
+      Err(HeaderSerializeError::InvalidHeader(_))
+    ));
+    assert!(matches!(
+      encode(&5),
+      Err(HeaderSerializeError::Unsupported(_))
+    ));
+  }
+}
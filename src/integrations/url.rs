@@ -0,0 +1,73 @@
+use core::convert::TryFrom;
+
+use url::{Position, Url};
+
+use crate::{InvalidUriError, Uri};
+
+impl<'a> TryFrom<&'a Url> for Uri<'a> {
+  type Error = InvalidUriError;
+
+  /// Use `url` as an absolute-form request target.
+  ///
+  /// The fragment, if any, is not included since it must not be sent
+  /// as part of a request. URLs that cannot be used as an absolute-form
+  /// target (such as `mailto:` URLs) are rejected.
+  fn try_from(url: &'a Url) -> Result<Self, Self::Error> {
+    Uri::try_absolute(url[..Position::AfterQuery].as_bytes())
+  }
+}
+
+impl<'a> Uri<'a> {
+  /// Use the path and query of `url` as an origin-form request target.
+  ///
+  /// This is the form used for requests made directly to an origin
+  /// server. The fragment, if any, is not included.
+  ///
+  /// # Errors
+  /// Returns an error if `url` doesn't have a hierarchical path, such
+  /// as is the case for `mailto:` URLs.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let url = url::Url::parse("https://example.com/a b?q=1#top")?;
+  /// let uri = Uri::origin_form_of(&url)?;
+  ///
+  /// assert_eq!(uri.as_bytes(), b"/a%20b?q=1");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn origin_form_of(url: &'a Url) -> Result<Self, InvalidUriError> {
+    if url.cannot_be_a_base() {
+      return Err(InvalidUriError(()));
+    }
+
+    Uri::try_new(url[Position::BeforePath..Position::AfterQuery].as_bytes())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn absolute_form_strips_fragment() {
+    let url = Url::parse("http://user@example.com:8080/p?q#frag").unwrap();
+
+    // Userinfo is not allowed within a request target.
+    assert!(Uri::try_from(&url).is_err());
+
+    let url = Url::parse("http://example.com:8080/p?q#frag").unwrap();
+    let uri = Uri::try_from(&url).unwrap();
+    assert_eq!(uri.as_bytes(), b"http://example.com:8080/p?q");
+  }
+
+  #[test]
+  fn cannot_be_a_base() {
+    let url = Url::parse("mailto:someone@example.com").unwrap();
+
+    assert!(Uri::try_from(&url).is_err());
+    assert!(Uri::origin_form_of(&url).is_err());
+  }
+}
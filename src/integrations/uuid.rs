@@ -0,0 +1,61 @@
+use ::uuid::fmt::Hyphenated;
+use ::uuid::Uuid;
+
+use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
+
+impl HttpWriteable for Uuid {
+  /// Write out this UUID in its lowercase hyphenated form, as used within
+  /// `X-Request-Id` and similar headers.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let id = uuid::Uuid::from_u128(0x67E55044_10B1_426F_9247_BB680E5FE0C8);
+  ///
+  /// let mut buffer = Vec::new();
+  /// Header::new("X-Request-Id", id).write_to(&mut buffer)?;
+  ///
+  /// assert_eq!(
+  ///   buffer,
+  ///   b"X-Request-Id: 67e55044-10b1-426f-9247-bb680e5fe0c8\r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let mut encoded = [0; Hyphenated::LENGTH];
+    let encoded = self.hyphenated().encode_lower(&mut encoded);
+
+    buffer.try_put_slice(encoded.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (Hyphenated::LENGTH, Some(Hyphenated::LENGTH))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn uuid() {
+    let cases = [
+      (Uuid::nil(), "00000000-0000-0000-0000-000000000000"),
+      (Uuid::max(), "ffffffff-ffff-ffff-ffff-ffffffffffff"),
+    ];
+
+    for (uuid, expected) in &cases {
+      let mut buffer = Vec::new();
+      uuid.write_to(&mut buffer).unwrap();
+
+      assert_eq!(buffer, expected.as_bytes());
+      assert_eq!(uuid.size_hint(), (buffer.len(), Some(buffer.len())));
+    }
+  }
+}
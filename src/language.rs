@@ -0,0 +1,199 @@
+use core::convert::TryFrom;
+
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError, Separated, Weighted,
+};
+
+/// Whether `tag` is made up of 1-8 character subtags separated by `-`,
+/// where the first subtag is alphabetic and the rest are alphanumeric.
+const fn is_language_tag(tag: &str) -> bool {
+  let bytes = tag.as_bytes();
+  let mut subtag = 0;
+  let mut len = 0;
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'-' if len != 0 => {
+        subtag += 1;
+        len = 0;
+      }
+      b'a'..=b'z' | b'A'..=b'Z' if len < 8 => len += 1,
+      b'0'..=b'9' if len < 8 && subtag != 0 => len += 1,
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  len != 0
+}
+
+/// A language tag, as used within `Accept-Language` and
+/// `Content-Language` headers.
+///
+/// Tags are made up of subtags of 1 to 8 characters separated by `-`,
+/// such as `en`, `en-US`, or `zh-Hant-TW`. The first subtag must be
+/// alphabetic and the rest may also contain digits. Only this syntax is
+/// checked, not whether the subtags are registered.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// assert!(LanguageTag::try_new("fr-CA").is_ok());
+/// assert!(LanguageTag::try_new("es-419").is_ok());
+///
+/// assert!(LanguageTag::try_new("en_US").is_err());
+/// assert!(LanguageTag::try_new("en-").is_err());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LanguageTag<'data>(&'data str);
+
+impl<'data> LanguageTag<'data> {
+  /// The `*` language range, which matches any language not listed
+  /// elsewhere within an `Accept-Language` header.
+  pub const ANY: Self = Self("*");
+
+  /// Create a language tag.
+  ///
+  /// # Errors
+  /// Returns an error if `tag` is not made up of 1 to 8 character
+  /// subtags separated by `-`, or if the first subtag is not alphabetic.
+  pub const fn try_new(tag: &'data str) -> Result<Self, InvalidHeaderError> {
+    if !is_language_tag(tag) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(tag))
+  }
+
+  /// Create a language tag.
+  ///
+  /// # Panics
+  /// Panics if `tag` is not made up of 1 to 8 character subtags separated
+  /// by `-`, or if the first subtag is not alphabetic.
+  pub const fn new(tag: &'data str) -> Self {
+    match Self::try_new(tag) {
+      Ok(tag) => tag,
+      Err(_) => const_panic!("Invalid language tag"),
+    }
+  }
+
+  /// Get the string representation of this tag.
+  pub const fn as_str(&self) -> &'data str {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data str> for LanguageTag<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(tag: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(tag)
+  }
+}
+
+impl HttpWriteable for LanguageTag<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.0.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.0.len(), Some(self.0.len()))
+  }
+}
+
+/// An `Accept-Language` header value listing the languages a client
+/// prefers along with their quality values.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const ACCEPT_LANGUAGE: AcceptLanguage = AcceptLanguage::new(&[
+///   Weighted::new(LanguageTag::new("fr-CH")),
+///   Weighted::new(LanguageTag::new("fr")).q(QValue::new(900)),
+///   Weighted::new(LanguageTag::ANY).q(QValue::new(500)),
+/// ]);
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Accept-Language", ACCEPT_LANGUAGE).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, &b"Accept-Language: fr-CH, fr;q=0.9, *;q=0.5\r\n"[..]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AcceptLanguage<'data> {
+  tags: &'data [Weighted<LanguageTag<'data>>],
+}
+
+impl<'data> AcceptLanguage<'data> {
+  /// Create an `Accept-Language` value from a list of language tags.
+  pub const fn new(tags: &'data [Weighted<LanguageTag<'data>>]) -> Self {
+    Self { tags }
+  }
+
+  /// The language tags in the order they are written out.
+  pub const fn tags(&self) -> &'data [Weighted<LanguageTag<'data>>] {
+    self.tags
+  }
+}
+
+impl HttpWriteable for AcceptLanguage<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    Separated::new(self.tags).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    Separated::new(self.tags).size_hint()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn valid_tags() {
+    for tag in &[
+      "en",
+      "EN-us",
+      "zh-Hant-TW",
+      "sgn-BE-FR",
+      "x-klingon",
+      "de-1996",
+    ] {
+      assert!(LanguageTag::try_new(tag).is_ok(), "{}", tag);
+    }
+  }
+
+  #[test]
+  fn invalid_tags() {
+    for tag in &[
+      "",
+      "*",
+      "-",
+      "en-",
+      "-en",
+      "en--US",
+      "1en",
+      "abcdefghi",
+      "en-abcdefghi",
+      "en US",
+      "en;q=1",
+      "en,fr",
+      "ü",
+    ] {
+      assert!(LanguageTag::try_new(tag).is_err(), "{}", tag);
+    }
+  }
+}
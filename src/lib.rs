@@ -87,25 +87,127 @@ macro_rules! const_panic {
 
 pub use bytes::BufMut;
 
+use core::convert::TryFrom;
+
+use bytes::buf::ext::{BufMutExt, Limit};
+
+mod auth;
+mod authority;
+mod base64;
+#[cfg(feature = "std")]
+mod block;
+mod chunked;
+mod coding;
+mod connect;
+mod connection;
+mod cookie;
+mod cors;
+mod date;
+mod digest;
+mod disposition;
 mod errors;
+mod h2;
 mod header;
+#[cfg(feature = "std")]
+mod headers;
 mod integrations;
+mod language;
+mod link;
+mod measure;
 mod method;
+mod params;
+mod placeholder;
+mod policy;
+mod pre_encoded;
+mod presets;
+mod product;
+mod quoted;
+mod qvalue;
+mod separated;
+mod signature;
 mod status;
+mod structured;
+mod target;
+mod to_headers;
+mod trace;
+mod trailer;
 mod uri;
+#[cfg(feature = "std")]
+mod uri_builder;
 mod util;
+mod validate;
+mod vary;
 mod version;
+mod warning;
 mod writable;
 
-pub use crate::header::{CheckedField, CheckedValue, Header};
+pub use crate::auth::{
+  Bearer, Challenge, DigestAlgorithm, DigestChallenge, DigestQop,
+  DigestResponse,
+};
+pub use crate::authority::{Authority, HostValue};
+#[cfg(feature = "std")]
+pub use crate::block::HeaderBlock;
+pub use crate::chunked::ChunkedWriter;
+pub use crate::coding::{
+  AcceptEncoding, ContentCoding, Te, TransferCoding, TransferEncoding,
+};
+pub use crate::connection::ConnectionOptions;
+pub use crate::cookie::{SameSite, SetCookie};
+pub use crate::cors::Cors;
+#[cfg(feature = "std")]
+pub use crate::date::CachedDate;
+pub use crate::date::{Age, DeltaSeconds, HttpDate, RetryAfter};
+pub use crate::digest::ContentDigest;
+pub use crate::disposition::{ContentDisposition, DispositionType};
+pub use crate::h2::{write_h2_preface, H2_PREFACE};
+pub use crate::header::{CheckedField, CheckedValue, Header, StrictValue};
+#[cfg(feature = "std")]
+pub use crate::headers::{Headers, HeadersIter};
+#[cfg(feature = "idna")]
+pub use crate::integrations::idna::to_ascii_host;
+#[cfg(feature = "serde")]
+pub use crate::integrations::serde::{HeaderSerializeError, HeaderSerializer};
+pub use crate::language::{AcceptLanguage, LanguageTag};
+pub use crate::link::Link;
+pub use crate::measure::CountingBufMut;
 pub use crate::method::Method;
-pub use crate::status::Status;
+pub use crate::params::Parameterized;
+pub use crate::placeholder::ContentLengthPlaceholder;
+pub use crate::policy::{Sanitized, ValuePolicy};
+#[doc(hidden)]
+pub use crate::pre_encoded::__pre_encode_header;
+pub use crate::pre_encoded::PreEncodedHeader;
+pub use crate::product::{Product, ProductList};
+pub use crate::quoted::{Comment, Quoted};
+pub use crate::qvalue::{QValue, Weighted};
+pub use crate::separated::{ListItem, Separated};
+pub use crate::signature::{
+  Signature, SignatureBase, SignatureInput, SignatureParams,
+};
+pub use crate::status::{ReasonPhrase, Status};
+pub use crate::structured::StructuredBool;
+pub use crate::target::RequestTarget;
+pub use crate::to_headers::ToHeaders;
+pub use crate::trace::{Traceparent, Tracestate};
+pub use crate::trailer::{Trailer, TrailerWriter};
 pub use crate::uri::Uri;
+#[doc(hidden)]
+pub use crate::uri::{__percent_encode, __percent_encoded_len};
+#[cfg(feature = "std")]
+pub use crate::uri_builder::UriBuilder;
 pub use crate::util::FallibleBufMut;
+pub use crate::validate::ValidatingBuilder;
+pub use crate::vary::Vary;
 pub use crate::version::Version;
-pub use crate::writable::HttpWriteable;
+pub use crate::warning::{WarnCode, Warning};
+pub use crate::writable::{DisplayValue, HttpWriteable, ValueWriter};
+
+#[cfg(feature = "derive")]
+pub use httpencode_derive::ToHeaders;
 
 const CRLF: [u8; 2] = *b"\r\n";
+const CONTENT_LENGTH: CheckedField = CheckedField::new("Content-Length");
 
 /// A custom HTTP method contained invalid characters.
 ///
@@ -139,10 +241,60 @@ pub struct InvalidUriError(());
 #[derive(Debug)]
 pub struct InvalidHeaderError(());
 
+/// A custom protocol name contained an invalid character.
+///
+/// Protocol names must be a valid token as defined in RFC 7230.
+#[derive(Debug)]
+pub struct InvalidVersionError(());
+
+/// A status code was out of range or a status contained an invalid
+/// reason phrase.
+///
+/// Status codes must be three digits long (`100..=999`). Reason phrases
+/// may not contain any control characters other than horizontal tab.
+/// From RFC 7230:
+/// ```text
+/// reason-phrase = *( HTAB / SP / VCHAR / obs-text )
+/// ```
+#[derive(Debug)]
+pub struct InvalidStatusError(());
+
 /// The target buffer doesn't have enough space to write out the desired data.
 #[derive(Default, Debug)]
 pub struct InsufficientSpaceError(());
 
+/// A message would have been semantically invalid or ambiguous had it
+/// been written out.
+///
+/// This is only returned by
+/// [`ValidatingBuilder`](crate::ValidatingBuilder).
+#[derive(Debug)]
+pub struct InvalidMessageError(&'static str);
+
+/// Any of the errors that can occur while validating or encoding an
+/// HTTP message.
+///
+/// This is useful when validation is being done at runtime and the
+/// caller just wants to propagate a single error type.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HttpEncodeError {
+  /// See [`InvalidMethodError`](crate::InvalidMethodError).
+  InvalidMethod(InvalidMethodError),
+  /// See [`InvalidUriError`](crate::InvalidUriError).
+  InvalidUri(InvalidUriError),
+  /// See [`InvalidHeaderError`](crate::InvalidHeaderError).
+  InvalidHeader(InvalidHeaderError),
+  /// See [`InsufficientSpaceError`](crate::InsufficientSpaceError).
+  InsufficientSpace(InsufficientSpaceError),
+  /// See [`InvalidMessageError`](crate::InvalidMessageError).
+  InvalidMessage(InvalidMessageError),
+  /// See [`InvalidStatusError`](crate::InvalidStatusError).
+  InvalidStatus(InvalidStatusError),
+  /// See [`InvalidVersionError`](crate::InvalidVersionError).
+  InvalidVersion(InvalidVersionError),
+}
+
 /// Start an HTTP-style request with the given method, uri, and protocol
 /// version.
 ///
@@ -213,7 +365,13 @@ pub fn response<B: BufMut>(
 /// Build an HTTP 1.1/1.0-style request or response and write it out to
 /// the provided buffer.
 pub struct HttpBuilder<B: BufMut> {
-  buffer: B,
+  buffer: Limit<B>,
+  // The value of remaining_mut() when the builder was created. Used to
+  // determine how far into the message the builder currently is.
+  start: usize,
+  headers: usize,
+  status: Option<u16>,
+  version: Option<(u8, u8)>,
 }
 
 impl<B: BufMut> HttpBuilder<B> {
@@ -245,6 +403,8 @@ impl<B: BufMut> HttpBuilder<B> {
     request_target: Uri,
     version: Version,
   ) -> Result<Self, InsufficientSpaceError> {
+    let start = buffer.remaining_mut();
+
     method.write_to(&mut buffer)?;
     buffer.try_put_u8(b' ')?;
     request_target.write_to(&mut buffer)?;
@@ -252,7 +412,56 @@ impl<B: BufMut> HttpBuilder<B> {
     version.write_to(&mut buffer)?;
     buffer.try_put_slice(&CRLF)?;
 
-    Ok(Self { buffer })
+    Ok(Self::started(buffer, start, None, version))
+  }
+
+  /// Start an `OPTIONS *` request, which asks about the capabilities of
+  /// the server as a whole instead of those of a specific resource.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::options_all(vec![], Version::HTTP_1_1)?;
+  /// builder.header(Header::new("Host", "example.com"))?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "OPTIONS * HTTP/1.1\r\n\
+  ///   Host: example.com\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn options_all(
+    buffer: B,
+    version: Version,
+  ) -> Result<Self, InsufficientSpaceError> {
+    Self::request(buffer, Method::OPTIONS, Uri::ASTERISK, version)
+  }
+
+  /// Create a builder for a message whose start line has already been
+  /// written out.
+  pub(crate) fn started(
+    buffer: B,
+    start: usize,
+    status: Option<u16>,
+    version: Version,
+  ) -> Self {
+    Self {
+      buffer: buffer.limit(usize::MAX),
+      start,
+      headers: 0,
+      status,
+      // Connection defaults are only known for HTTP itself.
+      version: if version.is_http() {
+        Some((version.major(), version.minor()))
+      } else {
+        None
+      },
+    }
   }
 
   /// Start an HTTP-style response with the given version and status.
@@ -280,18 +489,54 @@ impl<B: BufMut> HttpBuilder<B> {
   /// # }
   /// ```
   pub fn response(
+    buffer: B,
+    version: Version,
+    status: Status,
+  ) -> Result<Self, InsufficientSpaceError> {
+    Self::response_opts(buffer, version, status, ReasonPhrase::Include)
+  }
+
+  /// Start an HTTP-style response with the given version and status,
+  /// choosing whether to include the reason phrase at runtime.
+  ///
+  /// The reason phrase is purely informational and clients must ignore
+  /// it so omitting it saves a few bytes in every response. Unlike the
+  /// `no-reason-phrase` feature this can be chosen for each message.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::response_opts(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(404, "Not Found"),
+  ///     ReasonPhrase::Omit
+  /// )?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(output, b"HTTP/1.1 404 \r\n\r\n");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn response_opts(
     mut buffer: B,
     version: Version,
     status: Status,
+    reason: ReasonPhrase,
   ) -> Result<Self, InsufficientSpaceError> {
+    let start = buffer.remaining_mut();
+
     version.write_to(&mut buffer)?;
     buffer.try_put_u8(b' ')?;
     status.code().write_to(&mut buffer)?;
     buffer.try_put_u8(b' ')?;
-    buffer.try_put_slice(status.reason().as_bytes())?;
+    if let ReasonPhrase::Include = reason {
+      buffer.try_put_slice(status.reason().as_bytes())?;
+    }
     buffer.try_put_slice(&CRLF)?;
 
-    Ok(Self { buffer })
+    Ok(Self::started(buffer, start, Some(status.code()), version))
   }
 
   /// Write out a HTTP header field.
@@ -330,9 +575,493 @@ impl<B: BufMut> HttpBuilder<B> {
     H: Into<Header<'data, V>>,
   {
     header.into().write_to(&mut self.buffer)?;
+    self.headers += 1;
+    Ok(self)
+  }
+
+  /// Write out every HTTP header field produced by `headers`, in order.
+  ///
+  /// If the buffer runs out of space partway through then the headers
+  /// before the one that failed will have already been written.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// const HEADERS: &[Header<&str>] = &[
+  ///   Header::new("Server", "httpencode"),
+  ///   Header::new("Content-Type", "text/json"),
+  /// ];
+  ///
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(418, "I'm a Teapot")
+  /// )?;
+  /// builder.headers(HEADERS.iter().copied())?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 418 I'm a Teapot\r\n\
+  ///   Server: httpencode\r\n\
+  ///   Content-Type: text/json\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn headers<'data, V, H, I>(
+    &mut self,
+    headers: I,
+  ) -> Result<&mut Self, InsufficientSpaceError>
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+    I: IntoIterator<Item = H>,
+  {
+    for header in headers {
+      self.header(header)?;
+    }
+
+    Ok(self)
+  }
+
+  /// Validate the header field name at runtime and then write out the
+  /// header field.
+  ///
+  /// # Errors
+  /// Returns an error if `field` is not a valid HTTP header field name or
+  /// if there is not enough space in the buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), HttpEncodeError> {
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(418, "I'm a Teapot")
+  /// )?;
+  /// builder.try_header("X-Custom", &b"value"[..])?;
+  /// assert!(builder.try_header("Not Valid", "value").is_err());
+  ///
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   output,
+  ///   b"HTTP/1.1 418 I'm a Teapot\r\n\
+  ///   X-Custom: value\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn try_header<V: HttpWriteable>(
+    &mut self,
+    field: &str,
+    value: V,
+  ) -> Result<&mut Self, HttpEncodeError> {
+    let field = CheckedField::try_from(field)?;
+    Ok(self.header(Header::checked_new(field, value))?)
+  }
+
+  /// Write out all the headers within a pre-encoded
+  /// [`HeaderBlock`](crate::HeaderBlock).
+  #[cfg(feature = "std")]
+  pub fn header_block(
+    &mut self,
+    block: &HeaderBlock,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    self.buffer.try_put_slice(block.as_bytes())?;
+    self.headers += block.len();
+    Ok(self)
+  }
+
+  /// Write out a header line that was encoded in advance.
+  ///
+  /// See [`PreEncodedHeader`](crate::PreEncodedHeader) for an example.
+  pub fn header_pre_encoded(
+    &mut self,
+    header: PreEncodedHeader,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    self.buffer.try_put_slice(header.as_bytes())?;
+    self.headers += 1;
+    Ok(self)
+  }
+
+  /// Write out a HTTP header field if `value` is `Some`, otherwise do
+  /// nothing.
+  ///
+  /// # Panics
+  /// Panics if `field` is not a valid HTTP header field name, even if
+  /// `value` is `None`. See the docs for
+  /// [`InvalidHeaderError`](crate::InvalidHeaderError)
+  /// for details.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let etag: Option<&str> = Some("\"abcdef\"");
+  /// let range: Option<&str> = None;
+  ///
+  /// let mut builder = HttpBuilder::request(
+  ///     vec![],
+  ///     Method::GET,
+  ///     Uri::new(b"/"),
+  ///     Version::HTTP_1_1
+  /// )?;
+  /// builder.header_opt("If-None-Match", etag)?;
+  /// builder.header_opt("Range", range)?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "GET / HTTP/1.1\r\n\
+  ///   If-None-Match: \"abcdef\"\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn header_opt<V: HttpWriteable>(
+    &mut self,
+    field: &str,
+    value: Option<V>,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    let field = CheckedField::new(field);
+
+    match value {
+      Some(value) => self.header(Header::checked_new(field, value)),
+      None => Ok(self),
+    }
+  }
+
+  /// Write out the same HTTP header field once for every value in
+  /// `values`, in order.
+  ///
+  /// This is needed for fields such as `Set-Cookie` where the values
+  /// cannot be combined into a single comma-separated list. The field
+  /// name is only validated once.
+  ///
+  /// If the buffer runs out of space partway through then the headers
+  /// before the one that failed will have already been written.
+  ///
+  /// # Panics
+  /// Panics if `field` is not a valid HTTP header field name, even if
+  /// `values` is empty. See the docs for
+  /// [`InvalidHeaderError`](crate::InvalidHeaderError)
+  /// for details.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.header_each("Set-Cookie", &["a=1", "b=2; Secure"])?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Set-Cookie: a=1\r\n\
+  ///   Set-Cookie: b=2; Secure\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn header_each<V, I>(
+    &mut self,
+    field: &str,
+    values: I,
+  ) -> Result<&mut Self, InsufficientSpaceError>
+  where
+    V: HttpWriteable,
+    I: IntoIterator<Item = V>,
+  {
+    let field = CheckedField::try_from(field)
+      .expect("Header field contained invalid character");
+
+    for value in values {
+      self.header(Header::checked_new(field, value))?;
+    }
+
+    Ok(self)
+  }
+
+  /// Write out a HTTP header field whose value is produced piece by
+  /// piece by `f`.
+  ///
+  /// This avoids having to assemble very large values, such as big
+  /// `Cookie` headers or signatures, in a scratch buffer first. Line
+  /// breaks within the value are folded as it is written.
+  ///
+  /// If `f` returns an error then the header will have been partially
+  /// written out.
+  ///
+  /// # Panics
+  /// Panics if `field` is not a valid HTTP header field name. See the
+  /// docs for [`InvalidHeaderError`](crate::InvalidHeaderError) for
+  /// details.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let cookies = [("a", "1"), ("b", "2")];
+  ///
+  /// let mut builder = HttpBuilder::request(
+  ///     vec![],
+  ///     Method::GET,
+  ///     Uri::new(b"/"),
+  ///     Version::HTTP_1_1
+  /// )?;
+  /// builder.header_with("Cookie", |w| {
+  ///   for (idx, (name, value)) in cookies.iter().enumerate() {
+  ///     if idx != 0 {
+  ///       w.put_str("; ")?;
+  ///     }
+  ///     w.put_str(name)?.put_u8(b'=')?.put_str(value)?;
+  ///   }
+  ///   Ok(())
+  /// })?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "GET / HTTP/1.1\r\n\
+  ///   Cookie: a=1; b=2\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn header_with<F>(
+    &mut self,
+    field: &str,
+    f: F,
+  ) -> Result<&mut Self, InsufficientSpaceError>
+  where
+    F: FnOnce(&mut ValueWriter<'_, B>) -> Result<(), InsufficientSpaceError>,
+  {
+    let field = CheckedField::try_from(field)
+      .expect("Header field contained invalid character");
+
+    self.buffer.try_put_slice(field.as_str().as_bytes())?;
+    self.buffer.try_put_slice(b": ")?;
+
+    let mut writer = ValueWriter::new(&mut self.buffer);
+    f(&mut writer)?;
+    writer.finish()?;
+
+    self.buffer.try_put_slice(&CRLF)?;
+    self.headers += 1;
     Ok(self)
   }
 
+  /// Limit the total size of the HTTP header section, including the
+  /// start line and the blank line that terminates it, to `size` bytes.
+  ///
+  /// Once set, any write that would make the header section larger than
+  /// `size` will fail with an
+  /// [`InsufficientSpaceError`](crate::InsufficientSpaceError), even if
+  /// the underlying buffer has more room. The body written by
+  /// [`body`](crate::HttpBuilder::body) is not counted towards the limit.
+  ///
+  /// If the builder was created with
+  /// [`from_buffer`](crate::HttpBuilder::from_buffer) then the size is
+  /// counted from the point where the buffer was passed in.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::request(
+  ///     vec![],
+  ///     Method::GET,
+  ///     Uri::new(b"/"),
+  ///     Version::HTTP_1_1
+  /// )?;
+  /// builder.max_head_size(40);
+  ///
+  /// builder.header(Header::new("Host", "example.com"))?;
+  /// assert!(builder.header(Header::new("Accept", "text/html")).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn max_head_size(&mut self, size: usize) -> &mut Self {
+    let limit = size.saturating_sub(self.bytes_written());
+    self.buffer.set_limit(limit);
+    self
+  }
+
+  /// Write out a `Connection` header requesting that the connection
+  /// either be kept open or closed after this message.
+  ///
+  /// HTTP/1.1 connections are persistent by default while HTTP/1.0
+  /// connections are not so the header is only written when `keep_alive`
+  /// differs from the default for the version this message was started
+  /// with. If the builder was created with
+  /// [`from_buffer`](crate::HttpBuilder::from_buffer) then the version
+  /// is unknown and the header is always written.
+  ///
+  /// HTTP/2 and later forbid connection-specific header fields such as
+  /// `Connection` (RFC 9113 section 8.2.2) so for those versions this
+  /// does nothing.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_0,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.keep_alive(true)?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.0 200 OK\r\n\
+  ///   Connection: keep-alive\r\n\
+  ///   \r\n"
+  /// );
+  ///
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.keep_alive(true)?;
+  /// builder.keep_alive(false)?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Connection: close\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn keep_alive(
+    &mut self,
+    keep_alive: bool,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    const CONNECTION: CheckedField = CheckedField::new("Connection");
+
+    let persistent_default = match self.version {
+      Some((major, _)) if major >= 2 => return Ok(self),
+      // HTTP/1.1 defaults to persistent connections.
+      version => version.map(|version| version >= (1, 1)),
+    };
+
+    if persistent_default == Some(keep_alive) {
+      return Ok(self);
+    }
+
+    let value = match keep_alive {
+      true => ConnectionOptions::KEEP_ALIVE,
+      false => ConnectionOptions::CLOSE,
+    };
+
+    self.header(Header::checked_new(CONNECTION, value))
+  }
+
+  /// Write out a `Content-Length` header with a fixed-width placeholder
+  /// value that can be filled in once the body has been written.
+  ///
+  /// The placeholder is initially `0` padded with trailing whitespace.
+  /// Once the size of the body is known, the returned
+  /// [`ContentLengthPlaceholder`](crate::ContentLengthPlaceholder) can be
+  /// used to overwrite it in the encoded message.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// let placeholder = builder.content_length_placeholder()?;
+  /// let mut output = builder.finish()?;
+  ///
+  /// let head_len = output.len();
+  /// output.extend_from_slice(b"Some body data");
+  /// let body_len = output.len() - head_len;
+  /// placeholder.patch(&mut output, body_len as u64);
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Content-Length: 14                  \r\n\
+  ///   \r\n\
+  ///   Some body data"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn content_length_placeholder(
+    &mut self,
+  ) -> Result<ContentLengthPlaceholder, InsufficientSpaceError> {
+    self
+      .buffer
+      .try_put_slice(CONTENT_LENGTH.as_str().as_bytes())?;
+    self.buffer.try_put_slice(b": ")?;
+
+    let placeholder = ContentLengthPlaceholder::new(self.bytes_written());
+    placeholder.write_to(&mut self.buffer)?;
+    self.buffer.try_put_slice(&CRLF)?;
+    self.headers += 1;
+
+    Ok(placeholder)
+  }
+
+  /// The number of bytes that have been written out since the start of
+  /// the message.
+  ///
+  /// If the builder was created with
+  /// [`from_buffer`](crate::HttpBuilder::from_buffer) then this counts
+  /// from the point where the buffer was passed in.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::request(
+  ///     vec![],
+  ///     Method::GET,
+  ///     Uri::new(b"/"),
+  ///     Version::HTTP_1_1
+  /// )?;
+  /// assert_eq!(builder.bytes_written(), 16);
+  ///
+  /// builder.header(Header::new("Host", "example.com"))?;
+  /// assert_eq!(builder.bytes_written(), 35);
+  /// assert_eq!(builder.header_count(), 1);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn bytes_written(&self) -> usize {
+    self.start - self.buffer.get_ref().remaining_mut()
+  }
+
+  /// The number of header fields that have been written out by this
+  /// builder.
+  pub fn header_count(&self) -> usize {
+    self.headers
+  }
+
   /// Finish off the HTTP header and return the `BufMut` instance that
   /// was being written to.
   ///
@@ -340,7 +1069,91 @@ impl<B: BufMut> HttpBuilder<B> {
   /// if desired.
   pub fn finish(mut self) -> Result<B, InsufficientSpaceError> {
     self.buffer.try_put_slice(&CRLF)?;
-    Ok(self.buffer)
+    Ok(self.buffer.into_inner())
+  }
+
+  /// Write out a `Content-Length` header matching `body`, finish off
+  /// the HTTP header, and then copy `body` into the buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.header(Header::new("Content-Type", "text/plain"))?;
+  /// let output = builder.body(b"Hello World!")?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Content-Type: text/plain\r\n\
+  ///   Content-Length: 12\r\n\
+  ///   \r\n\
+  ///   Hello World!"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn body(mut self, body: &[u8]) -> Result<B, InsufficientSpaceError> {
+    self.header(Header::checked_new(CONTENT_LENGTH, body.len()))?;
+    let mut buffer = self.finish()?;
+    buffer.try_put_slice(body)?;
+
+    Ok(buffer)
+  }
+
+  /// Finish off the HTTP header of a message without a body.
+  ///
+  /// This writes out `Content-Length: 0` before the blank line ending
+  /// the header so that the receiver doesn't wait for a body. Responses
+  /// with a `1xx`, `204 No Content`, or `304 Not Modified` status never
+  /// have a body so the `Content-Length` header is omitted for those.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// let output = builder.finish_empty()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 200 OK\r\n\
+  ///   Content-Length: 0\r\n\
+  ///   \r\n"
+  /// );
+  ///
+  /// let builder = HttpBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(204, "No Content")
+  /// )?;
+  /// let output = builder.finish_empty()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 204 No Content\r\n\r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn finish_empty(mut self) -> Result<B, InsufficientSpaceError> {
+    match self.status {
+      Some(100..=199) | Some(204) | Some(304) => (),
+      _ => {
+        self.header(Header::checked_new(CONTENT_LENGTH, 0))?;
+      }
+    }
+
+    self.finish()
   }
 
   /// Construct an HttpBuilder from an existing stream without writing
@@ -366,7 +1179,13 @@ impl<B: BufMut> HttpBuilder<B> {
   /// # }
   /// ```
   pub fn from_buffer(buffer: B) -> Self {
-    Self { buffer }
+    Self {
+      start: buffer.remaining_mut(),
+      buffer: buffer.limit(usize::MAX),
+      headers: 0,
+      status: None,
+      version: None,
+    }
   }
 
   /// Return the existing buffer without adding the extra blank line
@@ -375,34 +1194,34 @@ impl<B: BufMut> HttpBuilder<B> {
   /// This can be used in combination with `from_buffer` to inject
   /// custom data into the middle of an HTTP request/response.
   pub fn into_inner(self) -> B {
-    self.buffer
+    self.buffer.into_inner()
   }
 }
 
-const fn is_token(token: &str) -> bool {
-  // According to RFC 7230 this is the valid set of chars in a token.
-  //
-  // token = 1*tchar
-  // tchar = "!" / "#" / "$" / "%" / "&" / "'" / "*"
-  //       / "+" / "-" / "." / "^" / "_" / "`" / "|" / "~"
-  //       / DIGIT / ALPHA
-  //       ; any VCHAR, except delimiters
-  const fn is_allowed(byte: u8) -> bool {
-    const MASK: u128 = 0x57FFFFFFC7FFFFFE03FF2CFA00000000u128;
-    const MASKLO: u64 = MASK as u64;
-    const MASKHI: u64 = (MASK >> 64) as u64;
-
-    match byte {
-      0..=63 => (MASKLO >> byte) & 1 == 1,
-      64..=127 => (MASKHI >> (byte & 63)) & 1 == 1,
-      _ => false,
-    }
+// According to RFC 7230 this is the valid set of chars in a token.
+//
+// token = 1*tchar
+// tchar = "!" / "#" / "$" / "%" / "&" / "'" / "*"
+//       / "+" / "-" / "." / "^" / "_" / "`" / "|" / "~"
+//       / DIGIT / ALPHA
+//       ; any VCHAR, except delimiters
+const fn is_tchar(byte: u8) -> bool {
+  const MASK: u128 = 0x57FFFFFFC7FFFFFE03FF6CFA00000000u128;
+  const MASKLO: u64 = MASK as u64;
+  const MASKHI: u64 = (MASK >> 64) as u64;
+
+  match byte {
+    0..=63 => (MASKLO >> byte) & 1 == 1,
+    64..=127 => (MASKHI >> (byte & 63)) & 1 == 1,
+    _ => false,
   }
+}
 
+const fn is_token(token: &str) -> bool {
   let mut i = 0;
   let bytes = token.as_bytes();
   while i < bytes.len() {
-    if !is_allowed(bytes[i]) {
+    if !is_tchar(bytes[i]) {
       return false;
     }
     i += 1;
@@ -411,6 +1230,44 @@ const fn is_token(token: &str) -> bool {
   !bytes.is_empty()
 }
 
+/// Lookup table version of `is_tchar`.
+static TCHAR_TABLE: [bool; 256] = {
+  let mut table = [false; 256];
+  let mut i = 0;
+  while i < 256 {
+    table[i] = is_tchar(i as u8);
+    i += 1;
+  }
+  table
+};
+
+/// Runtime-only version of `is_token`.
+///
+/// This checks 16 bytes at a time without branching on each byte which
+/// allows the compiler to unroll and vectorize the inner loop. It should
+/// be used instead of `is_token` anywhere that doesn't need to be const.
+fn is_token_fast(token: &[u8]) -> bool {
+  const CHUNK: usize = 16;
+
+  let mut chunks = token.chunks_exact(CHUNK);
+  for chunk in &mut chunks {
+    let valid = chunk
+      .iter()
+      .fold(true, |valid, &byte| valid & TCHAR_TABLE[byte as usize]);
+
+    if !valid {
+      return false;
+    }
+  }
+
+  let remainder = chunks.remainder();
+  let valid = remainder
+    .iter()
+    .fold(true, |valid, &byte| valid & TCHAR_TABLE[byte as usize]);
+
+  valid && !token.is_empty()
+}
+
 /// Validates that the uri doesn't contain space, CR, or LF
 const fn validate_uri(uri: &[u8]) -> bool {
   let mut i = 0;
@@ -423,3 +1280,62 @@ const fn validate_uri(uri: &[u8]) -> bool {
 
   !uri.is_empty()
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keep_alive_is_noop_for_multiplexed_versions() {
+    for version in &[Version::HTTP_2, Version::HTTP_3] {
+      for &keep_alive in &[true, false] {
+        let mut builder = HttpBuilder::response(
+          vec![],
+          *version,
+          Status::with_reason(200, "OK"),
+        )
+        .unwrap();
+        builder.keep_alive(keep_alive).unwrap();
+        let output = builder.finish().unwrap();
+
+        assert!(!output.windows(11).any(|w| w == b"Connection:"));
+      }
+    }
+  }
+
+  #[test]
+  fn tchars() {
+    for byte in 0..=255u8 {
+      let expected =
+        byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte);
+      assert_eq!(is_tchar(byte), expected, "{:?}", byte as char);
+    }
+  }
+
+  #[test]
+  fn dotted_tokens() {
+    assert!(CheckedField::try_new("X-Forwarded.For").is_ok());
+    assert!(Method::try_new("M.SEARCH").is_ok());
+    assert!(is_token("1.1"));
+    assert!(is_token_fast(b"1.1"));
+  }
+
+  #[test]
+  fn token_fast_matches_const() {
+    assert!(!is_token_fast(b""));
+
+    for byte in 0..=255u8 {
+      for len in &[1, 15, 16, 17, 33] {
+        let mut token = [b'a'; 33];
+        let token = &mut token[..*len];
+        token[len - 1] = byte;
+
+        let expected = is_tchar(byte);
+        assert_eq!(is_token_fast(token), expected, "{:?}", token);
+        token[len - 1] = b'a';
+        token[0] = byte;
+        assert_eq!(is_token_fast(token), expected, "{:?}", token);
+      }
+    }
+  }
+}
@@ -0,0 +1,236 @@
+use crate::params::{params_size_hint, validate_params, write_params};
+use crate::quoted::{is_plain_text, write_ext_value};
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError, Quoted, Uri,
+};
+
+/// A single link within a `Link` header (RFC 8288).
+///
+/// The target must be a URI-reference made up only of the characters
+/// accepted by [`Uri::try_new_strict`](crate::Uri::try_new_strict). The
+/// relation type is always written out quoted. Titles that are printable
+/// ASCII are written out as a quoted `title` parameter, all others as an
+/// RFC 8187 UTF-8 `title*` parameter.
+///
+/// A header listing several links can be written out by wrapping them in
+/// [`Separated`](crate::Separated).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const LINKS: [Link; 2] = [
+///   Link::new(b"/items?page=3", "next"),
+///   Link::new(b"/items?page=1", "prev").title("Première page"),
+/// ];
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Link", Separated::new(&LINKS)))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Link: </items?page=3>; rel=\"next\", \
+///   </items?page=1>; rel=\"prev\"; title*=UTF-8''Premi%C3%A8re%20page\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Link<'data> {
+  uri: Uri<'data>,
+  rel: &'data str,
+  title: Option<&'data str>,
+  params: &'data [(&'data str, &'data str)],
+}
+
+impl<'data> Link<'data> {
+  /// Create a link to `uri` with the relation type `rel`.
+  ///
+  /// Multiple relation types can be given by separating them with
+  /// spaces, e.g. `"preload prefetch"`.
+  ///
+  /// # Errors
+  /// Returns an error if `uri` contains any character not allowed within
+  /// a URI or if `rel` is empty or contains a control character other
+  /// than HTAB.
+  pub const fn try_new(
+    uri: &'data [u8],
+    rel: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    let uri = match Uri::try_new_strict(uri) {
+      Ok(uri) => uri,
+      Err(_) => return Err(InvalidHeaderError(())),
+    };
+
+    if rel.is_empty() || Quoted::try_new(rel.as_bytes()).is_err() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      uri,
+      rel,
+      title: None,
+      params: &[],
+    })
+  }
+
+  /// Create a link to `uri` with the relation type `rel`.
+  ///
+  /// # Panics
+  /// Panics if `uri` contains any character not allowed within a URI or
+  /// if `rel` is empty or contains a control character other than HTAB.
+  pub const fn new(uri: &'data [u8], rel: &'data str) -> Self {
+    match Self::try_new(uri, rel) {
+      Ok(link) => link,
+      Err(_) => const_panic!("Invalid link target or relation type"),
+    }
+  }
+
+  /// Set a human-readable title for the link.
+  pub const fn title(self, title: &'data str) -> Self {
+    Self {
+      title: Some(title),
+      ..self
+    }
+  }
+
+  /// Set extra target attributes, such as `as=style` for preload links.
+  ///
+  /// # Errors
+  /// Returns an error if any parameter name is not a token or if any
+  /// parameter value contains a control character other than HTAB.
+  pub const fn try_params(
+    self,
+    params: &'data [(&'data str, &'data str)],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !validate_params(params) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { params, ..self })
+  }
+
+  /// Set extra target attributes, such as `as=style` for preload links.
+  ///
+  /// # Panics
+  /// Panics if any parameter name is not a token or if any parameter
+  /// value contains a control character other than HTAB.
+  pub const fn params(self, params: &'data [(&'data str, &'data str)]) -> Self {
+    match self.try_params(params) {
+      Ok(link) => link,
+      Err(_) => const_panic!("Invalid link parameter"),
+    }
+  }
+
+  /// The target of the link.
+  pub const fn uri(&self) -> Uri<'data> {
+    self.uri
+  }
+
+  /// The relation type of the link.
+  pub const fn rel(&self) -> &'data str {
+    self.rel
+  }
+}
+
+impl HttpWriteable for Link<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_u8(b'<')?;
+    buffer.try_put_slice(self.uri.as_bytes())?;
+    buffer.try_put_slice(b">; rel=")?;
+    // The relation type was checked when this was created.
+    Quoted(self.rel.as_bytes()).write_to(buffer)?;
+
+    if let Some(title) = self.title {
+      if is_plain_text(title) {
+        buffer.try_put_slice(b"; title=")?;
+        Quoted(title.as_bytes()).write_to(buffer)?;
+      } else {
+        buffer.try_put_slice(b"; title*=")?;
+        write_ext_value(buffer, title)?;
+      }
+    }
+
+    write_params(buffer, self.params)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let uri = self.uri.as_bytes().len();
+    let (params, params_upper) = params_size_hint(self.params);
+    let lower = uri + self.rel.len() + "<>; rel=\"\"".len() + params;
+
+    // The relation type might be fully escaped and every byte of the
+    // title might be percent-encoded.
+    let title = self.title.map(str::len).unwrap_or(0);
+    let upper = title
+      .checked_mul(3)
+      .and_then(|len| len.checked_add("; title*=UTF-8''".len()))
+      .and_then(|len| len.checked_add(self.rel.len()))
+      .and_then(|len| len.checked_add(lower - params))
+      .and_then(|len| len.checked_add(params_upper?));
+
+    (lower, upper)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+  use crate::Separated;
+
+  #[test]
+  fn single() {
+    assert_eq!(
+      encode_str(Link::new(b"https://example.com/a", "next")),
+      "<https://example.com/a>; rel=\"next\""
+    );
+    assert_eq!(
+      encode_str(Link::new(b"/", "index").title("Home \"page\"")),
+      "</>; rel=\"index\"; title=\"Home \\\"page\\\"\""
+    );
+    assert_eq!(
+      encode_str(
+        Link::new(b"/style.css", "preload").params(&[("as", "style")])
+      ),
+      "</style.css>; rel=\"preload\"; as=style"
+    );
+  }
+
+  #[test]
+  fn list() {
+    let links = [
+      Link::new(b"/a", "preload").title("€"),
+      Link::new(b"/b", "preload prefetch"),
+    ];
+
+    assert_eq!(
+      encode_str(Separated::new(&links)),
+      "</a>; rel=\"preload\"; title*=UTF-8''%E2%82%AC, \
+       </b>; rel=\"preload prefetch\""
+    );
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(Link::try_new(b"", "next").is_err());
+    assert!(Link::try_new(b"/a>; rel=x", "next").is_err());
+    assert!(Link::try_new(b"/a b", "next").is_err());
+    assert!(Link::try_new(b"/a", "").is_err());
+    assert!(Link::try_new(b"/a", "next\r\nX: y").is_err());
+    assert!(Link::new(b"/a", "next")
+      .try_params(&[("a b", "c")])
+      .is_err());
+  }
+}
@@ -0,0 +1,106 @@
+use crate::BufMut;
+use core::mem::MaybeUninit;
+
+/// A [`BufMut`](crate::BufMut) that discards everything written to it
+/// and only keeps track of how many bytes were written.
+///
+/// This can be used to calculate the exact size of a message before
+/// encoding it for real, so that the destination buffer can be allocated
+/// or reserved once up front.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// fn encode<B: BufMut>(buffer: B) -> Result<B, InsufficientSpaceError> {
+///   let mut builder = response(buffer, Version::HTTP_1_1, Status::OK)?;
+///   builder.header(Header::new("Content-Type", "text/plain"))?;
+///   builder.finish()
+/// }
+///
+/// let size = encode(CountingBufMut::new())?.count();
+/// let output = encode(Vec::with_capacity(size))?;
+///
+/// assert_eq!(output.len(), size);
+/// # Ok(())
+/// # }
+/// ```
+pub struct CountingBufMut {
+  count: usize,
+  // BufMut requires that we hand out writable memory so anything that
+  // isn't handled by the overridden methods below gets written here.
+  scratch: [MaybeUninit<u8>; 64],
+}
+
+impl CountingBufMut {
+  /// Create a new `CountingBufMut` with a count of 0.
+  pub const fn new() -> Self {
+    Self {
+      count: 0,
+      scratch: [MaybeUninit::uninit(); 64],
+    }
+  }
+
+  /// The number of bytes that have been written to this buffer.
+  pub const fn count(&self) -> usize {
+    self.count
+  }
+}
+
+impl Default for CountingBufMut {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl core::fmt::Debug for CountingBufMut {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("CountingBufMut")
+      .field("count", &self.count)
+      .finish()
+  }
+}
+
+impl BufMut for CountingBufMut {
+  #[inline]
+  fn remaining_mut(&self) -> usize {
+    usize::MAX - self.count
+  }
+
+  #[inline]
+  unsafe fn advance_mut(&mut self, cnt: usize) {
+    self.count += cnt;
+  }
+
+  #[inline]
+  fn bytes_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+    &mut self.scratch
+  }
+
+  #[inline]
+  fn put_slice(&mut self, src: &[u8]) {
+    self.count += src.len();
+  }
+
+  #[inline]
+  fn put_u8(&mut self, _: u8) {
+    self.count += 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn counts_generic_puts() {
+    let mut buffer = CountingBufMut::new();
+
+    buffer.put_slice(&[0; 100]);
+    buffer.put_u8(0);
+    buffer.put_u32(0);
+    buffer.put(&[0u8; 200][..]);
+
+    assert_eq!(buffer.count(), 305);
+  }
+}
@@ -1,5 +1,9 @@
+use core::convert::TryFrom;
+use core::fmt;
+
 use crate::{
-  is_token, BufMut, FallibleBufMut, InsufficientSpaceError, InvalidMethodError,
+  is_token, is_token_fast, BufMut, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidMethodError,
 };
 
 /// HTTP Method.
@@ -7,19 +11,12 @@ use crate::{
 /// Unless you want to create non-standard methods for a request
 /// then most users of this class should just use the method
 /// constants provided.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Method<'data> {
   method: &'data str,
 }
 
 impl<'data> Method<'data> {
-  pub(crate) fn write_to<B: BufMut>(
-    &self,
-    buffer: &mut B,
-  ) -> Result<(), InsufficientSpaceError> {
-    buffer.try_put_slice(self.method.as_bytes())
-  }
-
   /// Get the string representation of this `Method`.
   #[inline]
   pub const fn as_str(&self) -> &'data str {
@@ -37,14 +34,126 @@ impl<'data> Method<'data> {
   /// HTTP DELETE.
   pub const DELETE: Self = Self::new("DELETE");
   /// HTTP CONNECT.
-  pub const CONNNECT: Self = Self::new("CONNECT");
+  pub const CONNECT: Self = Self::new("CONNECT");
+  /// HTTP CONNECT.
+  #[deprecated(note = "Misspelled, use Method::CONNECT instead")]
+  pub const CONNNECT: Self = Self::CONNECT;
   /// HTTP OPTIONS.
   pub const OPTIONS: Self = Self::new("OPTIONS");
   /// HTTP TRACE.
   pub const TRACE: Self = Self::new("TRACE");
+  /// HTTP PATCH (RFC 5789).
+  pub const PATCH: Self = Self::new("PATCH");
+  /// HTTP QUERY (draft-ietf-httpbis-safe-method-w-body).
+  pub const QUERY: Self = Self::new("QUERY");
+
+  /// WebDAV PROPFIND (RFC 4918).
+  pub const PROPFIND: Self = Self::new("PROPFIND");
+  /// WebDAV PROPPATCH (RFC 4918).
+  pub const PROPPATCH: Self = Self::new("PROPPATCH");
+  /// WebDAV MKCOL (RFC 4918).
+  pub const MKCOL: Self = Self::new("MKCOL");
+  /// WebDAV COPY (RFC 4918).
+  pub const COPY: Self = Self::new("COPY");
+  /// WebDAV MOVE (RFC 4918).
+  pub const MOVE: Self = Self::new("MOVE");
+  /// WebDAV LOCK (RFC 4918).
+  pub const LOCK: Self = Self::new("LOCK");
+  /// WebDAV UNLOCK (RFC 4918).
+  pub const UNLOCK: Self = Self::new("UNLOCK");
+  /// WebDAV REPORT (RFC 3253).
+  pub const REPORT: Self = Self::new("REPORT");
+
+  /// Whether this method is safe (read-only) according to the IANA HTTP
+  /// method registry.
+  ///
+  /// Unregistered methods are not considered safe.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Method::GET.is_safe());
+  /// assert!(!Method::POST.is_safe());
+  /// ```
+  pub fn is_safe(&self) -> bool {
+    matches!(
+      self.method,
+      "GET"
+        | "HEAD"
+        | "OPTIONS"
+        | "TRACE"
+        | "QUERY"
+        | "PROPFIND"
+        | "REPORT"
+        | "SEARCH"
+    )
+  }
+
+  /// Whether this method is idempotent according to the IANA HTTP method
+  /// registry.
+  ///
+  /// All safe methods are idempotent. Unregistered methods are not
+  /// considered idempotent.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Method::PUT.is_idempotent());
+  /// assert!(!Method::PATCH.is_idempotent());
+  /// ```
+  pub fn is_idempotent(&self) -> bool {
+    self.is_safe()
+      || matches!(
+        self.method,
+        "PUT"
+          | "DELETE"
+          | "PROPPATCH"
+          | "MKCOL"
+          | "COPY"
+          | "MOVE"
+          | "UNLOCK"
+          | "ACL"
+          | "BIND"
+          | "UNBIND"
+          | "REBIND"
+          | "MKCALENDAR"
+          | "MKWORKSPACE"
+          | "MKACTIVITY"
+          | "MKREDIRECTREF"
+          | "ORDERPATCH"
+          | "UPDATE"
+          | "UPDATEREDIRECTREF"
+          | "VERSION-CONTROL"
+          | "CHECKIN"
+          | "CHECKOUT"
+          | "UNCHECKOUT"
+          | "BASELINE-CONTROL"
+          | "LABEL"
+          | "MERGE"
+      )
+  }
+
+  /// Whether responses to this method can be cached, as defined by
+  /// RFC 7231 section 4.2.3.
+  ///
+  /// Note that responses to `POST` are only cacheable when they include
+  /// explicit freshness information.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Method::HEAD.is_cacheable());
+  /// assert!(!Method::PUT.is_cacheable());
+  /// ```
+  pub fn is_cacheable(&self) -> bool {
+    matches!(self.method, "GET" | "HEAD" | "POST" | "QUERY")
+  }
 
   /// Create a custom method from a method string.
   ///
+  /// If this method is not being used in a const context then
+  /// `Method::try_from` should be preferred as it will likely be faster.
+  ///
   /// # Errors
   /// Errors if the method is not a sytactically valid
   /// method (Method must be a token as per RFC 7320).
@@ -80,6 +189,78 @@ impl<'data> Method<'data> {
   }
 }
 
+/// Writes out the method name, e.g. for use in an `Allow` header.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(405, "Method Not Allowed")
+/// )?;
+/// builder.header(Header::new("Allow", Method::GET))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 405 Method Not Allowed\r\n\
+///   Allow: GET\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl HttpWriteable for Method<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.method.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.method.len(), Some(self.method.len()))
+  }
+}
+
+impl fmt::Display for Method<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.method)
+  }
+}
+
+impl<'data> TryFrom<&'data str> for Method<'data> {
+  type Error = InvalidMethodError;
+
+  /// Equivalent to [`Method::try_new`](crate::Method::try_new) but faster
+  /// since it doesn't need to be `const`.
+  fn try_from(method: &'data str) -> Result<Self, Self::Error> {
+    if !is_token_fast(method.as_bytes()) {
+      return Err(InvalidMethodError(()));
+    }
+
+    Ok(Self { method })
+  }
+}
+
+impl<'data> TryFrom<&'data [u8]> for Method<'data> {
+  type Error = InvalidMethodError;
+
+  /// Create a custom method from a byte string.
+  ///
+  /// A valid method is always ASCII so any byte string that is not valid
+  /// UTF-8 is rejected.
+  fn try_from(method: &'data [u8]) -> Result<Self, Self::Error> {
+    match core::str::from_utf8(method) {
+      Ok(method) => Self::try_from(method),
+      Err(_) => Err(InvalidMethodError(())),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -91,6 +272,57 @@ mod tests {
     assert_eq!(method.as_str(), "FOO");
   }
 
+  #[test]
+  fn method_eq() {
+    assert_eq!(Method::GET, Method::new("GET"));
+    assert_ne!(Method::GET, Method::new("get"));
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn method_display() {
+    assert_eq!(Method::PROPFIND.to_string(), "PROPFIND");
+  }
+
+  #[test]
+  fn method_classification() {
+    let methods = [
+      Method::GET,
+      Method::HEAD,
+      Method::POST,
+      Method::PUT,
+      Method::DELETE,
+      Method::CONNECT,
+      Method::OPTIONS,
+      Method::TRACE,
+      Method::PATCH,
+      Method::LOCK,
+      Method::new("get"),
+    ];
+
+    for method in &methods {
+      // Every safe method must also be idempotent.
+      assert!(!method.is_safe() || method.is_idempotent());
+    }
+
+    assert!(!Method::CONNECT.is_idempotent());
+    assert!(!Method::LOCK.is_idempotent());
+    assert!(Method::MOVE.is_idempotent());
+    assert!(Method::POST.is_cacheable());
+
+    // Methods are case-sensitive.
+    assert!(!Method::new("get").is_safe());
+  }
+
+  #[test]
+  fn method_try_from() {
+    assert_eq!(Method::try_from("GET").unwrap().as_str(), "GET");
+    assert_eq!(Method::try_from(&b"GET"[..]).unwrap().as_str(), "GET");
+
+    assert!(Method::try_from("G T").is_err());
+    assert!(Method::try_from(&b"\xFF"[..]).is_err());
+  }
+
   #[test]
   fn method_new_unchecked_roundtrip() {
     // Ok as long as it doesn't go into a request.
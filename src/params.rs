@@ -0,0 +1,221 @@
+use crate::{
+  is_tchar, is_token, is_token_fast, BufMut, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError, Quoted,
+};
+
+/// Whether `value` is either a token or two tokens separated by a `/`,
+/// as in a media type.
+const fn is_parameterized_value(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  let mut slash = None;
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'/' if slash.is_none() => slash = Some(idx),
+      byte if is_tchar(byte) => (),
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  match slash {
+    Some(slash) => slash != 0 && slash + 1 != bytes.len(),
+    None => !bytes.is_empty(),
+  }
+}
+
+/// Whether every parameter name is a token and every parameter value can
+/// be written out as a quoted-string.
+pub(crate) const fn validate_params(params: &[(&str, &str)]) -> bool {
+  let mut idx = 0;
+  while idx < params.len() {
+    let (name, value) = params[idx];
+
+    if !is_token(name) || Quoted::try_new(value.as_bytes()).is_err() {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  true
+}
+
+/// Write out each parameter as `; name=value`, quoting the value if it
+/// is not a token. The parameters must have been checked with
+/// `validate_params`.
+pub(crate) fn write_params<B: BufMut>(
+  buffer: &mut B,
+  params: &[(&str, &str)],
+) -> Result<(), InsufficientSpaceError> {
+  for &(name, value) in params {
+    buffer.try_put_slice(b"; ")?;
+    buffer.try_put_slice(name.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+
+    if is_token_fast(value.as_bytes()) {
+      buffer.try_put_slice(value.as_bytes())?;
+    } else {
+      Quoted(value.as_bytes()).write_to(buffer)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// The size hint for the output of `write_params`.
+pub(crate) fn params_size_hint(
+  params: &[(&str, &str)],
+) -> (usize, Option<usize>) {
+  let lower = params
+    .iter()
+    .map(|(name, value)| name.len() + value.len() + 3)
+    .fold(0, usize::saturating_add);
+  // Every parameter value might need to be quoted and fully escaped.
+  let quoting = params
+    .iter()
+    .map(|(_, value)| value.len().checked_add(2))
+    .try_fold(0usize, |acc, len| acc.checked_add(len?));
+
+  (
+    lower,
+    quoting.and_then(|quoting| quoting.checked_add(lower)),
+  )
+}
+
+/// A header value followed by a list of `key=value` parameters, as used
+/// in `Content-Type`, `Content-Disposition`, and `Accept`.
+///
+/// Parameter values that are valid tokens are written out as-is while
+/// all others are written out as a [`Quoted`](crate::Quoted) string.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const CONTENT_TYPE: Parameterized = Parameterized::new(
+///   "multipart/form-data",
+///   &[("charset", "utf-8"), ("boundary", "a boundary")],
+/// );
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Content-Type", CONTENT_TYPE))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Content-Type: multipart/form-data; charset=utf-8; boundary=\"a boundary\"\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Parameterized<'data> {
+  value: &'data str,
+  params: &'data [(&'data str, &'data str)],
+}
+
+impl<'data> Parameterized<'data> {
+  /// Create a `Parameterized` value from a base value and its parameters.
+  ///
+  /// # Errors
+  /// Returns an error if `value` is not a token or a `type/subtype` pair
+  /// of tokens, if any parameter name is not a token, or if any
+  /// parameter value contains a control character other than HTAB.
+  pub const fn try_new(
+    value: &'data str,
+    params: &'data [(&'data str, &'data str)],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_parameterized_value(value) || !validate_params(params) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { value, params })
+  }
+
+  /// Create a `Parameterized` value from a base value and its parameters.
+  ///
+  /// # Panics
+  /// Panics if `value` is not a token or a `type/subtype` pair of tokens,
+  /// if any parameter name is not a token, or if any parameter value
+  /// contains a control character other than HTAB.
+  pub const fn new(
+    value: &'data str,
+    params: &'data [(&'data str, &'data str)],
+  ) -> Self {
+    match Self::try_new(value, params) {
+      Ok(value) => value,
+      Err(_) => const_panic!("Invalid parameterized header value"),
+    }
+  }
+
+  /// The value that comes before the parameters.
+  pub const fn value(&self) -> &'data str {
+    self.value
+  }
+
+  /// The parameters in the order they are written out.
+  pub const fn params(&self) -> &'data [(&'data str, &'data str)] {
+    self.params
+  }
+}
+
+impl HttpWriteable for Parameterized<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.value.as_bytes())?;
+    // Parameters were checked when this was created.
+    write_params(buffer, self.params)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper) = params_size_hint(self.params);
+    let len = self.value.len();
+
+    (
+      lower.saturating_add(len),
+      upper.and_then(|upper| upper.checked_add(len)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn token_or_quoted() {
+    let value = Parameterized::new(
+      "attachment",
+      &[("filename", r#"a "b".txt"#), ("size", "10"), ("empty", "")],
+    );
+
+    assert_eq!(
+      encode_str(value),
+      r#"attachment; filename="a \"b\".txt"; size=10; empty="""#
+    );
+    assert_eq!(encode_str(Parameterized::new("text/*", &[])), "text/*");
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(Parameterized::try_new("", &[]).is_err());
+    assert!(Parameterized::try_new("text/", &[]).is_err());
+    assert!(Parameterized::try_new("/html", &[]).is_err());
+    assert!(Parameterized::try_new("a/b/c", &[]).is_err());
+    assert!(Parameterized::try_new("text html", &[]).is_err());
+    assert!(Parameterized::try_new("text/html", &[("a b", "c")]).is_err());
+    assert!(Parameterized::try_new("text/html", &[("a", "\r\n")]).is_err());
+  }
+}
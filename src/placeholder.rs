@@ -0,0 +1,92 @@
+use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
+
+// Enough digits to fit any u64.
+const WIDTH: usize = 20;
+
+/// The location of a `Content-Length` value that was written out with a
+/// placeholder value.
+///
+/// This is created by
+/// [`HttpBuilder::content_length_placeholder`](crate::HttpBuilder::content_length_placeholder)
+/// and allows the body to be written out directly after the header
+/// without knowing its size in advance. Once the body has been written
+/// the real length can be filled in using [`patch`](Self::patch).
+///
+/// The value is left-aligned and padded with trailing whitespace which
+/// HTTP parsers will strip from the end of a header value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ContentLengthPlaceholder {
+  offset: usize,
+}
+
+impl ContentLengthPlaceholder {
+  pub(crate) const fn new(offset: usize) -> Self {
+    Self { offset }
+  }
+
+  pub(crate) fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let mut value = [b' '; WIDTH];
+    value[0] = b'0';
+
+    buffer.try_put_slice(&value)
+  }
+
+  /// The offset of the placeholder value from the start of the message.
+  pub const fn offset(&self) -> usize {
+    self.offset
+  }
+
+  /// Overwrite the placeholder value within `message` with `length`.
+  ///
+  /// `message` must start at the same place that the message being built
+  /// started. For a builder created using
+  /// [`HttpBuilder::from_buffer`](crate::HttpBuilder::from_buffer) that is
+  /// wherever the buffer was when it was passed in.
+  ///
+  /// # Panics
+  /// Panics if `message` is too short to contain the placeholder.
+  pub fn patch(&self, message: &mut [u8], length: u64) {
+    let mut slot = &mut message[self.offset..self.offset + WIDTH];
+
+    length
+      .write_to(&mut slot)
+      .expect("Content-Length placeholder was too small");
+
+    for byte in slot {
+      *byte = b' ';
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn patch_max_length() {
+    let placeholder = ContentLengthPlaceholder::new(1);
+    let mut buffer = vec![b'|'];
+    placeholder.write_to(&mut buffer).unwrap();
+    buffer.push(b'|');
+
+    assert_eq!(&buffer, b"|0                   |");
+
+    placeholder.patch(&mut buffer, u64::MAX);
+    assert_eq!(&buffer, b"|18446744073709551615|");
+
+    placeholder.patch(&mut buffer, 5);
+    assert_eq!(&buffer, b"|5                   |");
+  }
+
+  #[test]
+  #[should_panic]
+  fn patch_short_message() {
+    let placeholder = ContentLengthPlaceholder::new(4);
+    let mut buffer = [0u8; 10];
+
+    placeholder.patch(&mut buffer, 0);
+  }
+}
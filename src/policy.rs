@@ -0,0 +1,238 @@
+use core::mem::MaybeUninit;
+
+use crate::util::LineBreakDetector;
+use crate::{
+  BufMut, HttpWriteable, InsufficientSpaceError, InvalidHeaderError,
+};
+
+/// What to do with line breaks that end up within a header value.
+///
+/// By default, any CRLF within a string or byte-string header value that
+/// is not already followed by whitespace has a tab inserted after it so
+/// that the value continues on the next line (obs-fold). RFC 9110
+/// deprecates this and many receivers reject it, so this controls what
+/// happens instead.
+///
+/// A policy can be applied to a single value with
+/// [`apply`](ValuePolicy::apply) or to every header written through a
+/// [`ValidatingBuilder`](crate::ValidatingBuilder) with
+/// [`value_policy`](crate::ValidatingBuilder::value_policy).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// let value = ValuePolicy::ReplaceWithSpace.apply("two\r\nlines")?;
+/// builder.header(Header::new("X-Note", value))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   X-Note: two  lines\r\n\
+///   \r\n"
+/// );
+///
+/// assert!(ValuePolicy::Reject.apply("two\r\nlines").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ValuePolicy {
+  /// Continue the value on the next line using obs-fold. This is the
+  /// behaviour of values written without any policy.
+  #[default]
+  Fold,
+  /// Replace every CR and LF within the value with a space, as RFC 9110
+  /// recommends receivers do with obs-fold. The tab that would otherwise
+  /// be inserted after a CRLF is left out.
+  ReplaceWithSpace,
+  /// Refuse to write values that contain a CR or LF.
+  Reject,
+}
+
+impl ValuePolicy {
+  /// Wrap `value` so that it is written out according to this policy.
+  ///
+  /// # Errors
+  /// Returns an error if this policy is
+  /// [`Reject`](ValuePolicy::Reject) and `value` would write out a CR
+  /// or LF.
+  pub fn apply<V: HttpWriteable>(
+    self,
+    value: V,
+  ) -> Result<Sanitized<V>, InvalidHeaderError> {
+    if self == ValuePolicy::Reject {
+      let mut detector = LineBreakDetector::new();
+      // The detector never runs out of space.
+      let _ = value.write_to(&mut detector);
+
+      if detector.found_line_break() {
+        return Err(InvalidHeaderError(()));
+      }
+    }
+
+    Ok(Sanitized {
+      value,
+      policy: self,
+    })
+  }
+}
+
+/// A header value that is written out according to a
+/// [`ValuePolicy`](crate::ValuePolicy).
+///
+/// This is created by [`ValuePolicy::apply`](crate::ValuePolicy::apply).
+#[derive(Copy, Clone, Debug)]
+pub struct Sanitized<V> {
+  value: V,
+  policy: ValuePolicy,
+}
+
+impl<V> Sanitized<V> {
+  /// The policy that this value is written out with.
+  pub fn policy(&self) -> ValuePolicy {
+    self.policy
+  }
+
+  /// Unwrap the original value.
+  pub fn into_inner(self) -> V {
+    self.value
+  }
+}
+
+impl<V: HttpWriteable> HttpWriteable for Sanitized<V> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    match self.policy {
+      ValuePolicy::ReplaceWithSpace => {
+        self.value.write_to(&mut SpaceReplacer::new(buffer))
+      }
+      // Rejected values were already checked when they were created.
+      ValuePolicy::Fold | ValuePolicy::Reject => self.value.write_to(buffer),
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper) = self.value.size_hint();
+
+    match self.policy {
+      // At most one in every three bytes is a dropped tab.
+      ValuePolicy::ReplaceWithSpace => (lower - lower / 3, upper),
+      ValuePolicy::Fold | ValuePolicy::Reject => (lower, upper),
+    }
+  }
+}
+
+/// A `BufMut` adaptor that replaces every CR and LF written through it
+/// with a space.
+///
+/// Writables insert a tab after any CRLF that isn't already followed by
+/// whitespace so a tab directly after a CRLF is dropped instead of being
+/// left in the middle of the value.
+struct SpaceReplacer<'a, B> {
+  buffer: &'a mut B,
+  prev_cr: bool,
+  after_crlf: bool,
+}
+
+impl<'a, B> SpaceReplacer<'a, B> {
+  fn new(buffer: &'a mut B) -> Self {
+    Self {
+      buffer,
+      prev_cr: false,
+      after_crlf: false,
+    }
+  }
+}
+
+impl<B: BufMut> BufMut for SpaceReplacer<'_, B> {
+  fn remaining_mut(&self) -> usize {
+    self.buffer.remaining_mut()
+  }
+
+  unsafe fn advance_mut(&mut self, cnt: usize) {
+    let bytes = &mut self.buffer.bytes_mut()[..cnt];
+    let mut len = 0;
+
+    for idx in 0..cnt {
+      let byte = bytes[idx].assume_init();
+      let skip = self.after_crlf && byte == b'\t';
+
+      self.after_crlf = self.prev_cr && byte == b'\n';
+      self.prev_cr = byte == b'\r';
+
+      if !skip {
+        bytes[len] = MaybeUninit::new(match byte {
+          b'\r' | b'\n' => b' ',
+          byte => byte,
+        });
+        len += 1;
+      }
+    }
+
+    self.buffer.advance_mut(len)
+  }
+
+  fn bytes_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+    self.buffer.bytes_mut()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode<V: HttpWriteable>(value: V) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    value.write_to(&mut buffer).unwrap();
+    buffer
+  }
+
+  #[test]
+  fn fold_matches_plain_value() {
+    let value = "a\r\nb \"q\r\n\" c";
+    let sanitized = ValuePolicy::Fold.apply(value).unwrap();
+
+    assert_eq!(encode(sanitized), encode(value));
+  }
+
+  #[test]
+  fn replace_with_space() {
+    let sanitized = ValuePolicy::ReplaceWithSpace;
+
+    assert_eq!(encode(sanitized.apply("a\nb\rc").unwrap()), b"a b c");
+    assert_eq!(encode(sanitized.apply(&b"\r\n x"[..]).unwrap()), b"   x");
+    assert_eq!(
+      encode(sanitized.apply("two\r\nlines").unwrap()),
+      b"two  lines"
+    );
+    assert_eq!(encode(sanitized.apply("a\r\n\tb").unwrap()), b"a  b");
+    assert_eq!(encode(sanitized.apply("a\r\n\r\nb").unwrap()), b"a    b");
+
+    // Long values go through BufMut::bytes_mut in several chunks.
+    let long = "\n".repeat(1000);
+    assert_eq!(
+      encode(sanitized.apply(long.as_str()).unwrap()),
+      [b' '; 1000]
+    );
+  }
+
+  #[test]
+  fn reject() {
+    assert!(ValuePolicy::Reject.apply("plain value").is_ok());
+    assert!(ValuePolicy::Reject.apply(1234).is_ok());
+
+    assert!(ValuePolicy::Reject.apply("bare\nlf").is_err());
+    assert!(ValuePolicy::Reject.apply("lone\rcr").is_err());
+    assert!(ValuePolicy::Reject.apply("folded\r\n value").is_err());
+  }
+}
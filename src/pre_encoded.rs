@@ -0,0 +1,186 @@
+use crate::{is_tchar, CheckedField, CheckedValue, InvalidHeaderError, CRLF};
+
+/// A complete header line, including the trailing CRLF, that has been
+/// encoded in advance.
+///
+/// Writing one out to a builder using
+/// [`HttpBuilder::header_pre_encoded`](crate::HttpBuilder::header_pre_encoded)
+/// is a single copy with no validation or formatting at all. The easiest
+/// way to create one is at compile time with the
+/// [`pre_encoded_header!`](crate::pre_encoded_header) macro.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const SERVER: PreEncodedHeader =
+///   pre_encoded_header!("Server", "myserver/1.0");
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header_pre_encoded(SERVER)?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Server: myserver/1.0\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PreEncodedHeader<'data>(&'data [u8]);
+
+impl<'data> PreEncodedHeader<'data> {
+  /// Create a `PreEncodedHeader` from a complete header line of the form
+  /// `<field>:<value>\r\n`.
+  ///
+  /// # Errors
+  /// Returns an error if `line` does not end in a CRLF, if the field name
+  /// is not a valid token, or if the value would not be accepted by
+  /// [`CheckedValue::try_new`](crate::CheckedValue::try_new).
+  pub const fn try_new(line: &'data [u8]) -> Result<Self, InvalidHeaderError> {
+    let len = line.len();
+    if len < CRLF.len() || line[len - 2] != b'\r' || line[len - 1] != b'\n' {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let mut colon = 0;
+    while colon < len && is_tchar(line[colon]) {
+      colon += 1;
+    }
+
+    if colon == 0 || colon == len || line[colon] != b':' {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let (_, value) = line.split_at(colon + 1);
+    let (value, _) = value.split_at(value.len() - CRLF.len());
+
+    match CheckedValue::try_new(value) {
+      Ok(_) => Ok(Self(line)),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Create a `PreEncodedHeader` from a complete header line of the form
+  /// `<field>:<value>\r\n`.
+  ///
+  /// # Panics
+  /// Panics if `line` does not end in a CRLF, if the field name is not a
+  /// valid token, or if the value would not be accepted by
+  /// [`CheckedValue::new`](crate::CheckedValue::new).
+  pub const fn new(line: &'data [u8]) -> Self {
+    match Self::try_new(line) {
+      Ok(header) => header,
+      Err(_) => const_panic!("Invalid pre-encoded header line"),
+    }
+  }
+
+  /// Access the encoded header line, including the trailing CRLF.
+  pub const fn as_bytes(&self) -> &'data [u8] {
+    self.0
+  }
+}
+
+/// Create a [`PreEncodedHeader<'static>`](crate::PreEncodedHeader) from a
+/// field name and a string value by encoding the whole header line at
+/// compile time.
+///
+/// The field name and value are validated the same way as by
+/// [`CheckedField::new`](crate::CheckedField::new) and
+/// [`CheckedValue::new`](crate::CheckedValue::new), so an invalid header
+/// is a compile error when the result is used to initialize a constant.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// const CACHE_CONTROL: PreEncodedHeader =
+///   pre_encoded_header!("Cache-Control", "no-cache");
+///
+/// assert_eq!(CACHE_CONTROL.as_bytes(), b"Cache-Control: no-cache\r\n");
+/// ```
+#[macro_export]
+macro_rules! pre_encoded_header {
+  ($field:expr, $value:expr) => {{
+    const FIELD: $crate::CheckedField<'static> =
+      $crate::CheckedField::new($field);
+    const VALUE: $crate::CheckedValue<'static> =
+      $crate::CheckedValue::new($value.as_bytes());
+    const LEN: usize = FIELD.as_str().len() + VALUE.as_bytes().len() + 4;
+    const LINE: [u8; LEN] = $crate::__pre_encode_header(FIELD, VALUE);
+    const HEADER: $crate::PreEncodedHeader<'static> =
+      $crate::PreEncodedHeader::new(&LINE);
+    HEADER
+  }};
+}
+
+#[doc(hidden)]
+pub const fn __pre_encode_header<const N: usize>(
+  field: CheckedField,
+  value: CheckedValue,
+) -> [u8; N] {
+  let field = field.as_str().as_bytes();
+  let value = value.as_bytes();
+
+  if field.len() + value.len() + 4 != N {
+    const_panic!("Output length does not match the encoded length");
+  }
+
+  let mut out = [0u8; N];
+  let mut j = 0;
+  let mut i = 0;
+  while i < field.len() {
+    out[j] = field[i];
+    i += 1;
+    j += 1;
+  }
+
+  out[j] = b':';
+  out[j + 1] = b' ';
+  j += 2;
+
+  i = 0;
+  while i < value.len() {
+    out[j] = value[i];
+    i += 1;
+    j += 1;
+  }
+
+  out[j] = b'\r';
+  out[j + 1] = b'\n';
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn macro_output() {
+    const EMPTY: PreEncodedHeader = pre_encoded_header!("X-Empty", "");
+    const FOLDED: PreEncodedHeader = pre_encoded_header!("X-F", "a\r\n b");
+
+    assert_eq!(EMPTY.as_bytes(), b"X-Empty: \r\n");
+    assert_eq!(FOLDED.as_bytes(), b"X-F: a\r\n b\r\n");
+  }
+
+  #[test]
+  fn try_new() {
+    assert!(PreEncodedHeader::try_new(b"Server:x\r\n").is_ok());
+    assert!(PreEncodedHeader::try_new(b"Server: x\r\n").is_ok());
+
+    assert!(PreEncodedHeader::try_new(b"").is_err());
+    assert!(PreEncodedHeader::try_new(b"\r\n").is_err());
+    assert!(PreEncodedHeader::try_new(b": x\r\n").is_err());
+    assert!(PreEncodedHeader::try_new(b"Server: x").is_err());
+    assert!(PreEncodedHeader::try_new(b"Server x\r\n").is_err());
+    assert!(PreEncodedHeader::try_new(b"Server\r\n").is_err());
+    assert!(PreEncodedHeader::try_new(b"A: x\r\nB: y\r\n").is_err());
+  }
+}
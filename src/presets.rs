@@ -0,0 +1,112 @@
+use crate::{
+  BufMut, CheckedField, FallibleBufMut, Header, HttpBuilder, HttpWriteable,
+  InsufficientSpaceError, Method, Status, Uri, Version, CRLF,
+};
+
+const LOCATION: CheckedField = CheckedField::new("Location");
+const ALLOW: CheckedField = CheckedField::new("Allow");
+
+/// Convenience constructors for common responses.
+impl<B: BufMut> HttpBuilder<B> {
+  /// Start a redirect response with the given status that points the
+  /// client at `location`.
+  ///
+  /// `status` should be one of the 3xx redirect statuses (e.g.
+  /// [`Status::FOUND`](crate::Status::FOUND) or
+  /// [`Status::SEE_OTHER`](crate::Status::SEE_OTHER)) but this is not
+  /// checked.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::redirect(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(301, "Moved Permanently"),
+  ///     Uri::new(b"https://example.com/new")
+  /// )?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "HTTP/1.1 301 Moved Permanently\r\n\
+  ///   Location: https://example.com/new\r\n\
+  ///   \r\n"
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn redirect(
+    buffer: B,
+    version: Version,
+    status: Status,
+    location: Uri,
+  ) -> Result<Self, InsufficientSpaceError> {
+    let mut builder = Self::response(buffer, version, status)?;
+    builder.header(Header::checked_new(LOCATION, location))?;
+    Ok(builder)
+  }
+
+  /// Start a `404 Not Found` response.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let output = HttpBuilder::not_found(vec![], Version::HTTP_1_1)?
+  ///   .finish_empty()?;
+  ///
+  /// assert!(output.starts_with(b"HTTP/1.1 404"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn not_found(
+    buffer: B,
+    version: Version,
+  ) -> Result<Self, InsufficientSpaceError> {
+    Self::response(buffer, version, Status::NOT_FOUND)
+  }
+
+  /// Start a `405 Method Not Allowed` response along with the `Allow`
+  /// header listing the methods that the resource does support.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::method_not_allowed(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     &[Method::GET, Method::HEAD]
+  /// )?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert!(output.ends_with(b"\r\nAllow: GET, HEAD\r\n\r\n"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn method_not_allowed(
+    buffer: B,
+    version: Version,
+    allow: &[Method],
+  ) -> Result<Self, InsufficientSpaceError> {
+    let mut builder =
+      Self::response(buffer, version, Status::METHOD_NOT_ALLOWED)?;
+
+    let buffer = &mut builder.buffer;
+    buffer.try_put_slice(ALLOW.as_str().as_bytes())?;
+    buffer.try_put_slice(b": ")?;
+    for (i, method) in allow.iter().enumerate() {
+      if i != 0 {
+        buffer.try_put_slice(b", ")?;
+      }
+
+      method.write_to(buffer)?;
+    }
+    buffer.try_put_slice(&CRLF)?;
+    builder.headers += 1;
+
+    Ok(builder)
+  }
+}
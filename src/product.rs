@@ -0,0 +1,220 @@
+use crate::util::sum_hints;
+use crate::{
+  is_token, BufMut, Comment, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError,
+};
+
+/// A single product within a `User-Agent` or `Server` header, e.g.
+/// `Crustacean/0.1 (linux; x86_64)`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Product<'data> {
+  name: &'data str,
+  version: Option<&'data str>,
+  comment: Option<Comment<'data>>,
+}
+
+impl<'data> Product<'data> {
+  /// Create a product with an optional version.
+  ///
+  /// # Errors
+  /// Returns an error if `name` or `version` is not a valid token.
+  pub const fn try_new(
+    name: &'data str,
+    version: Option<&'data str>,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_token(name) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    if let Some(version) = version {
+      if !is_token(version) {
+        return Err(InvalidHeaderError(()));
+      }
+    }
+
+    Ok(Self {
+      name,
+      version,
+      comment: None,
+    })
+  }
+
+  /// Create a product with an optional version.
+  ///
+  /// # Panics
+  /// Panics if `name` or `version` is not a valid token.
+  pub const fn new(name: &'data str, version: Option<&'data str>) -> Self {
+    match Self::try_new(name, version) {
+      Ok(product) => product,
+      Err(_) => const_panic!("Product name or version was not a token"),
+    }
+  }
+
+  /// Set a comment that is written out after the product.
+  pub const fn comment(self, comment: Comment<'data>) -> Self {
+    Self {
+      comment: Some(comment),
+      ..self
+    }
+  }
+
+  /// The product name.
+  pub const fn name(&self) -> &'data str {
+    self.name
+  }
+
+  /// The product version, if there is one.
+  pub const fn version(&self) -> Option<&'data str> {
+    self.version
+  }
+}
+
+impl HttpWriteable for Product<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.name.as_bytes())?;
+
+    if let Some(version) = self.version {
+      buffer.try_put_u8(b'/')?;
+      buffer.try_put_slice(version.as_bytes())?;
+    }
+
+    if let Some(comment) = self.comment {
+      buffer.try_put_u8(b' ')?;
+      comment.write_to(buffer)?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut len = self.name.len();
+    if let Some(version) = self.version {
+      len += version.len() + 1;
+    }
+
+    match self.comment {
+      Some(comment) => {
+        sum_hints([(len + 1, Some(len + 1)), comment.size_hint()])
+      }
+      None => (len, Some(len)),
+    }
+  }
+}
+
+/// A `User-Agent` or `Server` header value made up of one or more
+/// products, from most to least significant.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const USER_AGENT: ProductList = ProductList::new(&[
+///   Product::new("Crustacean", Some("0.1"))
+///     .comment(Comment::new(b"linux; x86_64")),
+///   Product::new("httpencode", None),
+/// ]);
+///
+/// let mut buffer = Vec::new();
+/// Header::new("User-Agent", USER_AGENT).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "User-Agent: Crustacean/0.1 (linux; x86_64) httpencode\r\n"
+/// );
+/// assert!(Product::try_new("Crustacean", Some("0.1 beta")).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ProductList<'data> {
+  products: &'data [Product<'data>],
+}
+
+impl<'data> ProductList<'data> {
+  /// Create a product list.
+  ///
+  /// # Errors
+  /// Returns an error if `products` is empty.
+  pub const fn try_new(
+    products: &'data [Product<'data>],
+  ) -> Result<Self, InvalidHeaderError> {
+    if products.is_empty() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { products })
+  }
+
+  /// Create a product list.
+  ///
+  /// # Panics
+  /// Panics if `products` is empty.
+  pub const fn new(products: &'data [Product<'data>]) -> Self {
+    match Self::try_new(products) {
+      Ok(list) => list,
+      Err(_) => const_panic!("Product list was empty"),
+    }
+  }
+
+  /// The products in the order they are written out.
+  pub const fn products(&self) -> &'data [Product<'data>] {
+    self.products
+  }
+}
+
+impl HttpWriteable for ProductList<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    for (idx, product) in self.products.iter().enumerate() {
+      if idx != 0 {
+        buffer.try_put_u8(b' ')?;
+      }
+
+      product.write_to(buffer)?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let spaces = self.products.len().saturating_sub(1);
+    let products = self.products.iter().map(|product| product.size_hint());
+
+    sum_hints(products.chain([(spaces, Some(spaces))]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn products() {
+    assert_eq!(
+      encode_str(ProductList::new(&[Product::new("a", None)])),
+      "a"
+    );
+    assert_eq!(
+      encode_str(ProductList::new(&[
+        Product::new("a", Some("1")).comment(Comment::new(b"(nested)")),
+        Product::new("b", Some("2.0-rc1")),
+      ])),
+      r"a/1 (\(nested\)) b/2.0-rc1"
+    );
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(Product::try_new("", None).is_err());
+    assert!(Product::try_new("a/b", None).is_err());
+    assert!(Product::try_new("a", Some("")).is_err());
+    assert!(Product::try_new("a", Some("1 2")).is_err());
+    assert!(ProductList::try_new(&[]).is_err());
+  }
+}
@@ -0,0 +1,320 @@
+use core::convert::TryFrom;
+
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError,
+};
+
+/// Whether `bytes` contains a control character other than HTAB. These
+/// can't be represented within a quoted-string, not even when escaped.
+const fn contains_ctl(bytes: &[u8]) -> bool {
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'\t' => (),
+      0x00..=0x1F | 0x7F => return true,
+      _ => (),
+    }
+
+    idx += 1;
+  }
+
+  false
+}
+
+/// Write out `bytes` between `open` and `close`, escaping every byte for
+/// which `escape` returns true with a backslash.
+fn write_escaped<B: BufMut>(
+  buffer: &mut B,
+  bytes: &[u8],
+  (open, close): (u8, u8),
+  escape: fn(u8) -> bool,
+) -> Result<(), InsufficientSpaceError> {
+  buffer.try_put_u8(open)?;
+
+  let mut prev = 0;
+  for (idx, &byte) in bytes.iter().enumerate() {
+    if escape(byte) {
+      buffer.try_put_slice(&bytes[prev..idx])?;
+      buffer.try_put_slice(&[b'\\', byte])?;
+      prev = idx + 1;
+    }
+  }
+
+  buffer.try_put_slice(&bytes[prev..])?;
+  buffer.try_put_u8(close)
+}
+
+// Every byte may need to be escaped.
+const fn escaped_size_hint(len: usize) -> (usize, Option<usize>) {
+  let upper = match len.checked_mul(2) {
+    Some(upper) => upper.checked_add(2),
+    None => None,
+  };
+
+  (len.saturating_add(2), upper)
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Whether `byte` is an attr-char (RFC 8187) that can appear within an
+/// ext-value without being percent-encoded.
+fn is_attr_char(byte: u8) -> bool {
+  byte.is_ascii_alphanumeric()
+    || matches!(
+      byte,
+      b'!'
+        | b'#'
+        | b'$'
+        | b'&'
+        | b'+'
+        | b'-'
+        | b'.'
+        | b'^'
+        | b'_'
+        | b'`'
+        | b'|'
+        | b'~'
+    )
+}
+
+/// Whether `text` is printable ASCII and so can be written out as a
+/// plain quoted-string instead of needing an RFC 8187 ext-value.
+pub(crate) fn is_plain_text(text: &str) -> bool {
+  text
+    .bytes()
+    .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+}
+
+/// Write out `value` as an RFC 8187 ext-value in UTF-8 with no language,
+/// e.g. `UTF-8''%E2%82%AC%20rates`.
+pub(crate) fn write_ext_value<B: BufMut>(
+  buffer: &mut B,
+  value: &str,
+) -> Result<(), InsufficientSpaceError> {
+  buffer.try_put_slice(b"UTF-8''")?;
+
+  for byte in value.bytes() {
+    if is_attr_char(byte) {
+      buffer.try_put_u8(byte)?;
+    } else {
+      let hex = [b'%', HEX[(byte >> 4) as usize], HEX[(byte & 0xF) as usize]];
+      buffer.try_put_slice(&hex)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// A value that is written out as an RFC 7230 quoted-string.
+///
+/// The value is surrounded by double quotes and any `"` or `\` within it
+/// is escaped. Control characters other than HTAB cannot be represented
+/// within a quoted-string so values containing them are rejected when
+/// the `Quoted` is created.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Quoted::try_from(r#"a "b".txt"#)?.write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, br#""a \"b\".txt""#);
+/// assert!(Quoted::try_from("new\nline").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Quoted<'data>(pub(crate) &'data [u8]);
+
+impl<'data> Quoted<'data> {
+  /// Create a `Quoted` from the unescaped contents of the string.
+  ///
+  /// # Errors
+  /// Returns an error if `value` contains a control character other
+  /// than HTAB.
+  pub const fn try_new(value: &'data [u8]) -> Result<Self, InvalidHeaderError> {
+    if contains_ctl(value) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(value))
+  }
+
+  /// Create a `Quoted` from the unescaped contents of the string.
+  ///
+  /// # Panics
+  /// Panics if `value` contains a control character other than HTAB.
+  pub const fn new(value: &'data [u8]) -> Self {
+    match Self::try_new(value) {
+      Ok(value) => value,
+      Err(_) => const_panic!("Quoted string contained a control character"),
+    }
+  }
+
+  /// Access the unescaped contents of this string.
+  pub const fn as_bytes(&self) -> &'data [u8] {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data [u8]> for Quoted<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(value: &'data [u8]) -> Result<Self, Self::Error> {
+    Self::try_new(value)
+  }
+}
+
+impl<'data> TryFrom<&'data str> for Quoted<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(value: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(value.as_bytes())
+  }
+}
+
+impl HttpWriteable for Quoted<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    write_escaped(buffer, self.0, (b'"', b'"'), |b| b == b'"' || b == b'\\')
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    escaped_size_hint(self.0.len())
+  }
+}
+
+/// A value that is written out as a parenthesized RFC 7230 comment, as
+/// used within `User-Agent`, `Server`, and `Via` headers.
+///
+/// Any `(`, `)`, or `\` within the value is escaped so the comment can
+/// never be closed early. As with [`Quoted`](crate::Quoted), values
+/// containing control characters other than HTAB are rejected.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::request(
+///     vec![],
+///     Method::GET,
+///     Uri::new(b"/"),
+///     Version::HTTP_1_1
+/// )?;
+/// let comment = Comment::try_from("X11; Linux :)")?;
+/// builder.header(Header::new("User-Agent", comment))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "GET / HTTP/1.1\r\n\
+///   User-Agent: (X11; Linux :\\))\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Comment<'data>(&'data [u8]);
+
+impl<'data> Comment<'data> {
+  /// Create a `Comment` from the unescaped contents of the comment.
+  ///
+  /// # Errors
+  /// Returns an error if `value` contains a control character other
+  /// than HTAB.
+  pub const fn try_new(value: &'data [u8]) -> Result<Self, InvalidHeaderError> {
+    if contains_ctl(value) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(value))
+  }
+
+  /// Create a `Comment` from the unescaped contents of the comment.
+  ///
+  /// # Panics
+  /// Panics if `value` contains a control character other than HTAB.
+  pub const fn new(value: &'data [u8]) -> Self {
+    match Self::try_new(value) {
+      Ok(value) => value,
+      Err(_) => const_panic!("Comment contained a control character"),
+    }
+  }
+
+  /// Access the unescaped contents of this comment.
+  pub const fn as_bytes(&self) -> &'data [u8] {
+    self.0
+  }
+}
+
+impl<'data> TryFrom<&'data [u8]> for Comment<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(value: &'data [u8]) -> Result<Self, Self::Error> {
+    Self::try_new(value)
+  }
+}
+
+impl<'data> TryFrom<&'data str> for Comment<'data> {
+  type Error = InvalidHeaderError;
+
+  fn try_from(value: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(value.as_bytes())
+  }
+}
+
+impl HttpWriteable for Comment<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    write_escaped(buffer, self.0, (b'(', b')'), |b| {
+      matches!(b, b'(' | b')' | b'\\')
+    })
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    escaped_size_hint(self.0.len())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode;
+
+  #[test]
+  fn quoted_escaping() {
+    assert_eq!(encode(Quoted::new(b"")), b"\"\"");
+    assert_eq!(encode(Quoted::new(b"plain")), b"\"plain\"");
+    assert_eq!(encode(Quoted::new(b"\"\\")), b"\"\\\"\\\\\"");
+    assert_eq!(encode(Quoted::new(b"tab\there")), b"\"tab\there\"");
+    assert_eq!(encode(Quoted::new(b"\xff")), b"\"\xff\"");
+  }
+
+  #[test]
+  fn quoted_rejects_ctls() {
+    for value in &[&b"\r"[..], b"\n", b"\0", b"\x7f", b"a\x1fb"] {
+      assert!(Quoted::try_new(value).is_err());
+    }
+  }
+
+  #[test]
+  fn comment_escaping() {
+    assert_eq!(encode(Comment::new(b"")), b"()");
+    assert_eq!(encode(Comment::new(b"compatible")), b"(compatible)");
+    assert_eq!(encode(Comment::new(b"(a)")), b"(\\(a\\))");
+    assert_eq!(encode(Comment::new(b"\"quoted\"")), b"(\"quoted\")");
+    assert_eq!(encode(Comment::new(b"\\")), b"(\\\\)");
+
+    assert!(Comment::try_new(b"\r\n").is_err());
+  }
+}
@@ -0,0 +1,223 @@
+use core::convert::TryFrom;
+
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError,
+};
+
+/// A quality value (RFC 7231 `qvalue`) used to weight the items within
+/// content negotiation headers such as `Accept-Encoding`.
+///
+/// Quality values have at most 3 decimal places so they are stored as an
+/// integer number of thousandths. They are written out in their shortest
+/// form, e.g. `1`, `0.5`, or `0.125`.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// QValue::new(800).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"0.8");
+/// assert!(QValue::try_new(1001).is_err());
+///
+/// // Quality values can also be converted from floats.
+/// assert_eq!(QValue::try_from(0.8)?, QValue::new(800));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct QValue(u16);
+
+impl QValue {
+  /// The highest quality value, `1`. This is the default when no
+  /// quality value is given.
+  pub const ONE: Self = Self(1000);
+  /// The lowest quality value, `0`, which marks an item as not
+  /// acceptable.
+  pub const ZERO: Self = Self(0);
+
+  /// Create a quality value from a number of thousandths.
+  ///
+  /// # Errors
+  /// Returns an error if `thousandths` is greater than 1000.
+  pub const fn try_new(thousandths: u16) -> Result<Self, InvalidHeaderError> {
+    if thousandths > 1000 {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(thousandths))
+  }
+
+  /// Create a quality value from a number of thousandths.
+  ///
+  /// # Panics
+  /// Panics if `thousandths` is greater than 1000.
+  pub const fn new(thousandths: u16) -> Self {
+    match Self::try_new(thousandths) {
+      Ok(q) => q,
+      Err(_) => const_panic!("Quality value was greater than 1"),
+    }
+  }
+
+  /// The number of thousandths within this quality value.
+  pub const fn thousandths(&self) -> u16 {
+    self.0
+  }
+}
+
+macro_rules! qvalue_try_from_float {
+  ($ty:ident) => {
+    impl TryFrom<$ty> for QValue {
+      type Error = InvalidHeaderError;
+
+      /// Round `value` to the nearest thousandth.
+      ///
+      /// Returns an error if `value` is not between 0 and 1 inclusive.
+      fn try_from(value: $ty) -> Result<Self, Self::Error> {
+        if !(0.0..=1.0).contains(&value) {
+          return Err(InvalidHeaderError(()));
+        }
+
+        Ok(Self((value * 1000.0 + 0.5) as u16))
+      }
+    }
+  };
+}
+
+qvalue_try_from_float!(f32);
+qvalue_try_from_float!(f64);
+
+impl HttpWriteable for QValue {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    match self.0 {
+      1000 => buffer.try_put_u8(b'1'),
+      0 => buffer.try_put_u8(b'0'),
+      q => {
+        let digits = [
+          b'0' + (q / 100) as u8,
+          b'0' + (q / 10 % 10) as u8,
+          b'0' + (q % 10) as u8,
+        ];
+        let len = match (digits[1], digits[2]) {
+          (b'0', b'0') => 1,
+          (_, b'0') => 2,
+          _ => 3,
+        };
+
+        buffer.try_put_slice(b"0.")?;
+        buffer.try_put_slice(&digits[..len])
+      }
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (1, Some(5))
+  }
+}
+
+/// An item within a content negotiation list along with its quality
+/// value.
+///
+/// The quality value is only written out (as `;q=<value>`) when it is
+/// not the default of [`QValue::ONE`](crate::QValue::ONE).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Weighted<T> {
+  item: T,
+  q: QValue,
+}
+
+impl<T> Weighted<T> {
+  /// Wrap `item` with the default quality value of 1.
+  pub const fn new(item: T) -> Self {
+    Self {
+      item,
+      q: QValue::ONE,
+    }
+  }
+
+  /// The item being weighted.
+  pub const fn item(&self) -> &T {
+    &self.item
+  }
+
+  /// The quality value of this item.
+  pub const fn quality(&self) -> QValue {
+    self.q
+  }
+}
+
+impl<T: Copy> Weighted<T> {
+  /// Use `q` as the quality value for this item.
+  pub const fn q(self, q: QValue) -> Self {
+    Self { q, ..self }
+  }
+}
+
+impl<T: HttpWriteable> HttpWriteable for Weighted<T> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    self.item.write_to(buffer)?;
+
+    if self.q != QValue::ONE {
+      buffer.try_put_slice(b";q=")?;
+      self.q.write_to(buffer)?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (lower, upper) = self.item.size_hint();
+
+    if self.q == QValue::ONE {
+      (lower, upper)
+    } else {
+      (lower + 4, upper.and_then(|upper| upper.checked_add(8)))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode;
+
+  #[test]
+  fn qvalue_shortest_form() {
+    assert_eq!(encode(QValue::ONE), b"1");
+    assert_eq!(encode(QValue::ZERO), b"0");
+    assert_eq!(encode(QValue::new(500)), b"0.5");
+    assert_eq!(encode(QValue::new(50)), b"0.05");
+    assert_eq!(encode(QValue::new(5)), b"0.005");
+    assert_eq!(encode(QValue::new(125)), b"0.125");
+    assert_eq!(encode(QValue::new(990)), b"0.99");
+  }
+
+  #[test]
+  fn qvalue_from_float() {
+    assert_eq!(QValue::try_from(0.8f32).unwrap(), QValue::new(800));
+    assert_eq!(QValue::try_from(0.1234f64).unwrap(), QValue::new(123));
+    assert_eq!(QValue::try_from(0.9996f64).unwrap(), QValue::ONE);
+    assert_eq!(QValue::try_from(0.0f32).unwrap(), QValue::ZERO);
+
+    for &value in &[-0.1, 1.001, f64::NAN, f64::INFINITY] {
+      assert!(QValue::try_from(value).is_err(), "{}", value);
+    }
+  }
+
+  #[test]
+  fn weighted() {
+    assert_eq!(encode(Weighted::new("gzip")), b"gzip");
+    assert_eq!(encode(Weighted::new("br").q(QValue::new(800))), b"br;q=0.8");
+    assert_eq!(encode(Weighted::new("*").q(QValue::ZERO)), b"*;q=0");
+  }
+}
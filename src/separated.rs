@@ -0,0 +1,256 @@
+use crate::{
+  BufMut, ContentCoding, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  LanguageTag, Link, Method, Parameterized, Product, Quoted, TransferCoding,
+  Warning, Weighted,
+};
+
+/// A list of values that are written out one after another with a
+/// separator between each of them.
+///
+/// This is meant for list-valued headers such as `Accept-Encoding`,
+/// `Vary`, and `Allow`. The items are only ever borrowed so they can be
+/// written straight from a slice or any other cloneable iterator without
+/// building up an intermediate string.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(405, "Method Not Allowed")
+/// )?;
+/// let allowed = [Method::GET, Method::HEAD, Method::OPTIONS];
+/// builder.header(Header::new("Allow", Separated::new(&allowed)))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 405 Method Not Allowed\r\n\
+///   Allow: GET, HEAD, OPTIONS\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Separated<I> {
+  items: I,
+  separator: &'static str,
+}
+
+impl<I> Separated<I> {
+  /// Create a list that writes out `items` separated by `", "`.
+  pub const fn new(items: I) -> Self {
+    Self {
+      items,
+      separator: ", ",
+    }
+  }
+
+  /// Use `separator` instead of `", "` between items.
+  ///
+  /// # Panics
+  /// Panics if `separator` contains a control character other than HTAB.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut buffer = Vec::new();
+  /// Separated::new(&["a=1", "b=2"])
+  ///   .with_separator("; ")
+  ///   .write_to(&mut buffer)?;
+  ///
+  /// assert_eq!(buffer, b"a=1; b=2");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_separator(self, separator: &'static str) -> Self {
+    let valid = separator
+      .bytes()
+      .all(|b| b == b'\t' || !b.is_ascii_control());
+    assert!(valid, "Separator contained a control character");
+
+    Self { separator, ..self }
+  }
+}
+
+impl<I> HttpWriteable for Separated<I>
+where
+  I: IntoIterator + Clone,
+  I::Item: HttpWriteable,
+{
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let mut items = self.items.clone().into_iter();
+
+    if let Some(first) = items.next() {
+      first.write_to(buffer)?;
+    }
+
+    for item in items {
+      buffer.try_put_slice(self.separator.as_bytes())?;
+      item.write_to(buffer)?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let separator = self.separator.len();
+
+    self.items.clone().into_iter().enumerate().fold(
+      (0, Some(0)),
+      |(lower, upper), (idx, item)| {
+        let separator = if idx == 0 { 0 } else { separator };
+        let (item_lower, item_upper) = item.size_hint();
+
+        (
+          lower.saturating_add(item_lower).saturating_add(separator),
+          upper
+            .and_then(|upper| upper.checked_add(item_upper?))
+            .and_then(|upper| upper.checked_add(separator)),
+        )
+      },
+    )
+  }
+}
+
+/// Writables that can be written out as an item within a comma-separated
+/// list.
+///
+/// Slices and arrays of these are written out separated by `", "`, the
+/// same as [`Separated::new`]. This is implemented for every writable
+/// within this crate that makes sense as a list item, other than `u8`,
+/// so that `&[u8]` and byte strings such as `b"abc"` keep being written
+/// out as raw bytes.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let allowed = [Method::GET, Method::HEAD];
+/// let vary: &[&str] = &["Accept", "Accept-Encoding"];
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Allow", &allowed).write_to(&mut buffer)?;
+/// Header::new("Vary", vary).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Allow: GET, HEAD\r\n\
+///   Vary: Accept, Accept-Encoding\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Byte arrays are not lists:
+/// ```compile_fail
+/// # use httpencode::*;
+/// let _ = [1u8, 2, 3].write_to(&mut Vec::new());
+/// ```
+pub trait ListItem: HttpWriteable {}
+
+impl<T: ListItem> ListItem for &'_ T {}
+
+impl<T: ListItem> ListItem for Weighted<T> {}
+
+macro_rules! list_item {
+  ($( $ty:ty ),* $(,)?) => {
+    $( impl ListItem for $ty {} )*
+  };
+}
+
+list_item!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+list_item!(&'_ str, Quoted<'_>, Method<'_>, Product<'_>);
+list_item!(ContentCoding<'_>, TransferCoding<'_>, LanguageTag<'_>);
+list_item!(Link<'_>, Warning<'_>, Parameterized<'_>);
+
+#[cfg(feature = "std")]
+list_item!(String);
+
+impl<T: ListItem> HttpWriteable for &'_ [T] {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    Separated::new(*self).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    Separated::new(*self).size_hint()
+  }
+}
+
+impl<T: ListItem, const N: usize> HttpWriteable for [T; N] {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    Separated::new(self).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    Separated::new(self).size_hint()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode<V: HttpWriteable>(value: V) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    value.write_to(&mut buffer).unwrap();
+    buffer
+  }
+
+  #[test]
+  fn separated() {
+    let empty: &[u32] = &[];
+
+    assert_eq!(encode(Separated::new(empty)), b"");
+    assert_eq!(encode(Separated::new(&[1])), b"1");
+    assert_eq!(encode(Separated::new(&[1, 20, 300])), b"1, 20, 300");
+    assert_eq!(encode(Separated::new(1..4).with_separator(",")), b"1,2,3");
+  }
+
+  #[test]
+  fn separated_size_hint() {
+    use crate::CheckedValue;
+
+    let encodings = [CheckedValue::new(b"gzip"), CheckedValue::new(b"br")];
+    let list = Separated::new(&encodings);
+
+    assert_eq!(list.size_hint(), (8, Some(8)));
+    assert_eq!(Separated::new(&[0u8; 0]).size_hint(), (0, Some(0)));
+  }
+
+  #[test]
+  fn slices_and_arrays() {
+    let empty: &[u32] = &[];
+
+    assert_eq!(encode(empty), b"");
+    assert_eq!(encode(&[1u32, 20, 300][..]), b"1, 20, 300");
+    assert_eq!(encode(["gzip", "br"]), b"gzip, br");
+    assert_eq!(encode(&[Method::GET, Method::HEAD][..]), b"GET, HEAD");
+    assert_eq!(
+      [300u32, 4].size_hint(),
+      Separated::new(&[300u32, 4]).size_hint()
+    );
+
+    // Bytes are still written out as they are rather than as a list.
+    assert_eq!(encode(&b"abc"[..]), b"abc");
+  }
+
+  #[test]
+  #[should_panic]
+  fn separator_with_crlf() {
+    let _ = Separated::new(&[1]).with_separator("\r\n");
+  }
+}
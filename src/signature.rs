@@ -0,0 +1,670 @@
+use crate::structured::{
+  byte_sequence_len, is_key, is_string, write_byte_sequence,
+};
+use crate::util::sum_hints;
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError, Quoted,
+};
+
+/// Whether `name` is a valid component identifier: a lowercase field
+/// name or a derived component such as `@method`.
+const fn is_component(name: &str) -> bool {
+  let bytes = name.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    if bytes[idx].is_ascii_uppercase() {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  !bytes.is_empty() && is_string(name)
+}
+
+/// Write out `;name="value"` if there is a value. The value must be a
+/// valid structured field string.
+fn write_string_param<B: BufMut>(
+  buffer: &mut B,
+  name: &str,
+  value: Option<&str>,
+) -> Result<(), InsufficientSpaceError> {
+  if let Some(value) = value {
+    buffer.try_put_u8(b';')?;
+    buffer.try_put_slice(name.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    Quoted(value.as_bytes()).write_to(buffer)?;
+  }
+
+  Ok(())
+}
+
+/// Write out `;name=value` if there is a value.
+fn write_integer_param<B: BufMut>(
+  buffer: &mut B,
+  name: &str,
+  value: Option<u64>,
+) -> Result<(), InsufficientSpaceError> {
+  if let Some(value) = value {
+    buffer.try_put_u8(b';')?;
+    buffer.try_put_slice(name.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    value.write_to(buffer)?;
+  }
+
+  Ok(())
+}
+
+/// The size hint of `;name=value` where the value is written out by `hint`.
+fn param_hint<T>(
+  name: &str,
+  value: Option<T>,
+  hint: impl FnOnce(T) -> (usize, Option<usize>),
+) -> (usize, Option<usize>) {
+  match value {
+    Some(value) => {
+      sum_hints([(name.len() + 2, Some(name.len() + 2)), hint(value)])
+    }
+    None => (0, Some(0)),
+  }
+}
+
+fn quoted_hint(value: &str) -> (usize, Option<usize>) {
+  Quoted(value.as_bytes()).size_hint()
+}
+
+/// The covered components and metadata of an HTTP message signature
+/// (RFC 9421).
+///
+/// This is written out as the inner list that makes up both a
+/// `Signature-Input` entry and the final `@signature-params` line of the
+/// signature base, e.g.
+/// `("@method" "content-digest");created=1618884473;keyid="key"`.
+///
+/// Components are given by name only, component parameters such as `;sf`
+/// or `;req` are not supported.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SignatureParams<'data> {
+  components: &'data [&'data str],
+  created: Option<u64>,
+  expires: Option<u64>,
+  nonce: Option<&'data str>,
+  alg: Option<&'data str>,
+  keyid: Option<&'data str>,
+  tag: Option<&'data str>,
+}
+
+impl<'data> SignatureParams<'data> {
+  /// Create signature parameters covering `components`, which are
+  /// lowercase field names or derived components such as `@method` or
+  /// `@target-uri`.
+  ///
+  /// # Errors
+  /// Returns an error if any component is empty, contains an uppercase
+  /// letter or contains a character other than printable ASCII.
+  pub const fn try_new(
+    components: &'data [&'data str],
+  ) -> Result<Self, InvalidHeaderError> {
+    let mut idx = 0;
+    while idx < components.len() {
+      if !is_component(components[idx]) {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self {
+      components,
+      created: None,
+      expires: None,
+      nonce: None,
+      alg: None,
+      keyid: None,
+      tag: None,
+    })
+  }
+
+  /// Create signature parameters covering `components`, which are
+  /// lowercase field names or derived components such as `@method` or
+  /// `@target-uri`.
+  ///
+  /// # Panics
+  /// Panics if any component is empty, contains an uppercase letter or
+  /// contains a character other than printable ASCII.
+  pub const fn new(components: &'data [&'data str]) -> Self {
+    match Self::try_new(components) {
+      Ok(params) => params,
+      Err(_) => const_panic!("Invalid signature component identifier"),
+    }
+  }
+
+  /// Set the creation time as a UNIX timestamp.
+  pub const fn created(self, created: u64) -> Self {
+    Self {
+      created: Some(created),
+      ..self
+    }
+  }
+
+  /// Set the expiration time as a UNIX timestamp.
+  pub const fn expires(self, expires: u64) -> Self {
+    Self {
+      expires: Some(expires),
+      ..self
+    }
+  }
+
+  /// Set a nonce for this signature.
+  ///
+  /// # Errors
+  /// Returns an error if `nonce` contains a character other than
+  /// printable ASCII.
+  pub const fn try_nonce(
+    self,
+    nonce: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_string(nonce) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      nonce: Some(nonce),
+      ..self
+    })
+  }
+
+  /// Set a nonce for this signature.
+  ///
+  /// # Panics
+  /// Panics if `nonce` contains a character other than printable ASCII.
+  pub const fn nonce(self, nonce: &'data str) -> Self {
+    match self.try_nonce(nonce) {
+      Ok(params) => params,
+      Err(_) => const_panic!("Signature nonce contained invalid character"),
+    }
+  }
+
+  /// Set the signature algorithm, such as `rsa-pss-sha512` or `ed25519`.
+  ///
+  /// # Errors
+  /// Returns an error if `alg` contains a character other than printable
+  /// ASCII.
+  pub const fn try_alg(
+    self,
+    alg: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_string(alg) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      alg: Some(alg),
+      ..self
+    })
+  }
+
+  /// Set the signature algorithm, such as `rsa-pss-sha512` or `ed25519`.
+  ///
+  /// # Panics
+  /// Panics if `alg` contains a character other than printable ASCII.
+  pub const fn alg(self, alg: &'data str) -> Self {
+    match self.try_alg(alg) {
+      Ok(params) => params,
+      Err(_) => const_panic!("Signature alg contained invalid character"),
+    }
+  }
+
+  /// Set the identifier of the key used to create the signature.
+  ///
+  /// # Errors
+  /// Returns an error if `keyid` contains a character other than
+  /// printable ASCII.
+  pub const fn try_keyid(
+    self,
+    keyid: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_string(keyid) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      keyid: Some(keyid),
+      ..self
+    })
+  }
+
+  /// Set the identifier of the key used to create the signature.
+  ///
+  /// # Panics
+  /// Panics if `keyid` contains a character other than printable ASCII.
+  pub const fn keyid(self, keyid: &'data str) -> Self {
+    match self.try_keyid(keyid) {
+      Ok(params) => params,
+      Err(_) => const_panic!("Signature keyid contained invalid character"),
+    }
+  }
+
+  /// Set an application-specific tag for this signature.
+  ///
+  /// # Errors
+  /// Returns an error if `tag` contains a character other than printable
+  /// ASCII.
+  pub const fn try_tag(
+    self,
+    tag: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_string(tag) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      tag: Some(tag),
+      ..self
+    })
+  }
+
+  /// Set an application-specific tag for this signature.
+  ///
+  /// # Panics
+  /// Panics if `tag` contains a character other than printable ASCII.
+  pub const fn tag(self, tag: &'data str) -> Self {
+    match self.try_tag(tag) {
+      Ok(params) => params,
+      Err(_) => const_panic!("Signature tag contained invalid character"),
+    }
+  }
+
+  /// The covered components, in order.
+  pub const fn components(&self) -> &'data [&'data str] {
+    self.components
+  }
+}
+
+impl HttpWriteable for SignatureParams<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_u8(b'(')?;
+    for (idx, component) in self.components.iter().enumerate() {
+      if idx != 0 {
+        buffer.try_put_u8(b' ')?;
+      }
+
+      // Components were checked when this was created.
+      Quoted(component.as_bytes()).write_to(buffer)?;
+    }
+    buffer.try_put_u8(b')')?;
+
+    write_integer_param(buffer, "created", self.created)?;
+    write_integer_param(buffer, "expires", self.expires)?;
+    write_string_param(buffer, "nonce", self.nonce)?;
+    write_string_param(buffer, "alg", self.alg)?;
+    write_string_param(buffer, "keyid", self.keyid)?;
+    write_string_param(buffer, "tag", self.tag)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.components.len().max(1) + 1;
+    let components = self.components.iter().map(|name| quoted_hint(name));
+    let integer = |value: u64| value.size_hint();
+
+    sum_hints(
+      [
+        (len, Some(len)),
+        param_hint("created", self.created, integer),
+        param_hint("expires", self.expires, integer),
+        param_hint("nonce", self.nonce, quoted_hint),
+        param_hint("alg", self.alg, quoted_hint),
+        param_hint("keyid", self.keyid, quoted_hint),
+        param_hint("tag", self.tag, quoted_hint),
+      ]
+      .iter()
+      .copied()
+      .chain(components),
+    )
+  }
+}
+
+/// A single `Signature-Input` entry: a signature label followed by its
+/// [`SignatureParams`].
+///
+/// Several entries can be written out by wrapping them in
+/// [`Separated`](crate::Separated).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const PARAMS: SignatureParams =
+///   SignatureParams::new(&["@method", "@authority", "content-digest"])
+///     .created(1618884473)
+///     .keyid("test-key-rsa-pss");
+///
+/// let mut base = Vec::new();
+/// let digest = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+/// SignatureBase::new(PARAMS, &["POST", "example.com", digest])
+///   .write_to(&mut base)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&base)?,
+///   "\"@method\": POST\n\
+///   \"@authority\": example.com\n\
+///   \"content-digest\": sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:\n\
+///   \"@signature-params\": (\"@method\" \"@authority\" \"content-digest\")\
+///   ;created=1618884473;keyid=\"test-key-rsa-pss\""
+/// );
+///
+/// // Sign `base` with the key then send the result.
+/// let signature = [0xAB; 4];
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Signature-Input", SignatureInput::new("sig1", PARAMS))
+///   .write_to(&mut buffer)?;
+/// Header::new("Signature", Signature::new("sig1", &signature))
+///   .write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Signature-Input: sig1=(\"@method\" \"@authority\" \"content-digest\")\
+///   ;created=1618884473;keyid=\"test-key-rsa-pss\"\r\n\
+///   Signature: sig1=:q6urqw==:\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SignatureInput<'data> {
+  label: &'data str,
+  params: SignatureParams<'data>,
+}
+
+impl<'data> SignatureInput<'data> {
+  /// Create a `Signature-Input` entry.
+  ///
+  /// # Errors
+  /// Returns an error if `label` is not a valid structured field key.
+  pub const fn try_new(
+    label: &'data str,
+    params: SignatureParams<'data>,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_key(label) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { label, params })
+  }
+
+  /// Create a `Signature-Input` entry.
+  ///
+  /// # Panics
+  /// Panics if `label` is not a valid structured field key.
+  pub const fn new(label: &'data str, params: SignatureParams<'data>) -> Self {
+    match Self::try_new(label, params) {
+      Ok(input) => input,
+      Err(_) => const_panic!("Invalid signature label"),
+    }
+  }
+
+  /// The signature label.
+  pub const fn label(&self) -> &'data str {
+    self.label
+  }
+
+  /// The signature parameters.
+  pub const fn params(&self) -> SignatureParams<'data> {
+    self.params
+  }
+}
+
+impl HttpWriteable for SignatureInput<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.label.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    self.params.write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.label.len() + 1;
+    sum_hints([(len, Some(len)), self.params.size_hint()])
+  }
+}
+
+/// A single `Signature` entry: a signature label followed by the raw
+/// signature bytes, written out as a structured field byte sequence.
+///
+/// See [`SignatureInput`] for an example.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Signature<'data> {
+  label: &'data str,
+  signature: &'data [u8],
+}
+
+impl<'data> Signature<'data> {
+  /// Create a `Signature` entry.
+  ///
+  /// # Errors
+  /// Returns an error if `label` is not a valid structured field key.
+  pub const fn try_new(
+    label: &'data str,
+    signature: &'data [u8],
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_key(label) {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self { label, signature })
+  }
+
+  /// Create a `Signature` entry.
+  ///
+  /// # Panics
+  /// Panics if `label` is not a valid structured field key.
+  pub const fn new(label: &'data str, signature: &'data [u8]) -> Self {
+    match Self::try_new(label, signature) {
+      Ok(signature) => signature,
+      Err(_) => const_panic!("Invalid signature label"),
+    }
+  }
+
+  /// The signature label.
+  pub const fn label(&self) -> &'data str {
+    self.label
+  }
+
+  /// The raw signature bytes.
+  pub const fn signature(&self) -> &'data [u8] {
+    self.signature
+  }
+}
+
+impl HttpWriteable for Signature<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.label.as_bytes())?;
+    buffer.try_put_u8(b'=')?;
+    write_byte_sequence(buffer, self.signature)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.label.len() + 1 + byte_sequence_len(self.signature.len());
+    (len, Some(len))
+  }
+}
+
+/// The signature base of an HTTP message signature: the exact bytes that
+/// get signed.
+///
+/// Each covered component is written out on its own line as
+/// `"name": value`, followed by the `@signature-params` line. Lines are
+/// separated by a single LF with no trailing newline. This is not a
+/// header value, write it out into a buffer and pass that to the signing
+/// algorithm.
+///
+/// The component values must already be in their canonical form, e.g.
+/// field values with leading and trailing whitespace removed and multiple
+/// field lines combined with `, `.
+///
+/// See [`SignatureInput`] for an example.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SignatureBase<'data> {
+  params: SignatureParams<'data>,
+  values: &'data [&'data str],
+}
+
+impl<'data> SignatureBase<'data> {
+  /// Create a signature base from the signature parameters and the value
+  /// of each covered component, in the same order as the components.
+  ///
+  /// # Errors
+  /// Returns an error if the number of values doesn't match the number of
+  /// components or if a value contains a control character other than
+  /// HTAB.
+  pub const fn try_new(
+    params: SignatureParams<'data>,
+    values: &'data [&'data str],
+  ) -> Result<Self, InvalidHeaderError> {
+    if values.len() != params.components.len() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let mut idx = 0;
+    while idx < values.len() {
+      if Quoted::try_new(values[idx].as_bytes()).is_err() {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self { params, values })
+  }
+
+  /// Create a signature base from the signature parameters and the value
+  /// of each covered component, in the same order as the components.
+  ///
+  /// # Panics
+  /// Panics if the number of values doesn't match the number of
+  /// components or if a value contains a control character other than
+  /// HTAB.
+  pub const fn new(
+    params: SignatureParams<'data>,
+    values: &'data [&'data str],
+  ) -> Self {
+    match Self::try_new(params, values) {
+      Ok(base) => base,
+      Err(_) => const_panic!("Invalid signature component values"),
+    }
+  }
+}
+
+impl HttpWriteable for SignatureBase<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let components = self.params.components.iter();
+    for (component, value) in components.zip(self.values) {
+      Quoted(component.as_bytes()).write_to(buffer)?;
+      buffer.try_put_slice(b": ")?;
+      buffer.try_put_slice(value.as_bytes())?;
+      buffer.try_put_u8(b'\n')?;
+    }
+
+    buffer.try_put_slice(b"\"@signature-params\": ")?;
+    self.params.write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let components = self.params.components.iter();
+    let lines = components.zip(self.values).map(|(component, value)| {
+      let len = value.len() + 3;
+      sum_hints([quoted_hint(component), (len, Some(len))])
+    });
+    let len = "\"@signature-params\": ".len();
+
+    sum_hints(lines.chain([(len, Some(len)), self.params.size_hint()]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn params() {
+    assert_eq!(encode_str(SignatureParams::new(&[])), "()");
+    assert_eq!(
+      encode_str(
+        SignatureParams::new(&["@method", "x-\"odd\""])
+          .created(1)
+          .expires(2)
+          .nonce("n")
+          .alg("ed25519")
+          .keyid("k\\1")
+          .tag("app")
+      ),
+      "(\"@method\" \"x-\\\"odd\\\"\");created=1;expires=2;nonce=\"n\";\
+       alg=\"ed25519\";keyid=\"k\\\\1\";tag=\"app\""
+    );
+  }
+
+  #[test]
+  fn base() {
+    let params = SignatureParams::new(&["@path", "date"]).keyid("k");
+
+    assert_eq!(
+      encode_str(SignatureBase::new(
+        params,
+        &["/foo", "Tue, 20 Apr 2021 02:07:55 GMT"]
+      )),
+      "\"@path\": /foo\n\
+       \"date\": Tue, 20 Apr 2021 02:07:55 GMT\n\
+       \"@signature-params\": (\"@path\" \"date\");keyid=\"k\""
+    );
+    assert_eq!(
+      encode_str(SignatureBase::new(SignatureParams::new(&[]), &[])),
+      "\"@signature-params\": ()"
+    );
+    assert!(SignatureBase::try_new(params, &["/foo"]).is_err());
+    assert!(SignatureBase::try_new(params, &["/foo", "a\nb"]).is_err());
+  }
+
+  #[test]
+  fn entries() {
+    let params = SignatureParams::new(&["@method"]);
+
+    assert_eq!(
+      encode_str(SignatureInput::new("sig-b21", params)),
+      r#"sig-b21=("@method")"#
+    );
+    assert_eq!(encode_str(Signature::new("sig1", b"")), "sig1=::");
+    assert_eq!(
+      encode_str(Signature::new("sig1", b"\xfb\xff")),
+      "sig1=:+/8=:"
+    );
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(SignatureParams::try_new(&["Date"]).is_err());
+    assert!(SignatureParams::try_new(&[""]).is_err());
+    assert!(SignatureParams::try_new(&["a\tb"]).is_err());
+
+    let params = SignatureParams::new(&[]);
+    assert!(params.try_keyid("\u{e9}").is_err());
+    assert!(params.try_nonce("a\r\n").is_err());
+    assert!(SignatureInput::try_new("Sig1", params).is_err());
+    assert!(Signature::try_new("sig 1", b"").is_err());
+  }
+}
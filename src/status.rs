@@ -1,4 +1,12 @@
+use core::hash::{Hash, Hasher};
+
+use crate::InvalidStatusError;
+
 /// HTTP Status Code.
+///
+/// Two statuses are equal if they have the same code, regardless of
+/// their reason phrases.
+#[derive(Copy, Clone, Debug)]
 pub struct Status<'msg> {
   code: u16,
   reason: Option<&'msg str>,
@@ -30,8 +38,39 @@ impl<'msg> Status<'msg> {
     }
   }
 
+  /// Create a new status code, using the default reason phrase, after
+  /// checking that it is a three digit number.
+  ///
+  /// [`Status::new`](crate::Status::new) accepts any `u16`, which allows
+  /// creating status lines that no parser will accept.
+  ///
+  /// # Errors
+  /// Returns an error if `code` is not within `100..=999`.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Status::try_new(200).is_ok());
+  /// assert!(Status::try_new(999).is_ok());
+  ///
+  /// assert!(Status::try_new(99).is_err());
+  /// assert!(Status::try_new(63000).is_err());
+  /// ```
+  pub const fn try_new(code: u16) -> Result<Self, InvalidStatusError> {
+    if code < 100 || code > 999 {
+      return Err(InvalidStatusError(()));
+    }
+
+    Ok(Self::new(code))
+  }
+
   /// Create a status with a custom reason phrase.
   ///
+  /// # Panics
+  /// Panics if `reason` contains any control characters other than
+  /// horizontal tab. See
+  /// [`InvalidStatusError`](crate::InvalidStatusError) for details.
+  ///
   /// # Example
   /// ```
   /// # use httpencode::*;
@@ -41,6 +80,50 @@ impl<'msg> Status<'msg> {
   /// assert_eq!(status.reason(), "Not a valid status");
   /// ```
   pub const fn with_reason(code: u16, reason: &'msg str) -> Self {
+    match Self::try_with_reason(code, reason) {
+      Ok(status) => status,
+      Err(_) => const_panic!("Reason phrase contained invalid character"),
+    }
+  }
+
+  /// Create a status with a custom reason phrase.
+  ///
+  /// # Errors
+  /// Returns an error if `reason` contains any control characters other
+  /// than horizontal tab. See
+  /// [`InvalidStatusError`](crate::InvalidStatusError) for details.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Status::try_with_reason(200, "Fine").is_ok());
+  /// assert!(Status::try_with_reason(200, "OK\r\nEvil: yes").is_err());
+  /// ```
+  pub const fn try_with_reason(
+    code: u16,
+    reason: &'msg str,
+  ) -> Result<Self, InvalidStatusError> {
+    if !validate_reason(reason.as_bytes()) {
+      return Err(InvalidStatusError(()));
+    }
+
+    Ok(Self {
+      code,
+      reason: Some(reason),
+    })
+  }
+
+  /// Create a status with a custom reason phrase without validating the
+  /// reason phrase.
+  ///
+  /// # Safety
+  /// If `reason` contains any invalid characters then any HTTP response
+  /// constructed using this status may have invalid syntax or contain
+  /// injected headers.
+  pub const unsafe fn with_reason_unchecked(
+    code: u16,
+    reason: &'msg str,
+  ) -> Self {
     Self {
       code,
       reason: Some(reason),
@@ -61,15 +144,86 @@ impl<'msg> Status<'msg> {
     }
   }
 
-  #[cfg(not(feature = "no-reason-phrase"))]
-  const fn reason_phrase(mut code: u16) -> Option<&'static str> {
-    code = match code {
-      0..=99 => return None,
-      code if code as usize > REASON_PHRASES.len() + 100 => return None,
-      code => code,
-    };
+  /// Whether this is an informational (1xx) status.
+  pub const fn is_informational(&self) -> bool {
+    self.code >= 100 && self.code < 200
+  }
 
-    REASON_PHRASES[(code - 100) as usize]
+  /// Whether this is a successful (2xx) status.
+  pub const fn is_success(&self) -> bool {
+    self.code >= 200 && self.code < 300
+  }
+
+  /// Whether this is a redirection (3xx) status.
+  pub const fn is_redirection(&self) -> bool {
+    self.code >= 300 && self.code < 400
+  }
+
+  /// Whether this is a client error (4xx) status.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert!(Status::NOT_FOUND.is_client_error());
+  /// assert!(!Status::BAD_GATEWAY.is_client_error());
+  /// ```
+  pub const fn is_client_error(&self) -> bool {
+    self.code >= 400 && self.code < 500
+  }
+
+  /// Whether this is a server error (5xx) status.
+  pub const fn is_server_error(&self) -> bool {
+    self.code >= 500 && self.code < 600
+  }
+
+  /// Get the reason phrase for `code` from the status code registry, if
+  /// `code` is registered.
+  ///
+  /// This works regardless of whether the `no-reason-phrase` feature is
+  /// enabled.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert_eq!(Status::canonical_reason(404), Some("Not Found"));
+  /// assert_eq!(Status::canonical_reason(499), None);
+  /// ```
+  pub const fn canonical_reason(code: u16) -> Option<&'static str> {
+    match code {
+      0..=99 => None,
+      code if code as usize >= REASON_PHRASES.len() + 100 => None,
+      code => REASON_PHRASES[(code - 100) as usize],
+    }
+  }
+
+  /// Iterate over all registered status codes, in ascending order, along
+  /// with their canonical reason phrases.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let mut statuses = Status::registered();
+  /// let first = statuses.next().unwrap();
+  ///
+  /// assert_eq!(first.code(), 100);
+  /// assert_eq!(first.reason(), "Continue");
+  /// assert!(statuses.all(|status| status.code() > 100));
+  /// ```
+  pub fn registered() -> impl Iterator<Item = Status<'static>> {
+    REASON_PHRASES
+      .iter()
+      .zip(100..)
+      .filter_map(|(&reason, code)| {
+        reason.map(|reason| Status {
+          code,
+          reason: Some(reason),
+        })
+      })
+  }
+
+  #[cfg(not(feature = "no-reason-phrase"))]
+  const fn reason_phrase(code: u16) -> Option<&'static str> {
+    Self::canonical_reason(code)
   }
 
   #[cfg(feature = "no-reason-phrase")]
@@ -78,6 +232,79 @@ impl<'msg> Status<'msg> {
   }
 }
 
+/// Status codes that are widely deployed but not registered with IANA.
+///
+/// The reason phrases are the ones used by the software that introduced
+/// each code. They are left blank if the `no-reason-phrase` feature is
+/// enabled.
+#[cfg(feature = "unofficial-status-codes")]
+impl Status<'static> {
+  const fn unofficial(code: u16, reason: &'static str) -> Self {
+    let reason = if cfg!(feature = "no-reason-phrase") {
+      None
+    } else {
+      Some(reason)
+    };
+
+    Self { code, reason }
+  }
+
+  /// nginx: close the connection without sending a response.
+  pub const NGINX_NO_RESPONSE: Self = Self::unofficial(444, "No Response");
+  /// nginx: the request headers were too large.
+  pub const NGINX_REQUEST_HEADER_TOO_LARGE: Self =
+    Self::unofficial(494, "Request Header Too Large");
+  /// nginx: the client certificate was invalid.
+  pub const NGINX_SSL_CERTIFICATE_ERROR: Self =
+    Self::unofficial(495, "SSL Certificate Error");
+  /// nginx: a client certificate was required but not provided.
+  pub const NGINX_SSL_CERTIFICATE_REQUIRED: Self =
+    Self::unofficial(496, "SSL Certificate Required");
+  /// nginx: a plain HTTP request was sent to an HTTPS port.
+  pub const NGINX_HTTP_REQUEST_SENT_TO_HTTPS_PORT: Self =
+    Self::unofficial(497, "HTTP Request Sent to HTTPS Port");
+  /// Esri: an expired or invalid token was used.
+  pub const INVALID_TOKEN: Self = Self::unofficial(498, "Invalid Token");
+  /// nginx: the client closed the connection before the response was sent.
+  pub const NGINX_CLIENT_CLOSED_REQUEST: Self =
+    Self::unofficial(499, "Client Closed Request");
+
+  /// Cloudflare: the origin server returned an unexpected response.
+  pub const CLOUDFLARE_UNKNOWN_ERROR: Self =
+    Self::unofficial(520, "Web Server Returned an Unknown Error");
+  /// Cloudflare: the origin server refused the connection.
+  pub const CLOUDFLARE_WEB_SERVER_IS_DOWN: Self =
+    Self::unofficial(521, "Web Server Is Down");
+  /// Cloudflare: connecting to the origin server timed out.
+  pub const CLOUDFLARE_CONNECTION_TIMED_OUT: Self =
+    Self::unofficial(522, "Connection Timed Out");
+  /// Cloudflare: the origin server could not be reached.
+  pub const CLOUDFLARE_ORIGIN_IS_UNREACHABLE: Self =
+    Self::unofficial(523, "Origin Is Unreachable");
+  /// Cloudflare: the origin server did not respond in time.
+  pub const CLOUDFLARE_TIMEOUT_OCCURRED: Self =
+    Self::unofficial(524, "A Timeout Occurred");
+  /// Cloudflare: the TLS handshake with the origin server failed.
+  pub const CLOUDFLARE_SSL_HANDSHAKE_FAILED: Self =
+    Self::unofficial(525, "SSL Handshake Failed");
+  /// Cloudflare: the origin server's certificate could not be validated.
+  pub const CLOUDFLARE_INVALID_SSL_CERTIFICATE: Self =
+    Self::unofficial(526, "Invalid SSL Certificate");
+}
+
+/// Whether to include the reason phrase in the status line of a
+/// response.
+///
+/// See [`HttpBuilder::response_opts`](crate::HttpBuilder::response_opts).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReasonPhrase {
+  /// Write out the reason phrase of the status.
+  #[default]
+  Include,
+  /// Leave the reason phrase empty.
+  Omit,
+}
+
 macro_rules! decl_status {
   {
     $(
@@ -221,7 +448,6 @@ macro_rules! arraytable {
   }}
 }
 
-#[cfg_attr(not(features="no-reason-phrase"), allow(dead_code))]
 const REASON_PHRASES: &[Option<&str>] = &arraytable![
   // 1xx codes
   [100] = "Continue",
@@ -297,3 +523,120 @@ const REASON_PHRASES: &[Option<&str>] = &arraytable![
   [510] = "Not Extended",
   [511] = "Network Authentication Required"
 ];
+
+/// Validates that `reason` matches
+/// `reason-phrase = *( HTAB / SP / VCHAR / obs-text )`.
+const fn validate_reason(reason: &[u8]) -> bool {
+  let mut i = 0;
+  while i < reason.len() {
+    match reason[i] {
+      b'\t' => (),
+      0x00..=0x1F | 0x7F => return false,
+      _ => (),
+    }
+    i += 1;
+  }
+
+  true
+}
+
+impl PartialEq for Status<'_> {
+  fn eq(&self, other: &Self) -> bool {
+    self.code == other.code
+  }
+}
+
+impl Eq for Status<'_> {}
+
+impl Hash for Status<'_> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.code.hash(state)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn status_classes() {
+    assert!(Status::CONTINUE.is_informational());
+    assert!(Status::NO_CONTENT.is_success());
+    assert!(Status::SEE_OTHER.is_redirection());
+    assert!(Status::IM_A_TEAPOT.is_client_error());
+    assert!(Status::SERVICE_UNAVAILABLE.is_server_error());
+
+    let unusual = Status::new(600);
+    assert!(!unusual.is_informational());
+    assert!(!unusual.is_success());
+    assert!(!unusual.is_redirection());
+    assert!(!unusual.is_client_error());
+    assert!(!unusual.is_server_error());
+  }
+
+  #[test]
+  fn reason_validation() {
+    assert!(validate_reason(b""));
+    assert!(validate_reason(b"I'm a\tTeapot"));
+    assert!(validate_reason("Zu groß".as_bytes()));
+
+    assert!(!validate_reason(b"OK\r\nEvil: yes"));
+    assert!(!validate_reason(b"OK\n"));
+    assert!(!validate_reason(b"\0"));
+    assert!(!validate_reason(b"\x7F"));
+  }
+
+  #[test]
+  #[should_panic]
+  fn with_reason_injection() {
+    let _ = Status::with_reason(200, "OK\r\nSet-Cookie: a=b");
+  }
+
+  #[test]
+  fn canonical_reason_bounds() {
+    assert_eq!(Status::canonical_reason(0), None);
+    assert_eq!(Status::canonical_reason(99), None);
+    assert_eq!(Status::canonical_reason(100), Some("Continue"));
+    assert_eq!(
+      Status::canonical_reason(511),
+      Some("Network Authentication Required")
+    );
+    assert_eq!(Status::canonical_reason(512), None);
+    assert_eq!(Status::canonical_reason(u16::MAX), None);
+
+    assert_eq!(Status::new(512).reason(), "");
+  }
+
+  #[test]
+  fn registered_matches_canonical() {
+    for status in Status::registered() {
+      assert_eq!(
+        Some(status.reason()),
+        Status::canonical_reason(status.code())
+      );
+    }
+
+    assert_eq!(Status::registered().last().unwrap().code(), 511);
+  }
+
+  #[test]
+  #[cfg(all(
+    feature = "unofficial-status-codes",
+    not(feature = "no-reason-phrase")
+  ))]
+  fn unofficial_status_codes() {
+    let status = Status::NGINX_CLIENT_CLOSED_REQUEST;
+    assert_eq!(status.code(), 499);
+    assert_eq!(status.reason(), "Client Closed Request");
+
+    // These must never shadow a registered status.
+    assert_eq!(Status::canonical_reason(status.code()), None);
+    assert_eq!(Status::canonical_reason(520), None);
+  }
+
+  #[test]
+  fn status_eq_ignores_reason() {
+    assert_eq!(Status::OK, Status::with_reason(200, "Fine"));
+    assert_ne!(Status::OK, Status::CREATED);
+  }
+}
@@ -0,0 +1,159 @@
+// Helpers for writing out Structured Field Values (RFC 8941).
+
+use crate::base64::{encoded_len, write_base64, STANDARD};
+use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
+
+/// Whether `key` is a valid structured field key:
+/// ```text
+/// key = ( lcalpha / "*" ) *( lcalpha / DIGIT / "_" / "-" / "." / "*" )
+/// ```
+pub(crate) const fn is_key(key: &str) -> bool {
+  let bytes = key.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'a'..=b'z' | b'*' => (),
+      b'0'..=b'9' | b'_' | b'-' | b'.' if idx != 0 => (),
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  !bytes.is_empty()
+}
+
+/// Whether `value` can be written out as a structured field string, which
+/// only allows printable ASCII characters.
+pub(crate) const fn is_string(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    if !matches!(bytes[idx], b' '..=b'~') {
+      return false;
+    }
+
+    idx += 1;
+  }
+
+  true
+}
+
+/// Write out `bytes` as a byte sequence: `:<base64>:`.
+pub(crate) fn write_byte_sequence<B: BufMut>(
+  buffer: &mut B,
+  bytes: &[u8],
+) -> Result<(), InsufficientSpaceError> {
+  buffer.try_put_u8(b':')?;
+  write_base64(buffer, bytes, STANDARD, true)?;
+  buffer.try_put_u8(b':')
+}
+
+/// The exact length of `bytes` once written out as a byte sequence.
+pub(crate) const fn byte_sequence_len(len: usize) -> usize {
+  encoded_len(len, true) + 2
+}
+
+/// A structured field boolean (RFC 8941), written out as `?1` or `?0`.
+///
+/// Plain `bool`s are written out as `true` or `false` instead.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut buffer = Vec::new();
+/// Header::new("Sec-CH-UA-Mobile", StructuredBool::new(false))
+///   .write_to(&mut buffer)?;
+/// Header::new("X-Enabled", true).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Sec-CH-UA-Mobile: ?0\r\nX-Enabled: true\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct StructuredBool(bool);
+
+impl StructuredBool {
+  /// Wrap `value` so it is written out as a structured field boolean.
+  pub const fn new(value: bool) -> Self {
+    Self(value)
+  }
+
+  /// The wrapped value.
+  pub const fn get(&self) -> bool {
+    self.0
+  }
+}
+
+impl From<bool> for StructuredBool {
+  fn from(value: bool) -> Self {
+    Self(value)
+  }
+}
+
+impl HttpWriteable for StructuredBool {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let value: &[u8] = match self.0 {
+      true => b"?1",
+      false => b"?0",
+    };
+
+    buffer.try_put_slice(value)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (2, Some(2))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keys() {
+    for key in &["a", "*", "sha-256", "a_b.c*1"] {
+      assert!(is_key(key), "{}", key);
+    }
+
+    for key in &["", "A", "1a", "-a", "a b", "a=b"] {
+      assert!(!is_key(key), "{}", key);
+    }
+  }
+
+  #[test]
+  fn strings() {
+    assert!(is_string(""));
+    assert!(is_string("a \"quoted\" \\ string"));
+
+    for value in &["a\tb", "a\r\n", "\u{e9}", "\x7f"] {
+      assert!(!is_string(value), "{:?}", value);
+    }
+  }
+
+  #[test]
+  fn booleans() {
+    for &(value, expected) in &[(true, b"?1"), (false, b"?0")] {
+      let mut buffer = Vec::new();
+      StructuredBool::new(value).write_to(&mut buffer).unwrap();
+
+      assert_eq!(&buffer, expected);
+    }
+  }
+
+  #[test]
+  fn byte_sequence() {
+    let mut buffer = Vec::new();
+    write_byte_sequence(&mut buffer, b"hello").unwrap();
+
+    assert_eq!(buffer, b":aGVsbG8=:");
+    assert_eq!(byte_sequence_len(5), buffer.len());
+  }
+}
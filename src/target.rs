@@ -0,0 +1,221 @@
+use crate::uri::{validate_absolute, validate_authority};
+use crate::{
+  BufMut, HttpBuilder, HttpEncodeError, InvalidMessageError, InvalidUriError,
+  Method, Uri, Version,
+};
+
+/// The request target of an HTTP request, in one of the four forms
+/// defined by RFC 7230 section 5.3.
+///
+/// Unlike a bare [`Uri`](crate::Uri), which only guarantees that the
+/// request line can be parsed, a `RequestTarget` is checked against the
+/// rules for its form and against the method of the request when it is
+/// used with [`HttpBuilder::request_to`](crate::HttpBuilder::request_to).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// let origin = RequestTarget::OriginForm(Uri::new(b"/index.html"));
+/// let authority = RequestTarget::AuthorityForm(Uri::new(b"example.com:443"));
+///
+/// assert!(origin.check(Method::GET).is_ok());
+/// assert!(authority.check(Method::CONNECT).is_ok());
+///
+/// // Only CONNECT requests may use authority-form.
+/// assert!(authority.check(Method::GET).is_err());
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub enum RequestTarget<'data> {
+  /// An absolute path with an optional query, e.g. `/where?q=now`.
+  ///
+  /// This is the form used for most requests made directly to an origin
+  /// server.
+  OriginForm(Uri<'data>),
+  /// An absolute URI, e.g. `http://example.com/where?q=now`.
+  ///
+  /// This is the form used for requests made to a forward proxy.
+  AbsoluteForm(Uri<'data>),
+  /// A host and port, e.g. `example.com:443`.
+  ///
+  /// This form is only valid for `CONNECT` requests.
+  AuthorityForm(Uri<'data>),
+  /// The single character `*`.
+  ///
+  /// This form is only valid for `OPTIONS` requests.
+  AsteriskForm,
+}
+
+impl<'data> RequestTarget<'data> {
+  /// Get the URI that will be written out for this request target.
+  pub const fn uri(&self) -> Uri<'data> {
+    match *self {
+      Self::OriginForm(uri)
+      | Self::AbsoluteForm(uri)
+      | Self::AuthorityForm(uri) => uri,
+      Self::AsteriskForm => Uri::ASTERISK,
+    }
+  }
+
+  /// Check that this request target is valid for its form and that it
+  /// can be used with `method`.
+  ///
+  /// # Errors
+  /// Returns an error if the URI doesn't match the form of the target or
+  /// if the form cannot be used with `method`.
+  pub fn check(&self, method: Method) -> Result<(), HttpEncodeError> {
+    let is_connect = method == Method::CONNECT;
+    let is_options = method == Method::OPTIONS;
+
+    match *self {
+      Self::OriginForm(uri) => {
+        let bytes = uri.as_bytes();
+        let has_fragment = memchr::memchr(b'#', bytes).is_some();
+        if bytes.first() != Some(&b'/') || has_fragment {
+          return Err(InvalidUriError(()).into());
+        }
+      }
+      Self::AbsoluteForm(uri) => {
+        if !validate_absolute(uri.as_bytes()) {
+          return Err(InvalidUriError(()).into());
+        }
+      }
+      Self::AuthorityForm(uri) => {
+        let bytes = uri.as_bytes();
+        if !validate_authority(bytes, 0, bytes.len(), true) {
+          return Err(InvalidUriError(()).into());
+        }
+      }
+      Self::AsteriskForm => (),
+    }
+
+    match *self {
+      Self::AuthorityForm(_) if !is_connect => Err(
+        InvalidMessageError("Authority-form is only valid for CONNECT").into(),
+      ),
+      Self::AsteriskForm if !is_options => Err(
+        InvalidMessageError("Asterisk-form is only valid for OPTIONS").into(),
+      ),
+      Self::OriginForm(_) | Self::AbsoluteForm(_) | Self::AsteriskForm
+        if is_connect =>
+      {
+        Err(
+          InvalidMessageError("CONNECT requires an authority-form target")
+            .into(),
+        )
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+impl<B: BufMut> HttpBuilder<B> {
+  /// Start an HTTP-style request after checking that `request_target`
+  /// is valid for its form and for `method`.
+  ///
+  /// # Errors
+  /// Returns an error if [`RequestTarget::check`] fails or if there is
+  /// not enough space in the buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut builder = HttpBuilder::request_to(
+  ///     vec![],
+  ///     Method::GET,
+  ///     RequestTarget::AbsoluteForm(Uri::new(b"http://example.com/")),
+  ///     Version::HTTP_1_1
+  /// )?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "GET http://example.com/ HTTP/1.1\r\n\r\n"
+  /// );
+  ///
+  /// assert!(HttpBuilder::request_to(
+  ///     vec![],
+  ///     Method::GET,
+  ///     RequestTarget::AuthorityForm(Uri::new(b"example.com:80")),
+  ///     Version::HTTP_1_1
+  /// ).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn request_to(
+    buffer: B,
+    method: Method,
+    request_target: RequestTarget,
+    version: Version,
+  ) -> Result<Self, HttpEncodeError> {
+    request_target.check(method)?;
+
+    Ok(Self::request(
+      buffer,
+      method,
+      request_target.uri(),
+      version,
+    )?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn form_validation() {
+    let get = Method::GET;
+
+    assert!(RequestTarget::OriginForm(Uri::new(b"/a?b"))
+      .check(get)
+      .is_ok());
+    assert!(RequestTarget::OriginForm(Uri::new(b"a"))
+      .check(get)
+      .is_err());
+    assert!(RequestTarget::OriginForm(Uri::new(b"/#a"))
+      .check(get)
+      .is_err());
+    assert!(RequestTarget::AbsoluteForm(Uri::new(b"/a"))
+      .check(get)
+      .is_err());
+
+    let connect = Method::CONNECT;
+    assert!(RequestTarget::AuthorityForm(Uri::new(b"[::1]:80"))
+      .check(connect)
+      .is_ok());
+    assert!(RequestTarget::AuthorityForm(Uri::new(b"example.com"))
+      .check(connect)
+      .is_err());
+    assert!(RequestTarget::AuthorityForm(Uri::new(b"example.com:"))
+      .check(connect)
+      .is_err());
+    assert!(RequestTarget::OriginForm(Uri::new(b"/"))
+      .check(connect)
+      .is_err());
+  }
+
+  #[test]
+  fn empty_uri() {
+    let empty = unsafe { Uri::new_unchecked(b"") };
+    let targets = [
+      RequestTarget::OriginForm(empty),
+      RequestTarget::AbsoluteForm(empty),
+      RequestTarget::AuthorityForm(empty),
+    ];
+
+    for target in &targets {
+      assert!(target.check(Method::CONNECT).is_err());
+      assert!(target.check(Method::GET).is_err());
+    }
+  }
+
+  #[test]
+  fn asterisk_form_method() {
+    let target = RequestTarget::AsteriskForm;
+
+    assert!(target.check(Method::OPTIONS).is_ok());
+    assert!(target.check(Method::GET).is_err());
+    assert_eq!(target.uri().as_bytes(), b"*");
+  }
+}
@@ -0,0 +1,62 @@
+use crate::{BufMut, HttpBuilder, InsufficientSpaceError};
+
+/// A type that can write itself out as a set of HTTP headers.
+///
+/// With the `derive` feature enabled this can be derived for structs
+/// with named fields. Each field is written out as one header using the
+/// field name converted to `Train-Case`, and `Option` fields are only
+/// written out when they are `Some`. Fields can be renamed with
+/// `#[header(rename = "...")]` or left out with `#[header(skip)]`.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// struct CachePolicy {
+///   max_age: u32,
+/// }
+///
+/// impl ToHeaders for CachePolicy {
+///   fn write_headers<B: BufMut>(
+///     &self,
+///     builder: &mut HttpBuilder<B>,
+///   ) -> Result<(), InsufficientSpaceError> {
+///     let value = format_args!("max-age={}", self.max_age);
+///     builder.header(Header::new("Cache-Control", value))?;
+///     Ok(())
+///   }
+/// }
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// CachePolicy { max_age: 60 }.write_headers(&mut builder)?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Cache-Control: max-age=60\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub trait ToHeaders {
+  /// Write out all the headers for this value to `builder`.
+  fn write_headers<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+  ) -> Result<(), InsufficientSpaceError>;
+}
+
+impl<T: ToHeaders + ?Sized> ToHeaders for &'_ T {
+  fn write_headers<B: BufMut>(
+    &self,
+    builder: &mut HttpBuilder<B>,
+  ) -> Result<(), InsufficientSpaceError> {
+    (**self).write_headers(builder)
+  }
+}
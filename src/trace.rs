@@ -0,0 +1,391 @@
+use crate::{
+  BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidHeaderError,
+};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// The maximum number of list members allowed within a `tracestate`.
+const MAX_MEMBERS: usize = 32;
+
+/// Whether `byte` can appear after the first character of a tracestate
+/// key.
+const fn is_key_char(byte: u8) -> bool {
+  matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'*' | b'/')
+}
+
+/// Whether `key` is a valid tracestate key:
+/// ```text
+/// key              = simple-key / multi-tenant-key
+/// simple-key       = lcalpha 0*255( keychar )
+/// multi-tenant-key = tenant-id "@" system-id
+/// tenant-id        = ( lcalpha / DIGIT ) 0*240( keychar )
+/// system-id        = lcalpha 0*13( keychar )
+/// ```
+const fn is_tracestate_key(key: &str) -> bool {
+  let bytes = key.as_bytes();
+  let mut at = None;
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'@' if at.is_none() => at = Some(idx),
+      byte if is_key_char(byte) => (),
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  match at {
+    None => {
+      !bytes.is_empty() && bytes.len() <= 256 && bytes[0].is_ascii_lowercase()
+    }
+    Some(at) => {
+      let system = bytes.len() - at - 1;
+
+      at != 0
+        && at <= 241
+        && !matches!(bytes[0], b'_' | b'-' | b'*' | b'/')
+        && system != 0
+        && system <= 14
+        && bytes[at + 1].is_ascii_lowercase()
+    }
+  }
+}
+
+/// Whether `value` is a valid tracestate value: up to 256 printable
+/// characters other than `,` and `=` that doesn't end with a space.
+const fn is_tracestate_value(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b',' | b'=' => return false,
+      b' '..=b'~' => (),
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  match bytes.last() {
+    Some(&last) => bytes.len() <= 256 && last != b' ',
+    None => false,
+  }
+}
+
+/// Write out the low bits of `value` as `digits` lowercase hex digits.
+fn write_hex<B: BufMut>(
+  buffer: &mut B,
+  value: u128,
+  digits: usize,
+) -> Result<(), InsufficientSpaceError> {
+  let mut out = [0; 32];
+  for (idx, digit) in out[..digits].iter_mut().enumerate() {
+    let shift = (digits - 1 - idx) * 4;
+    *digit = HEX[(value >> shift) as usize & 0xF];
+  }
+
+  buffer.try_put_slice(&out[..digits])
+}
+
+/// A W3C Trace Context `traceparent` header value.
+///
+/// This writes out version `00` as
+/// `00-<trace-id>-<parent-id>-<trace-flags>` with every field as
+/// fixed-width lowercase hex.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let traceparent = Traceparent::new(
+///   0x4bf92f3577b34da6a3ce929d0e0e4736,
+///   0x00f067aa0ba902b7,
+///   Traceparent::SAMPLED,
+/// );
+///
+/// let mut buffer = Vec::new();
+/// Header::new("traceparent", traceparent).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Traceparent {
+  trace_id: u128,
+  parent_id: u64,
+  flags: u8,
+}
+
+impl Traceparent {
+  /// The trace flag indicating that the caller may have recorded trace
+  /// data.
+  pub const SAMPLED: u8 = 0x01;
+
+  /// Create a `traceparent` value.
+  ///
+  /// # Errors
+  /// Returns an error if `trace_id` or `parent_id` is zero, since
+  /// all-zero ids are invalid.
+  pub const fn try_new(
+    trace_id: u128,
+    parent_id: u64,
+    flags: u8,
+  ) -> Result<Self, InvalidHeaderError> {
+    if trace_id == 0 || parent_id == 0 {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      trace_id,
+      parent_id,
+      flags,
+    })
+  }
+
+  /// Create a `traceparent` value.
+  ///
+  /// # Panics
+  /// Panics if `trace_id` or `parent_id` is zero.
+  pub const fn new(trace_id: u128, parent_id: u64, flags: u8) -> Self {
+    match Self::try_new(trace_id, parent_id, flags) {
+      Ok(traceparent) => traceparent,
+      Err(_) => const_panic!("Trace and parent ids must not be zero"),
+    }
+  }
+
+  /// The id of the whole trace.
+  pub const fn trace_id(&self) -> u128 {
+    self.trace_id
+  }
+
+  /// The id of the caller's span.
+  pub const fn parent_id(&self) -> u64 {
+    self.parent_id
+  }
+
+  /// The trace flags.
+  pub const fn flags(&self) -> u8 {
+    self.flags
+  }
+}
+
+impl HttpWriteable for Traceparent {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(b"00-")?;
+    write_hex(buffer, self.trace_id, 32)?;
+    buffer.try_put_u8(b'-')?;
+    write_hex(buffer, self.parent_id as u128, 16)?;
+    buffer.try_put_u8(b'-')?;
+    write_hex(buffer, self.flags as u128, 2)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (55, Some(55))
+  }
+}
+
+/// A W3C Trace Context `tracestate` header value listing vendor-specific
+/// `key=value` entries.
+///
+/// Keys and values are checked against the Trace Context grammar and
+/// length limits, and there may be at most 32 entries.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const TRACESTATE: Tracestate =
+///   Tracestate::new(&[("rojo", "00f067aa0ba902b7"), ("congo", "t61rcWkgMzE")]);
+///
+/// let mut buffer = Vec::new();
+/// Header::new("tracestate", TRACESTATE).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   buffer,
+///   &b"tracestate: rojo=00f067aa0ba902b7,congo=t61rcWkgMzE\r\n"[..]
+/// );
+/// assert!(Tracestate::try_new(&[("Upper", "x")]).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Tracestate<'data> {
+  entries: &'data [(&'data str, &'data str)],
+}
+
+impl<'data> Tracestate<'data> {
+  /// Create a `tracestate` value from a list of entries, most recently
+  /// updated first.
+  ///
+  /// # Errors
+  /// Returns an error if there are more than 32 entries or if any key or
+  /// value is not valid.
+  pub const fn try_new(
+    entries: &'data [(&'data str, &'data str)],
+  ) -> Result<Self, InvalidHeaderError> {
+    if entries.len() > MAX_MEMBERS {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let mut idx = 0;
+    while idx < entries.len() {
+      let (key, value) = entries[idx];
+
+      if !is_tracestate_key(key) || !is_tracestate_value(value) {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self { entries })
+  }
+
+  /// Create a `tracestate` value from a list of entries, most recently
+  /// updated first.
+  ///
+  /// # Panics
+  /// Panics if there are more than 32 entries or if any key or value is
+  /// not valid.
+  pub const fn new(entries: &'data [(&'data str, &'data str)]) -> Self {
+    match Self::try_new(entries) {
+      Ok(tracestate) => tracestate,
+      Err(_) => const_panic!("Invalid tracestate entry"),
+    }
+  }
+
+  /// The entries in the order they are written out.
+  pub const fn entries(&self) -> &'data [(&'data str, &'data str)] {
+    self.entries
+  }
+}
+
+impl HttpWriteable for Tracestate<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    for (idx, &(key, value)) in self.entries.iter().enumerate() {
+      if idx != 0 {
+        buffer.try_put_u8(b',')?;
+      }
+
+      buffer.try_put_slice(key.as_bytes())?;
+      buffer.try_put_u8(b'=')?;
+      buffer.try_put_slice(value.as_bytes())?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self
+      .entries
+      .iter()
+      .map(|(key, value)| key.len() + value.len() + 2)
+      .sum::<usize>()
+      .saturating_sub(1);
+
+    (len, Some(len))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode<V: HttpWriteable>(value: V) -> String {
+    let mut buffer = Vec::new();
+    value.write_to(&mut buffer).unwrap();
+
+    assert_eq!(value.size_hint(), (buffer.len(), Some(buffer.len())));
+
+    String::from_utf8(buffer).unwrap()
+  }
+
+  #[test]
+  fn traceparent() {
+    assert_eq!(
+      encode(Traceparent::new(1, 0xABC, 0)),
+      "00-00000000000000000000000000000001-0000000000000abc-00"
+    );
+    assert_eq!(
+      encode(Traceparent::new(u128::MAX, u64::MAX, 0xFF)),
+      "00-ffffffffffffffffffffffffffffffff-ffffffffffffffff-ff"
+    );
+    assert!(Traceparent::try_new(0, 1, 0).is_err());
+    assert!(Traceparent::try_new(1, 0, 0).is_err());
+  }
+
+  #[test]
+  fn tracestate_keys() {
+    let long_key = "a".repeat(256);
+    let long_tenant = format!("{}@sys", "1".repeat(241));
+
+    for key in &[
+      "a",
+      "a1_-*/",
+      "tenant@vendor",
+      "1x@y",
+      &long_key,
+      &long_tenant,
+    ] {
+      assert!(is_tracestate_key(key), "{}", key);
+    }
+
+    let too_long = "a".repeat(257);
+    let tenant_too_long = format!("{}@sys", "1".repeat(242));
+
+    for key in &[
+      "",
+      "1a",
+      "A",
+      "a b",
+      "@vendor",
+      "tenant@",
+      "tenant@1x",
+      "-x@y",
+      "a@b@c",
+      "t@abcdefghijklmno",
+      &too_long,
+      &tenant_too_long,
+    ] {
+      assert!(!is_tracestate_key(key), "{}", key);
+    }
+  }
+
+  #[test]
+  fn tracestate_values() {
+    assert!(is_tracestate_value("a b"));
+    assert!(is_tracestate_value(&"x".repeat(256)));
+
+    for value in &["", "a ", "a,b", "a=b", "a\tb", "é", &"x".repeat(257)] {
+      assert!(!is_tracestate_value(value), "{:?}", value);
+    }
+  }
+
+  #[test]
+  fn tracestate() {
+    assert_eq!(encode(Tracestate::new(&[])), "");
+    assert_eq!(
+      encode(Tracestate::new(&[("a", "1"), ("b@c", "2")])),
+      "a=1,b@c=2"
+    );
+
+    let entries = [("a", "1"); 33];
+    assert!(Tracestate::try_new(&entries[..32]).is_ok());
+    assert!(Tracestate::try_new(&entries).is_err());
+  }
+}
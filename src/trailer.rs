@@ -0,0 +1,268 @@
+use crate::{
+  BufMut, CheckedField, FallibleBufMut, Header, HttpBuilder, HttpEncodeError,
+  HttpWriteable, InsufficientSpaceError, InvalidHeaderError,
+  InvalidMessageError,
+};
+
+/// Fields that recipients need before the body is processed and so must
+/// not be sent within trailers (RFC 7230 section 4.1.2).
+const FORBIDDEN: &[&str] = &[
+  // Message framing
+  "Content-Length",
+  "Transfer-Encoding",
+  "Trailer",
+  // Routing
+  "Host",
+  // Request modifiers
+  "Cache-Control",
+  "Expect",
+  "Max-Forwards",
+  "Pragma",
+  "Range",
+  "TE",
+  "If-Match",
+  "If-None-Match",
+  "If-Modified-Since",
+  "If-Unmodified-Since",
+  "If-Range",
+  // Authentication
+  "Authorization",
+  "Proxy-Authorization",
+  "WWW-Authenticate",
+  "Proxy-Authenticate",
+  "Cookie",
+  "Set-Cookie",
+  // Response control data
+  "Age",
+  "Date",
+  "Expires",
+  "Location",
+  "Retry-After",
+  "Vary",
+  "Warning",
+  // Payload processing
+  "Content-Encoding",
+  "Content-Type",
+  "Content-Range",
+];
+
+/// Whether `field` must not be sent within trailers.
+const fn is_forbidden(field: &CheckedField) -> bool {
+  let mut idx = 0;
+  while idx < FORBIDDEN.len() {
+    if field.eq_ignore_case(FORBIDDEN[idx]) {
+      return true;
+    }
+
+    idx += 1;
+  }
+
+  false
+}
+
+/// A `Trailer` header value announcing the fields that will be sent
+/// within the trailers of a chunked body.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const FIELDS: [CheckedField; 1] = [CheckedField::new("Content-Digest")];
+///
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Trailer", Trailer::new(&FIELDS)))?;
+///
+/// let mut body = builder.chunked(TransferEncoding::CHUNKED)?;
+/// body.chunk(b"hello")?;
+///
+/// let mut trailers = body.trailers()?;
+/// trailers.header(Header::checked_new(FIELDS[0], "sha-256=:LPJN:"))?;
+/// let output = trailers.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 200 OK\r\n\
+///   Trailer: Content-Digest\r\n\
+///   Transfer-Encoding: chunked\r\n\
+///   \r\n\
+///   5\r\nhello\r\n\
+///   0\r\n\
+///   Content-Digest: sha-256=:LPJN:\r\n\
+///   \r\n"
+/// );
+/// assert!(Trailer::try_new(&[CheckedField::new("content-length")]).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Trailer<'data> {
+  fields: &'data [CheckedField<'data>],
+}
+
+impl<'data> Trailer<'data> {
+  /// Create a `Trailer` value from a list of field names.
+  ///
+  /// # Errors
+  /// Returns an error if `fields` is empty or contains a field that is
+  /// not allowed within trailers, such as `Content-Length`, `Host`, or
+  /// `Authorization`.
+  pub const fn try_new(
+    fields: &'data [CheckedField<'data>],
+  ) -> Result<Self, InvalidHeaderError> {
+    if fields.is_empty() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    let mut idx = 0;
+    while idx < fields.len() {
+      if is_forbidden(&fields[idx]) {
+        return Err(InvalidHeaderError(()));
+      }
+
+      idx += 1;
+    }
+
+    Ok(Self { fields })
+  }
+
+  /// Create a `Trailer` value from a list of field names.
+  ///
+  /// # Panics
+  /// Panics if `fields` is empty or contains a field that is not allowed
+  /// within trailers.
+  pub const fn new(fields: &'data [CheckedField<'data>]) -> Self {
+    match Self::try_new(fields) {
+      Ok(trailer) => trailer,
+      Err(_) => const_panic!("Field is not allowed within trailers"),
+    }
+  }
+
+  /// The announced field names, in order.
+  pub const fn fields(&self) -> &'data [CheckedField<'data>] {
+    self.fields
+  }
+}
+
+impl HttpWriteable for Trailer<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    for (idx, field) in self.fields.iter().enumerate() {
+      if idx != 0 {
+        buffer.try_put_slice(b", ")?;
+      }
+
+      buffer.try_put_slice(field.as_str().as_bytes())?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self
+      .fields
+      .iter()
+      .map(|field| field.as_str().len() + 2)
+      .sum::<usize>()
+      .saturating_sub(2);
+
+    (len, Some(len))
+  }
+}
+
+/// Writes out the trailer fields at the end of a chunked body.
+///
+/// This is created by
+/// [`ChunkedWriter::trailers`](crate::ChunkedWriter::trailers). When the
+/// body was started from a
+/// [`ValidatingBuilder`](crate::ValidatingBuilder), fields that are not
+/// allowed within trailers are rejected.
+pub struct TrailerWriter<B: BufMut> {
+  builder: HttpBuilder<B>,
+  validated: bool,
+}
+
+impl<B: BufMut> TrailerWriter<B> {
+  pub(crate) fn new(buffer: B, validated: bool) -> Self {
+    Self {
+      builder: HttpBuilder::from_buffer(buffer),
+      validated,
+    }
+  }
+
+  /// Write out a trailer field.
+  ///
+  /// # Errors
+  /// Returns an error if validation is enabled and the field is not
+  /// allowed within trailers or if there is not enough space in the
+  /// buffer.
+  pub fn header<'data, V, H>(
+    &mut self,
+    header: H,
+  ) -> Result<&mut Self, HttpEncodeError>
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    let header = header.into();
+
+    if self.validated && is_forbidden(&header.field) {
+      return Err(
+        InvalidMessageError("Field is not allowed within trailers").into(),
+      );
+    }
+
+    self.builder.header(header)?;
+    Ok(self)
+  }
+
+  /// Finish off the trailers and return the `BufMut` instance that was
+  /// being written to.
+  pub fn finish(self) -> Result<B, InsufficientSpaceError> {
+    self.builder.finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn trailer() {
+    let fields = [CheckedField::new("Server-Timing"), CheckedField::new("X-A")];
+    let trailer = Trailer::new(&fields);
+
+    let mut buffer = Vec::new();
+    trailer.write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"Server-Timing, X-A");
+    assert_eq!(trailer.size_hint(), (buffer.len(), Some(buffer.len())));
+  }
+
+  #[test]
+  fn forbidden_fields() {
+    for field in &["Content-Length", "host", "TRANSFER-ENCODING", "cookie"] {
+      let fields = [CheckedField::new(field)];
+      assert!(Trailer::try_new(&fields).is_err(), "{}", field);
+    }
+
+    assert!(Trailer::try_new(&[]).is_err());
+  }
+
+  #[test]
+  fn validated_writer() {
+    let mut writer = TrailerWriter::new(Vec::new(), true);
+    assert!(writer.header(Header::new("Authorization", "x")).is_err());
+    writer.header(Header::new("X-Checksum", "1")).unwrap();
+    assert_eq!(writer.finish().unwrap(), b"X-Checksum: 1\r\n\r\n");
+
+    let mut writer = TrailerWriter::new(Vec::new(), false);
+    writer.header(Header::new("Authorization", "x")).unwrap();
+    assert_eq!(writer.finish().unwrap(), b"Authorization: x\r\n\r\n");
+  }
+}
@@ -1,5 +1,9 @@
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
 use crate::{
-  validate_uri, BufMut, FallibleBufMut, InsufficientSpaceError, InvalidUriError,
+  validate_uri, BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+  InvalidUriError,
 };
 
 /// The URI component of an HTTP request.
@@ -23,17 +27,22 @@ use crate::{
 /// let _ = Uri::try_new(b"/uri\nnewline").unwrap_err();    // Not allowed
 /// let _ = Uri::try_new(b"/uri\rlinefeed").unwrap_err();   // Not allowed
 /// ```
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Uri<'data> {
   uri: &'data [u8],
 }
 
 impl<'data> Uri<'data> {
-  pub(crate) fn write_to<B: BufMut>(
-    &self,
-    buffer: &mut B,
-  ) -> Result<(), InsufficientSpaceError> {
-    buffer.try_put_slice(self.uri)
+  /// The asterisk-form request target (`*`).
+  ///
+  /// This is only valid as the target of an `OPTIONS` request, where it
+  /// refers to the server as a whole. See
+  /// [`HttpBuilder::options_all`](crate::HttpBuilder::options_all).
+  pub const ASTERISK: Uri<'static> = Uri { uri: b"*" };
+
+  /// Whether this is the asterisk-form request target (`*`).
+  pub const fn is_asterisk(&self) -> bool {
+    self.uri.len() == 1 && self.uri[0] == b'*'
   }
 
   /// Create a `Uri` instance with the provided byte string.
@@ -103,12 +112,615 @@ impl<'data> Uri<'data> {
     }
   }
 
+  /// Create a `Uri` instance after checking that `uri` only contains
+  /// characters allowed by RFC 3986.
+  ///
+  /// The permissive constructors accept anything that keeps the request
+  /// line parseable, including control characters and non-ASCII bytes.
+  /// This constructor only accepts unreserved and reserved characters
+  /// along with well-formed percent-encoded octets, which guarantees
+  /// that the request target will be accepted by other implementations.
+  ///
+  /// # Errors
+  /// Returns an error if `uri` contains any character not allowed within
+  /// a URI or a `%` that isn't followed by two hex digits.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let _ = Uri::try_new_strict(b"/search?q=caf%C3%A9").unwrap();
+  ///
+  /// let _ = Uri::try_new_strict(b"/\xFF").unwrap_err();
+  /// let _ = Uri::try_new_strict(b"/tab\there").unwrap_err();
+  /// let _ = Uri::try_new_strict(b"/100%").unwrap_err();
+  /// ```
+  pub const fn try_new_strict(
+    uri: &'data [u8],
+  ) -> Result<Self, InvalidUriError> {
+    if !validate_strict(uri) {
+      return Err(InvalidUriError(()));
+    }
+
+    Ok(Self { uri })
+  }
+
+  /// Create a `Uri` instance after checking that `uri` only contains
+  /// characters allowed by RFC 3986.
+  ///
+  /// See [`try_new_strict`](Self::try_new_strict) for details.
+  ///
+  /// # Panics
+  /// Panics if `uri` contains any invalid characters.
+  pub const fn new_strict(uri: &'data [u8]) -> Self {
+    match Self::try_new_strict(uri) {
+      Ok(uri) => uri,
+      Err(_) => const_panic!("URI contained invalid character"),
+    }
+  }
+
+  /// Create a `Uri` for an absolute-form request target, as is needed
+  /// when sending requests to a forward proxy.
+  ///
+  /// Along with the checks done by `try_new`, this checks that `uri`
+  /// has the form `scheme://host[:port][/path][?query]`. URIs with
+  /// userinfo (`user@host`) or a fragment (`#fragment`) are rejected
+  /// since they must not be sent within a request target.
+  ///
+  /// # Errors
+  /// Returns an error if `uri` is not a valid absolute-form target.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let _ = Uri::try_absolute(b"http://example.com/index.html?q=1").unwrap();
+  /// let _ = Uri::try_absolute(b"http://[::1]:8080").unwrap();
+  ///
+  /// let _ = Uri::try_absolute(b"/index.html").unwrap_err();
+  /// let _ = Uri::try_absolute(b"http://example.com/#top").unwrap_err();
+  /// let _ = Uri::try_absolute(b"http://user@example.com/").unwrap_err();
+  /// ```
+  pub const fn try_absolute(uri: &'data [u8]) -> Result<Self, InvalidUriError> {
+    if !validate_absolute(uri) {
+      return Err(InvalidUriError(()));
+    }
+
+    Ok(Self { uri })
+  }
+
+  /// Create a `Uri` for an absolute-form request target.
+  ///
+  /// See [`try_absolute`](Self::try_absolute) for details.
+  ///
+  /// # Panics
+  /// Panics if `uri` is not a valid absolute-form target.
+  pub const fn absolute(uri: &'data [u8]) -> Self {
+    match Self::try_absolute(uri) {
+      Ok(uri) => uri,
+      Err(_) => const_panic!("Invalid absolute-form URI"),
+    }
+  }
+
+  /// Get the path component of this URI.
+  ///
+  /// The scheme and authority, if present, are skipped along with the
+  /// query and fragment. Asterisk-form and authority-form targets do not
+  /// have a well-defined path so the result for them is unspecified.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert_eq!(Uri::new(b"/a/b?c#d").path(), b"/a/b");
+  /// assert_eq!(Uri::new(b"http://example.com/a?b").path(), b"/a");
+  /// assert_eq!(Uri::new(b"http://example.com?b").path(), b"");
+  /// ```
+  pub fn path(&self) -> &'data [u8] {
+    let (start, end) = self.path_range();
+    &self.uri[start..end]
+  }
+
+  /// Get the query component of this URI, without the leading `?`.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert_eq!(Uri::new(b"/a/b?c=d#e").query(), Some(&b"c=d"[..]));
+  /// assert_eq!(Uri::new(b"/a/b?").query(), Some(&b""[..]));
+  /// assert_eq!(Uri::new(b"/a/b").query(), None);
+  /// ```
+  pub fn query(&self) -> Option<&'data [u8]> {
+    let (_, end) = self.path_range();
+    let rest = &self.uri[end..];
+
+    if rest.first() != Some(&b'?') {
+      return None;
+    }
+
+    let end = memchr::memchr(b'#', rest).unwrap_or(rest.len());
+    Some(&rest[1..end])
+  }
+
+  /// Resolve `relative` against `base` as described by RFC 3986
+  /// section 5.2.
+  ///
+  /// Fragments are dropped since they are never sent as part of a
+  /// request target. The result is returned as a
+  /// [`UriBuilder`](crate::UriBuilder) so that it can be extended
+  /// further.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// let base = Uri::new(b"http://a.com/b/c/d?q");
+  /// let join = |rel: &[u8]| Uri::join(base, Uri::new(rel));
+  ///
+  /// assert_eq!(join(b"e").as_uri().as_bytes(), b"http://a.com/b/c/e");
+  /// assert_eq!(join(b"../e?x").as_uri().as_bytes(), b"http://a.com/b/e?x");
+  /// assert_eq!(join(b"/e").as_uri().as_bytes(), b"http://a.com/e");
+  /// assert_eq!(join(b"?y#z").as_uri().as_bytes(), b"http://a.com/b/c/d?y");
+  /// assert_eq!(join(b"//b.org/").as_uri().as_bytes(), b"http://b.org/");
+  /// ```
+  #[cfg(feature = "std")]
+  pub fn join(base: Uri<'_>, relative: Uri<'_>) -> crate::UriBuilder {
+    let rel = relative.as_bytes();
+    let rel = &rel[..memchr::memchr(b'#', rel).unwrap_or(rel.len())];
+    let (rel_start, rel_end) = relative.path_range();
+    let (base_start, base_end) = base.path_range();
+
+    let mut target = Vec::with_capacity(base.uri.len() + rel.len());
+
+    if rel_start != 0 {
+      // The reference has a scheme or authority of its own.
+      if rel[0] == b'/' {
+        let scheme = memchr::memchr(b':', base.uri).map_or(0, |i| i + 1);
+        target.extend_from_slice(&base.uri[..scheme]);
+      }
+      target.extend_from_slice(&rel[..rel_start]);
+      remove_dot_segments(&mut target, &rel[rel_start..rel_end]);
+    } else {
+      target.extend_from_slice(&base.uri[..base_start]);
+      let base_path = &base.uri[base_start..base_end];
+
+      if rel_end == 0 {
+        target.extend_from_slice(base_path);
+        if rel.is_empty() {
+          if let Some(query) = base.query() {
+            target.push(b'?');
+            target.extend_from_slice(query);
+          }
+        }
+      } else if rel[0] == b'/' {
+        remove_dot_segments(&mut target, &rel[..rel_end]);
+      } else {
+        let mut merged = Vec::with_capacity(base_path.len() + rel_end + 1);
+        match memchr::memrchr(b'/', base_path) {
+          Some(i) => merged.extend_from_slice(&base_path[..=i]),
+          None if base_start != 0 => merged.push(b'/'),
+          None => (),
+        }
+        merged.extend_from_slice(&rel[..rel_end]);
+        remove_dot_segments(&mut target, &merged);
+      }
+    }
+
+    target.extend_from_slice(&rel[rel_end..]);
+    crate::UriBuilder::from_target(target)
+  }
+
+  /// Returns the start and end offsets of the path within this URI.
+  fn path_range(&self) -> (usize, usize) {
+    let uri = self.uri;
+    let mut start = 0;
+
+    // scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+    if matches!(uri.first(), Some(b) if b.is_ascii_alphabetic()) {
+      let scheme_end = uri
+        .iter()
+        .position(|&b| !(b.is_ascii_alphanumeric() || b"+-.".contains(&b)));
+
+      if let Some(i) = scheme_end.filter(|&i| uri[i] == b':') {
+        start = i + 1;
+      }
+    }
+
+    if uri[start..].starts_with(b"//") {
+      start += 2;
+      start += uri[start..]
+        .iter()
+        .position(|&b| b == b'/' || b == b'?' || b == b'#')
+        .unwrap_or(uri.len() - start);
+    }
+
+    let end = start
+      + memchr::memchr2(b'?', b'#', &uri[start..]).unwrap_or(uri.len() - start);
+
+    (start, end)
+  }
+
+  /// Get the contents of this URI as a string slice, if it is valid
+  /// UTF-8.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// assert_eq!(Uri::new(b"/index.html").as_str(), Some("/index.html"));
+  /// assert_eq!(Uri::new(b"/\xFF").as_str(), None);
+  /// ```
+  pub fn as_str(&self) -> Option<&'data str> {
+    core::str::from_utf8(self.uri).ok()
+  }
+
   /// Get the contents of this URI as a byte slice.
   pub const fn as_bytes(&self) -> &'data [u8] {
     self.uri
   }
 }
 
+/// Create a `Uri<'static>` from a path literal by percent-encoding it at
+/// compile time.
+///
+/// Spaces, non-ASCII characters, and any other bytes that are not allowed
+/// within a URI (including `%` and `#`) are percent-encoded. Reserved
+/// characters such as `/`, `?`, `&`, and `=` are kept as-is. The result
+/// is always accepted by [`Uri::try_new_strict`](crate::Uri::try_new_strict)
+/// and can be used to initialize constants.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// const MENU: Uri<'static> = percent_encoded_uri!("/café/menu du jour");
+///
+/// assert_eq!(MENU.as_bytes(), b"/caf%C3%A9/menu%20du%20jour");
+/// ```
+#[macro_export]
+macro_rules! percent_encoded_uri {
+  ($path:expr) => {{
+    const INPUT: &str = $path;
+    const LEN: usize = $crate::__percent_encoded_len(INPUT.as_bytes());
+    const ENCODED: [u8; LEN] = $crate::__percent_encode(INPUT.as_bytes());
+    const URI: $crate::Uri<'static> = $crate::Uri::new_const(&ENCODED);
+    URI
+  }};
+}
+
+#[doc(hidden)]
+pub const fn __percent_encoded_len(path: &[u8]) -> usize {
+  let mut len = 0;
+  let mut i = 0;
+  while i < path.len() {
+    len += if is_static_path_char(path[i]) { 1 } else { 3 };
+    i += 1;
+  }
+
+  len
+}
+
+#[doc(hidden)]
+pub const fn __percent_encode<const N: usize>(path: &[u8]) -> [u8; N] {
+  const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+  if __percent_encoded_len(path) != N {
+    const_panic!("Output length does not match the encoded length");
+  }
+
+  let mut out = [0u8; N];
+  let mut i = 0;
+  let mut j = 0;
+  while i < path.len() {
+    let byte = path[i];
+    if is_static_path_char(byte) {
+      out[j] = byte;
+      j += 1;
+    } else {
+      out[j] = b'%';
+      out[j + 1] = HEX[(byte >> 4) as usize];
+      out[j + 2] = HEX[(byte & 0xF) as usize];
+      j += 3;
+    }
+    i += 1;
+  }
+
+  out
+}
+
+const fn is_static_path_char(byte: u8) -> bool {
+  matches!(byte, b':' | b'@' | b'/' | b'?') || is_unreserved_or_sub_delim(byte)
+}
+
+impl<'data> TryFrom<&'data [u8]> for Uri<'data> {
+  type Error = InvalidUriError;
+
+  /// Equivalent to [`Uri::try_new`](crate::Uri::try_new).
+  fn try_from(uri: &'data [u8]) -> Result<Self, Self::Error> {
+    Self::try_new(uri)
+  }
+}
+
+impl<'data> TryFrom<&'data str> for Uri<'data> {
+  type Error = InvalidUriError;
+
+  /// Equivalent to [`Uri::try_new`](crate::Uri::try_new).
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # use std::convert::TryInto;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let uri: Uri = "/index.html".try_into()?;
+  /// assert_eq!(uri.as_bytes(), b"/index.html");
+  /// # Ok(())
+  /// # }
+  /// ```
+  fn try_from(uri: &'data str) -> Result<Self, Self::Error> {
+    Self::try_new(uri.as_bytes())
+  }
+}
+
+/// A `Uri` never contains CR or LF so it can be used as a header value
+/// as-is.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(201, "Created")
+/// )?;
+/// builder.header(Header::new("Location", Uri::new(b"/items/42")))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "HTTP/1.1 201 Created\r\n\
+///   Location: /items/42\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl HttpWriteable for Uri<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.uri)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.uri.len(), Some(self.uri.len()))
+  }
+}
+
+/// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT
+/// CHARACTER`.
+impl fmt::Display for Uri<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut bytes = self.uri;
+
+    loop {
+      let error = match core::str::from_utf8(bytes) {
+        Ok(valid) => return f.write_str(valid),
+        Err(error) => error,
+      };
+
+      let (valid, rest) = bytes.split_at(error.valid_up_to());
+      f.write_str(core::str::from_utf8(valid).map_err(|_| fmt::Error)?)?;
+      f.write_char('\u{FFFD}')?;
+
+      match error.error_len() {
+        Some(len) => bytes = &rest[len..],
+        // The URI ended partway through a UTF-8 sequence.
+        None => return Ok(()),
+      }
+    }
+  }
+}
+
+/// Append `path` to `out` with all `.` and `..` segments removed as
+/// described by RFC 3986 section 5.2.4.
+#[cfg(feature = "std")]
+fn remove_dot_segments(out: &mut Vec<u8>, mut path: &[u8]) {
+  let base = out.len();
+
+  while !path.is_empty() {
+    if path.starts_with(b"../") {
+      path = &path[3..];
+    } else if path.starts_with(b"./") || path.starts_with(b"/./") {
+      path = &path[2..];
+    } else if path == b"/." {
+      path = b"/";
+    } else if path.starts_with(b"/../") || path == b"/.." {
+      path = if path.len() == 3 { b"/" } else { &path[3..] };
+      let last = memchr::memrchr(b'/', &out[base..]).unwrap_or(0);
+      out.truncate(base + last);
+    } else if path == b"." || path == b".." {
+      path = b"";
+    } else {
+      let end = memchr::memchr(b'/', &path[1..]).map_or(path.len(), |i| i + 1);
+      out.extend_from_slice(&path[..end]);
+      path = &path[end..];
+    }
+  }
+}
+
+/// Validates that `host` matches the `host` rule from RFC 3986:
+/// ```text
+/// host        = IP-literal / IPv4address / reg-name
+/// IP-literal  = "[" ( IPv6address / IPvFuture  ) "]"
+/// reg-name    = *( unreserved / pct-encoded / sub-delims )
+/// ```
+///
+/// Empty hosts are rejected and IP-literals are only checked for
+/// characters that could appear in them.
+pub(crate) const fn validate_host(host: &[u8]) -> bool {
+  validate_host_range(host, 0, host.len())
+}
+
+/// Same as `validate_host` but only checks `bytes[start..end]`.
+const fn validate_host_range(bytes: &[u8], start: usize, end: usize) -> bool {
+  if start >= end {
+    return false;
+  }
+
+  if bytes[start] == b'[' {
+    if end - start < 3 || bytes[end - 1] != b']' {
+      return false;
+    }
+
+    let mut i = start + 1;
+    while i < end - 1 {
+      if !is_unreserved_or_sub_delim(bytes[i]) && bytes[i] != b':' {
+        return false;
+      }
+      i += 1;
+    }
+
+    return true;
+  }
+
+  let mut i = start;
+  while i < end {
+    match bytes[i] {
+      b'%' => {
+        if i + 2 >= end
+          || !bytes[i + 1].is_ascii_hexdigit()
+          || !bytes[i + 2].is_ascii_hexdigit()
+        {
+          return false;
+        }
+        i += 3;
+      }
+      byte if is_unreserved_or_sub_delim(byte) => i += 1,
+      _ => return false,
+    }
+  }
+
+  true
+}
+
+/// Validates that `uri` only contains characters from RFC 3986:
+/// ```text
+/// unreserved / reserved / pct-encoded
+/// ```
+const fn validate_strict(uri: &[u8]) -> bool {
+  let mut i = 0;
+  while i < uri.len() {
+    match uri[i] {
+      b'%' => {
+        if i + 2 >= uri.len()
+          || !uri[i + 1].is_ascii_hexdigit()
+          || !uri[i + 2].is_ascii_hexdigit()
+        {
+          return false;
+        }
+        i += 3;
+      }
+      b':' | b'/' | b'?' | b'#' | b'[' | b']' | b'@' => i += 1,
+      byte if is_unreserved_or_sub_delim(byte) => i += 1,
+      _ => return false,
+    }
+  }
+
+  !uri.is_empty()
+}
+
+/// Validates that `uri` is an absolute-form request target without a
+/// fragment or userinfo:
+/// ```text
+/// absolute-form = scheme "://" host [ ":" port ] path-abempty [ "?" query ]
+/// ```
+pub(crate) const fn validate_absolute(uri: &[u8]) -> bool {
+  // scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+  if uri.is_empty() || !uri[0].is_ascii_alphabetic() {
+    return false;
+  }
+
+  let mut i = 1;
+  while i < uri.len() {
+    match uri[i] {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'-' | b'.' => i += 1,
+      _ => break,
+    }
+  }
+
+  if i + 3 > uri.len()
+    || uri[i] != b':'
+    || uri[i + 1] != b'/'
+    || uri[i + 2] != b'/'
+  {
+    return false;
+  }
+
+  // authority = host [ ":" port ]
+  let host_start = i + 3;
+  let mut end = host_start;
+  while end < uri.len() && uri[end] != b'/' && uri[end] != b'?' {
+    end += 1;
+  }
+
+  if !validate_authority(uri, host_start, end, false) {
+    return false;
+  }
+
+  // path-abempty [ "?" query ] with no fragment
+  let mut i = end;
+  while i < uri.len() {
+    match uri[i] {
+      b' ' | b'\r' | b'\n' | b'#' => return false,
+      _ => i += 1,
+    }
+  }
+
+  true
+}
+
+/// Validates that `bytes[start..end]` is an authority without userinfo:
+/// ```text
+/// authority = host [ ":" port ]
+/// ```
+///
+/// If `port_required` is set then the port must be present and non-empty.
+pub(crate) const fn validate_authority(
+  bytes: &[u8],
+  start: usize,
+  end: usize,
+  port_required: bool,
+) -> bool {
+  // The port is everything after the last ':', unless that ':' is
+  // within an IP-literal.
+  let mut host_end = end;
+  let mut j = end;
+  while j > start {
+    j -= 1;
+    match bytes[j] {
+      b':' => {
+        host_end = j;
+        break;
+      }
+      b'0'..=b'9' => (),
+      _ => break,
+    }
+  }
+
+  if port_required && (host_end == end || host_end + 1 == end) {
+    return false;
+  }
+
+  validate_host_range(bytes, start, host_end)
+}
+
+pub(crate) const fn is_unreserved_or_sub_delim(byte: u8) -> bool {
+  matches!(
+    byte,
+    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+      | b'-' | b'.' | b'_' | b'~'
+      | b'!' | b'$' | b'&' | b'\'' | b'(' | b')'
+      | b'*' | b'+' | b',' | b';' | b'='
+  )
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -160,6 +772,162 @@ mod tests {
     }
   }
 
+  #[test]
+  fn asterisk() {
+    assert!(Uri::ASTERISK.is_asterisk());
+    assert!(Uri::new(b"*").is_asterisk());
+    assert!(!Uri::new(b"/*").is_asterisk());
+  }
+
+  #[test]
+  fn host_validation() {
+    assert!(validate_host(b"example.com"));
+    assert!(validate_host(b"127.0.0.1"));
+    assert!(validate_host(b"[::1]"));
+    assert!(validate_host(b"caf%C3%A9.example"));
+
+    assert!(!validate_host(b""));
+    assert!(!validate_host(b"[]"));
+    assert!(!validate_host(b"[::1"));
+    assert!(!validate_host(b"::1"));
+    assert!(!validate_host(b"exa mple.com"));
+    assert!(!validate_host(b"example.com/path"));
+    assert!(!validate_host(b"user@example.com"));
+    assert!(!validate_host(b"bad%2"));
+    assert!(!validate_host(b"bad%zz"));
+  }
+
+  #[test]
+  fn path_and_query() {
+    let uri = Uri::new(b"http://example.com");
+    assert_eq!(uri.path(), b"");
+    assert_eq!(uri.query(), None);
+
+    let uri = Uri::new(b"/a:b?c?d#e?f");
+    assert_eq!(uri.path(), b"/a:b");
+    assert_eq!(uri.query(), Some(&b"c?d"[..]));
+
+    let uri = Uri::new(b"/#?");
+    assert_eq!(uri.path(), b"/");
+    assert_eq!(uri.query(), None);
+
+    let uri = unsafe { Uri::new_unchecked(b"") };
+    assert_eq!(uri.path(), b"");
+    assert_eq!(uri.query(), None);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn join_rfc3986_examples() {
+    let base = Uri::new(b"http://a/b/c/d;p?q");
+    let cases: &[(&[u8], &[u8])] = &[
+      (b"g:h", b"g:h"),
+      (b"g", b"http://a/b/c/g"),
+      (b"./g", b"http://a/b/c/g"),
+      (b"g/", b"http://a/b/c/g/"),
+      (b"/g", b"http://a/g"),
+      (b"//g", b"http://g"),
+      (b"?y", b"http://a/b/c/d;p?y"),
+      (b"g?y", b"http://a/b/c/g?y"),
+      (b"#s", b"http://a/b/c/d;p?q"),
+      (b"g#s", b"http://a/b/c/g"),
+      (b";x", b"http://a/b/c/;x"),
+      (b".", b"http://a/b/c/"),
+      (b"./", b"http://a/b/c/"),
+      (b"..", b"http://a/b/"),
+      (b"../", b"http://a/b/"),
+      (b"../g", b"http://a/b/g"),
+      (b"../..", b"http://a/"),
+      (b"../../g", b"http://a/g"),
+      (b"../../../g", b"http://a/g"),
+      (b"/./g", b"http://a/g"),
+      (b"/../g", b"http://a/g"),
+      (b"g.", b"http://a/b/c/g."),
+      (b"..g", b"http://a/b/c/..g"),
+      (b"./../g", b"http://a/b/g"),
+      (b"g/./h", b"http://a/b/c/g/h"),
+      (b"g/../h", b"http://a/b/c/h"),
+      (b"g;x=1/../y", b"http://a/b/c/y"),
+      (b"g?y/./x", b"http://a/b/c/g?y/./x"),
+    ];
+
+    for &(relative, expected) in cases {
+      let joined = Uri::join(base, Uri::new(relative));
+      assert_eq!(
+        joined.as_uri().as_bytes(),
+        expected,
+        "{}",
+        String::from_utf8_lossy(relative)
+      );
+    }
+  }
+
+  #[test]
+  fn const_percent_encoding() {
+    const EMPTY: [u8; 0] = __percent_encode(b"");
+    const ENCODED: [u8; 18] = __percent_encode(b"/a b%#\r\n");
+
+    assert_eq!(EMPTY, *b"");
+    assert_eq!(&ENCODED, b"/a%20b%25%23%0D%0A");
+    assert!(validate_strict(&ENCODED));
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn display_replaces_invalid_utf8() {
+    assert_eq!(Uri::new(b"/caf\xC3\xA9").to_string(), "/café");
+    assert_eq!(Uri::new(b"/a\xFF\xFEb").to_string(), "/a\u{FFFD}\u{FFFD}b");
+    assert_eq!(Uri::new(b"/a\xE2\x82b").to_string(), "/a\u{FFFD}b");
+    assert_eq!(Uri::new(b"/a\xE2\x82").to_string(), "/a\u{FFFD}");
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn usable_as_map_key() {
+    let mut routes = std::collections::HashSet::new();
+    routes.insert(Uri::new(b"/a"));
+
+    assert!(routes.contains(&Uri::new_const(b"/a")));
+    assert!(!routes.contains(&Uri::new(b"/b")));
+  }
+
+  #[test]
+  fn strict_validation() {
+    assert!(validate_strict(b"/"));
+    assert!(validate_strict(b"*"));
+    assert!(validate_strict(b"http://[::1]:80/a;b?c=d&e#f"));
+    assert!(validate_strict(b"/%2f%2F"));
+
+    assert!(!validate_strict(b""));
+    assert!(!validate_strict(b"/a b"));
+    assert!(!validate_strict(b"/\x00"));
+    assert!(!validate_strict(b"/\x7F"));
+    assert!(!validate_strict(b"/\xC3\xA9"));
+    assert!(!validate_strict(b"/\"quoted\""));
+    assert!(!validate_strict(b"/{}"));
+    assert!(!validate_strict(b"/%"));
+    assert!(!validate_strict(b"/%a"));
+    assert!(!validate_strict(b"/%gg"));
+  }
+
+  #[test]
+  fn absolute_validation() {
+    assert!(validate_absolute(b"http://example.com"));
+    assert!(validate_absolute(b"https://example.com:443/"));
+    assert!(validate_absolute(b"h2+x.y-z://a/b?c=d"));
+    assert!(validate_absolute(b"http://[2001:db8::7]:80/x"));
+    assert!(validate_absolute(b"http://example.com?query"));
+
+    assert!(!validate_absolute(b""));
+    assert!(!validate_absolute(b"1http://example.com"));
+    assert!(!validate_absolute(b"http:/example.com"));
+    assert!(!validate_absolute(b"http://"));
+    assert!(!validate_absolute(b"http://:80/"));
+    assert!(!validate_absolute(b"http://a b/"));
+    assert!(!validate_absolute(b"http://example.com/a b"));
+    assert!(!validate_absolute(b"http://example.com/#frag"));
+  }
+
   uri_invalid! {
     empty       => b"";
     only_space  => b" ";
@@ -0,0 +1,190 @@
+use crate::uri::is_unreserved_or_sub_delim;
+use crate::Uri;
+
+/// Builds a request target (`/path?query`) out of raw, unencoded,
+/// components.
+///
+/// Path segments and query parameters are percent-encoded as they are
+/// added so any byte string can be used, including ones containing
+/// spaces, line breaks, or non-ASCII characters. The resulting target
+/// is always a valid [`Uri`](crate::Uri).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// let mut builder = UriBuilder::new();
+/// builder
+///   .segment("files")
+///   .segment("my report.pdf")
+///   .query("lang", "fr-CA")
+///   .query("q", "a&b");
+///
+/// assert_eq!(
+///   builder.as_uri().as_bytes(),
+///   &b"/files/my%20report.pdf?lang=fr-CA&q=a%26b"[..]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct UriBuilder {
+  data: Vec<u8>,
+  // Offset of the '?' that starts the query, if any.
+  query: Option<usize>,
+  // Whether the path is a "/" that was only added so that the query
+  // would have a path to go with it.
+  implicit_root: bool,
+}
+
+impl UriBuilder {
+  /// Create an empty `UriBuilder`, which refers to the root path `/`.
+  pub const fn new() -> Self {
+    Self {
+      data: Vec::new(),
+      query: None,
+      implicit_root: false,
+    }
+  }
+
+  /// Create a builder for an existing, already encoded, request target.
+  pub(crate) fn from_target(data: Vec<u8>) -> Self {
+    let path_end = memchr::memchr(b'#', &data).unwrap_or(data.len());
+
+    Self {
+      query: memchr::memchr(b'?', &data[..path_end]),
+      data,
+      implicit_root: false,
+    }
+  }
+
+  /// Add a path segment to the end of the path.
+  ///
+  /// All bytes that are not valid within a path segment, including `/`,
+  /// are percent-encoded. Segments can be added after query parameters
+  /// and will still be placed before the query.
+  pub fn segment<S: AsRef<[u8]>>(&mut self, segment: S) -> &mut Self {
+    let mut encoded = Vec::with_capacity(segment.as_ref().len() + 1);
+    encoded.push(b'/');
+    percent_encode(&mut encoded, segment.as_ref(), is_pchar);
+
+    match self.query {
+      Some(offset) => {
+        let start = if self.implicit_root { 0 } else { offset };
+        self.data.splice(start..offset, encoded.iter().copied());
+        self.query = Some(start + encoded.len());
+        self.implicit_root = false;
+      }
+      None => self.data.extend_from_slice(&encoded),
+    }
+
+    self
+  }
+
+  /// Add a `key=value` parameter to the end of the query.
+  ///
+  /// All bytes that are not valid within a query, along with `&`, `=`,
+  /// and `+`, are percent-encoded.
+  pub fn query<K, V>(&mut self, key: K, value: V) -> &mut Self
+  where
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+  {
+    match self.query {
+      Some(_) => self.data.push(b'&'),
+      None => {
+        if self.data.is_empty() {
+          self.data.push(b'/');
+          self.implicit_root = true;
+        }
+
+        self.query = Some(self.data.len());
+        self.data.push(b'?');
+      }
+    }
+
+    percent_encode(&mut self.data, key.as_ref(), is_query_param_char);
+    self.data.push(b'=');
+    percent_encode(&mut self.data, value.as_ref(), is_query_param_char);
+
+    self
+  }
+
+  /// Get the request target that has been built so far.
+  pub fn as_uri(&self) -> Uri<'_> {
+    if self.data.is_empty() {
+      return Uri::new_const(b"/");
+    }
+
+    // Safety: Everything written to data has been percent-encoded so it
+    //         cannot contain any invalid characters.
+    unsafe { Uri::new_unchecked(&self.data) }
+  }
+}
+
+/// Append `bytes` to `out`, percent-encoding all bytes for which
+/// `allowed` returns false.
+pub(crate) fn percent_encode(
+  out: &mut Vec<u8>,
+  bytes: &[u8],
+  allowed: fn(u8) -> bool,
+) {
+  const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+  for &byte in bytes {
+    if allowed(byte) {
+      out.push(byte);
+    } else {
+      out.extend_from_slice(&[
+        b'%',
+        HEX[(byte >> 4) as usize],
+        HEX[(byte & 0xF) as usize],
+      ]);
+    }
+  }
+}
+
+/// `pchar = unreserved / pct-encoded / sub-delims / ":" / "@"`
+fn is_pchar(byte: u8) -> bool {
+  is_unreserved_or_sub_delim(byte) || byte == b':' || byte == b'@'
+}
+
+/// The characters allowed by `query` except for the ones commonly used to
+/// delimit `key=value` pairs.
+fn is_query_param_char(byte: u8) -> bool {
+  match byte {
+    b'&' | b'=' | b'+' => false,
+    b'/' | b'?' => true,
+    _ => is_pchar(byte),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty_is_root() {
+    assert_eq!(UriBuilder::new().as_uri().as_bytes(), b"/");
+  }
+
+  #[test]
+  fn encodes_invalid_bytes() {
+    let mut builder = UriBuilder::new();
+    builder.segment("a b\r\n/é").query("k y", "1+1=2");
+
+    assert_eq!(
+      builder.as_uri().as_bytes(),
+      &b"/a%20b%0D%0A%2F%C3%A9?k%20y=1%2B1%3D2"[..]
+    );
+  }
+
+  #[test]
+  fn segment_after_query() {
+    let mut builder = UriBuilder::new();
+    builder
+      .query("a", "1")
+      .segment("x")
+      .segment("y")
+      .query("b", "2");
+
+    assert_eq!(builder.as_uri().as_bytes(), &b"/x/y?a=1&b=2"[..]);
+  }
+}
@@ -1,6 +1,6 @@
 use crate::{BufMut, InsufficientSpaceError};
 use bytes::Buf;
-use core::mem::size_of_val;
+use core::mem::{size_of_val, MaybeUninit};
 
 // Hack used to refer to the never type in stable rust.
 //
@@ -139,6 +139,102 @@ pub(crate) const fn ilog10(mut x: u128) -> usize {
   result
 }
 
+/// Add together the size hints of the parts of a value.
+pub(crate) fn sum_hints<I>(hints: I) -> (usize, Option<usize>)
+where
+  I: IntoIterator<Item = (usize, Option<usize>)>,
+{
+  hints
+    .into_iter()
+    .fold((0, Some(0)), |(lower, upper), (lo, up)| {
+      (
+        lower.saturating_add(lo),
+        upper.and_then(|upper| upper.checked_add(up?)),
+      )
+    })
+}
+
+/// A `BufMut` that discards everything written to it and only records
+/// whether any line breaks were written.
+///
+/// Every writable is required to follow a CRLF with whitespace so a
+/// CRLF within a header value means that it was folded.
+pub(crate) struct LineBreakDetector {
+  prev_cr: bool,
+  line_break: bool,
+  crlf: bool,
+  scratch: [MaybeUninit<u8>; 64],
+}
+
+impl LineBreakDetector {
+  pub(crate) fn new() -> Self {
+    Self {
+      prev_cr: false,
+      line_break: false,
+      crlf: false,
+      scratch: [MaybeUninit::uninit(); 64],
+    }
+  }
+
+  /// Whether a CR or LF was written.
+  pub(crate) fn found_line_break(&self) -> bool {
+    self.line_break
+  }
+
+  /// Whether a CR directly followed by a LF was written.
+  pub(crate) fn found_crlf(&self) -> bool {
+    self.crlf
+  }
+}
+
+impl BufMut for LineBreakDetector {
+  fn remaining_mut(&self) -> usize {
+    usize::MAX
+  }
+
+  unsafe fn advance_mut(&mut self, cnt: usize) {
+    for i in 0..cnt {
+      let byte = self.scratch[i].assume_init();
+      self.put_u8(byte);
+    }
+  }
+
+  fn bytes_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+    &mut self.scratch
+  }
+
+  fn put_slice(&mut self, src: &[u8]) {
+    for &byte in src {
+      self.put_u8(byte);
+    }
+  }
+
+  fn put_u8(&mut self, byte: u8) {
+    self.line_break |= byte == b'\r' || byte == b'\n';
+    self.crlf |= self.prev_cr && byte == b'\n';
+    self.prev_cr = byte == b'\r';
+  }
+}
+
+/// Write out `value` and check that its size hint bounds the number of
+/// bytes that were actually written.
+#[cfg(test)]
+pub(crate) fn encode<V: crate::HttpWriteable>(value: V) -> Vec<u8> {
+  let mut buffer = Vec::new();
+  value.write_to(&mut buffer).unwrap();
+
+  let (lower, upper) = value.size_hint();
+  assert!(lower <= buffer.len() && buffer.len() <= upper.unwrap());
+
+  buffer
+}
+
+/// Same as [`encode`] but the output is converted to a `String`.
+#[cfg(test)]
+pub(crate) fn encode_str<V: crate::HttpWriteable>(value: V) -> String {
+  String::from_utf8(encode(value)).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -183,6 +279,7 @@ mod tests {
   }
 
   #[test]
+  #[allow(clippy::useless_vec)]
   fn u8_buffer_too_short() {
     use crate::HttpWriteable;
 
@@ -191,4 +288,21 @@ mod tests {
 
     assert!(val.write_to(&mut &mut buffer[..]).is_err());
   }
+
+  #[test]
+  fn line_break_detector_generic_puts() {
+    let mut detector = LineBreakDetector::new();
+    detector.put_u16(0x0D0A);
+    assert!(detector.found_crlf());
+    assert!(detector.found_line_break());
+
+    let mut detector = LineBreakDetector::new();
+    detector.put_u16(0x0A0D);
+    assert!(!detector.found_crlf());
+    assert!(detector.found_line_break());
+
+    let mut detector = LineBreakDetector::new();
+    detector.put_u32(0x6162_6364);
+    assert!(!detector.found_line_break());
+  }
 }
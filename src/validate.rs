@@ -0,0 +1,609 @@
+use crate::chunked::TRANSFER_ENCODING;
+use crate::connection::CONNECTION_SPECIFIC;
+use crate::util::LineBreakDetector;
+use crate::{
+  BufMut, ChunkedWriter, FallibleBufMut, Header, HttpBuilder, HttpEncodeError,
+  HttpWriteable, InvalidMessageError, Method, ReasonPhrase, Status,
+  TransferEncoding, Uri, ValuePolicy, Version,
+};
+
+/// An [`HttpBuilder`](crate::HttpBuilder) that also checks the message
+/// being written for semantic errors.
+///
+/// `HttpBuilder` only guarantees that the message it produces is
+/// syntactically valid. `ValidatingBuilder` additionally rejects messages
+/// that receivers could interpret in more than one way. This is meant as
+/// a last line of defense for proxies and other intermediaries that
+/// forward messages built from untrusted input.
+///
+/// Currently, the following are rejected:
+/// - messages with both a `Content-Length` and a `Transfer-Encoding`
+///   header,
+/// - messages with multiple `Content-Length` headers that have different
+///   values,
+/// - `Content-Length` values that are not a plain decimal number,
+/// - the asterisk-form request target (`*`) with any method other than
+///   `OPTIONS`, and
+/// - constructs that only exist in HTTP/1.x when the message uses
+///   HTTP/2 or later: `Transfer-Encoding`, connection-specific headers
+///   such as `Connection` or `Upgrade`, line folding within header
+///   values, and reason phrases.
+///
+/// Any header that would make the message invalid is rejected before it
+/// is written out to the buffer.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), HttpEncodeError> {
+/// let mut builder = ValidatingBuilder::response(
+///     vec![],
+///     Version::HTTP_1_1,
+///     Status::with_reason(200, "OK")
+/// )?;
+/// builder.header(Header::new("Content-Length", 5))?;
+///
+/// // A second, different, Content-Length would allow request smuggling.
+/// assert!(builder.header(Header::new("Content-Length", 6)).is_err());
+/// assert!(builder.header(Header::new("Transfer-Encoding", "chunked")).is_err());
+///
+/// let output = builder.finish()?;
+/// assert_eq!(output, b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n");
+/// # Ok(())
+/// # }
+/// ```
+pub struct ValidatingBuilder<B: BufMut> {
+  builder: HttpBuilder<B>,
+  validator: Validator,
+  policy: ValuePolicy,
+}
+
+impl<B: BufMut> ValidatingBuilder<B> {
+  /// Start a validated HTTP-style request with the given method, uri, and
+  /// protocol version.
+  ///
+  /// # Errors
+  /// Returns an error if the asterisk-form request target (`*`) is used
+  /// with any method other than `OPTIONS` or if there is not enough space
+  /// in the buffer.
+  pub fn request(
+    buffer: B,
+    method: Method,
+    request_target: Uri,
+    version: Version,
+  ) -> Result<Self, HttpEncodeError> {
+    if request_target.is_asterisk() && method != Method::OPTIONS {
+      return Err(
+        InvalidMessageError(
+          "Asterisk-form request target is only valid for OPTIONS",
+        )
+        .into(),
+      );
+    }
+
+    let builder =
+      HttpBuilder::request(buffer, method, request_target, version)?;
+
+    Ok(Self::new(builder))
+  }
+
+  /// Start a validated HTTP-style response with the given version and
+  /// status.
+  ///
+  /// # Errors
+  /// Returns an error if `status` has a reason phrase but `version` is
+  /// HTTP/2 or later or if there is not enough space in the buffer.
+  pub fn response(
+    buffer: B,
+    version: Version,
+    status: Status,
+  ) -> Result<Self, HttpEncodeError> {
+    Self::response_opts(buffer, version, status, ReasonPhrase::Include)
+  }
+
+  /// Start a validated HTTP-style response with the given version and
+  /// status, choosing whether to include the reason phrase.
+  ///
+  /// See [`HttpBuilder::response_opts`](crate::HttpBuilder::response_opts).
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), HttpEncodeError> {
+  /// // HTTP/2 has no reason phrases.
+  /// let status = Status::with_reason(200, "OK");
+  /// let builder = ValidatingBuilder::response(vec![], Version::HTTP_2, status);
+  /// assert!(builder.is_err());
+  ///
+  /// let builder = ValidatingBuilder::response_opts(
+  ///     vec![],
+  ///     Version::HTTP_2,
+  ///     Status::OK,
+  ///     ReasonPhrase::Omit
+  /// )?;
+  /// assert_eq!(builder.finish()?, b"HTTP/2.0 200 \r\n\r\n");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn response_opts(
+    buffer: B,
+    version: Version,
+    status: Status,
+    reason: ReasonPhrase,
+  ) -> Result<Self, HttpEncodeError> {
+    let has_reason =
+      reason == ReasonPhrase::Include && !status.reason().is_empty();
+
+    if version.major() >= 2 && has_reason {
+      return Err(
+        InvalidMessageError(
+          "Reason phrases are not allowed in HTTP/2 and later",
+        )
+        .into(),
+      );
+    }
+
+    let builder = HttpBuilder::response_opts(buffer, version, status, reason)?;
+
+    Ok(Self::new(builder))
+  }
+
+  /// Start validating the headers written to an existing `HttpBuilder`.
+  ///
+  /// Headers written to `builder` before this point are not known to the
+  /// validator and so will not be checked against.
+  pub fn new(builder: HttpBuilder<B>) -> Self {
+    let multiplexed = matches!(builder.version, Some((major, _)) if major >= 2);
+
+    Self {
+      builder,
+      validator: Validator {
+        multiplexed,
+        ..Validator::default()
+      },
+      policy: ValuePolicy::default(),
+    }
+  }
+
+  /// Set the [`ValuePolicy`](crate::ValuePolicy) that is applied to the
+  /// values of all further headers written to this builder.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), HttpEncodeError> {
+  /// let mut builder = ValidatingBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.value_policy(ValuePolicy::Reject);
+  ///
+  /// assert!(builder.header(Header::new("X-Split", "a\nb")).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn value_policy(&mut self, policy: ValuePolicy) -> &mut Self {
+    self.policy = policy;
+    self
+  }
+
+  /// Validate and then write out a HTTP header field.
+  ///
+  /// # Errors
+  /// Returns an error if the header would make the message invalid, if
+  /// its value is refused by the current
+  /// [`ValuePolicy`](crate::ValuePolicy), or if there is not enough space
+  /// in the buffer.
+  pub fn header<'data, V, H>(
+    &mut self,
+    header: H,
+  ) -> Result<&mut Self, HttpEncodeError>
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+  {
+    let Header { field, value } = header.into();
+    let header = Header::checked_new(field, self.policy.apply(value)?);
+
+    // Only record the header once it has actually been written out.
+    let framing = self.validator.check_header(&header)?;
+    self.builder.header(header)?;
+    self.validator.record(framing);
+
+    Ok(self)
+  }
+
+  /// Validate and write out every HTTP header field produced by
+  /// `headers`, in order.
+  pub fn headers<'data, V, H, I>(
+    &mut self,
+    headers: I,
+  ) -> Result<&mut Self, HttpEncodeError>
+  where
+    V: HttpWriteable,
+    H: Into<Header<'data, V>>,
+    I: IntoIterator<Item = H>,
+  {
+    for header in headers {
+      self.header(header)?;
+    }
+
+    Ok(self)
+  }
+
+  /// Finish off the HTTP header and return the `BufMut` instance that
+  /// was being written to.
+  pub fn finish(self) -> Result<B, HttpEncodeError> {
+    Ok(self.builder.finish()?)
+  }
+
+  /// Write out a `Content-Length` header matching `body`, finish off
+  /// the HTTP header, and then copy `body` into the buffer.
+  ///
+  /// If a `Content-Length` header with the same length has already been
+  /// written then it is not repeated.
+  pub fn body(self, body: &[u8]) -> Result<B, HttpEncodeError> {
+    let length = body.len() as u64;
+
+    if self.validator.content_length == Some(length) {
+      let mut buffer = self.builder.finish()?;
+      buffer.try_put_slice(body)?;
+      return Ok(buffer);
+    }
+
+    self.validator.check_content_length(length)?;
+    Ok(self.builder.body(body)?)
+  }
+
+  /// Write out a `Transfer-Encoding` header ending in `chunked`, finish
+  /// off the HTTP header, and start writing out a chunked body.
+  ///
+  /// See [`HttpBuilder::chunked`](crate::HttpBuilder::chunked).
+  ///
+  /// # Errors
+  /// Returns an error if a `Content-Length` or `Transfer-Encoding` header
+  /// has already been written, if the message uses a version other than
+  /// HTTP/1.1, or if there is not enough space in the buffer.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), HttpEncodeError> {
+  /// let mut builder = ValidatingBuilder::response(
+  ///     vec![],
+  ///     Version::HTTP_1_1,
+  ///     Status::with_reason(200, "OK")
+  /// )?;
+  /// builder.header(Header::new("Content-Length", 5))?;
+  ///
+  /// // The body can't be framed both ways.
+  /// assert!(builder.chunked(TransferEncoding::CHUNKED).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn chunked(
+    mut self,
+    encoding: TransferEncoding,
+  ) -> Result<ChunkedWriter<B>, HttpEncodeError> {
+    if self.validator.transfer_encoding {
+      return Err(
+        InvalidMessageError("Message already contained Transfer-Encoding")
+          .into(),
+      );
+    }
+
+    if matches!(self.builder.version, Some(version) if version < (1, 1)) {
+      return Err(
+        InvalidMessageError("Chunked bodies are only supported by HTTP/1.1")
+          .into(),
+      );
+    }
+
+    let encoding = encoding.chunked();
+    self.header(Header::checked_new(TRANSFER_ENCODING, encoding))?;
+
+    Ok(ChunkedWriter::new(self.builder.finish()?, true))
+  }
+
+  /// See [`HttpBuilder::bytes_written`](crate::HttpBuilder::bytes_written).
+  pub fn bytes_written(&self) -> usize {
+    self.builder.bytes_written()
+  }
+
+  /// See [`HttpBuilder::header_count`](crate::HttpBuilder::header_count).
+  pub fn header_count(&self) -> usize {
+    self.builder.header_count()
+  }
+
+  /// Return the existing buffer without adding the extra blank line
+  /// required to terminate the HTTP header section.
+  pub fn into_inner(self) -> B {
+    self.builder.into_inner()
+  }
+}
+
+impl<B: BufMut> HttpBuilder<B> {
+  /// Validate all further headers written to this builder.
+  ///
+  /// See [`ValidatingBuilder`](crate::ValidatingBuilder) for details on
+  /// what is validated.
+  pub fn validated(self) -> ValidatingBuilder<B> {
+    ValidatingBuilder::new(self)
+  }
+}
+
+/// Semantic state for the message being validated.
+#[derive(Clone, Debug, Default)]
+struct Validator {
+  content_length: Option<u64>,
+  transfer_encoding: bool,
+  // Whether the message uses HTTP/2 or later.
+  multiplexed: bool,
+}
+
+/// How a header that passed validation affects the framing of the message.
+#[derive(Copy, Clone, Debug)]
+enum Framing {
+  Unchanged,
+  ContentLength(u64),
+  TransferEncoding,
+}
+
+impl Validator {
+  fn check_header<V: HttpWriteable>(
+    &self,
+    header: &Header<'_, V>,
+  ) -> Result<Framing, InvalidMessageError> {
+    let field = header.field.as_str();
+
+    if self.multiplexed {
+      self.check_multiplexed_header(field, &header.value)?;
+    }
+
+    if field.eq_ignore_ascii_case("Content-Length") {
+      let length = parse_content_length(&header.value)?;
+      self.check_content_length(length)?;
+      Ok(Framing::ContentLength(length))
+    } else if field.eq_ignore_ascii_case("Transfer-Encoding") {
+      self.check_transfer_encoding()?;
+      Ok(Framing::TransferEncoding)
+    } else {
+      Ok(Framing::Unchanged)
+    }
+  }
+
+  /// Record a header that was checked by `check_header` once it has been
+  /// written out.
+  fn record(&mut self, framing: Framing) {
+    match framing {
+      Framing::Unchanged => (),
+      Framing::ContentLength(length) => self.content_length = Some(length),
+      Framing::TransferEncoding => self.transfer_encoding = true,
+    }
+  }
+
+  fn check_multiplexed_header<V: HttpWriteable>(
+    &self,
+    field: &str,
+    value: &V,
+  ) -> Result<(), InvalidMessageError> {
+    if CONNECTION_SPECIFIC
+      .iter()
+      .any(|name| field.eq_ignore_ascii_case(name))
+    {
+      return Err(InvalidMessageError(
+        "Connection-specific headers are not allowed in HTTP/2 and later",
+      ));
+    }
+
+    let mut detector = LineBreakDetector::new();
+    // The detector never runs out of space.
+    let _ = value.write_to(&mut detector);
+
+    if detector.found_crlf() {
+      return Err(InvalidMessageError(
+        "Line folding is not allowed in HTTP/2 and later",
+      ));
+    }
+
+    Ok(())
+  }
+
+  fn check_content_length(
+    &self,
+    length: u64,
+  ) -> Result<(), InvalidMessageError> {
+    if self.transfer_encoding {
+      return Err(InvalidMessageError(
+        "Message contained both Content-Length and Transfer-Encoding",
+      ));
+    }
+
+    match self.content_length {
+      Some(prev) if prev != length => Err(InvalidMessageError(
+        "Message contained multiple differing Content-Length values",
+      )),
+      _ => Ok(()),
+    }
+  }
+
+  fn check_transfer_encoding(&self) -> Result<(), InvalidMessageError> {
+    if self.content_length.is_some() {
+      return Err(InvalidMessageError(
+        "Message contained both Content-Length and Transfer-Encoding",
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+fn parse_content_length<V: HttpWriteable>(
+  value: &V,
+) -> Result<u64, InvalidMessageError> {
+  const INVALID: InvalidMessageError =
+    InvalidMessageError("Content-Length value was not a valid length");
+
+  // Any valid length fits within the 20 digits of u64::MAX so anything
+  // that doesn't fit in the scratch buffer is invalid anyway.
+  let mut scratch = [0u8; 20];
+  let mut remaining = &mut scratch[..];
+  value.write_to(&mut remaining).map_err(|_| INVALID)?;
+  let len = 20 - remaining.len();
+
+  if len == 0 {
+    return Err(INVALID);
+  }
+
+  scratch[..len]
+    .iter()
+    .try_fold(0u64, |acc, &byte| match byte {
+      b'0'..=b'9' => acc
+        .checked_mul(10)
+        .and_then(|acc| acc.checked_add((byte - b'0') as u64))
+        .ok_or(INVALID),
+      _ => Err(INVALID),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::TransferCoding;
+
+  #[test]
+  fn content_length_parsing() {
+    assert_eq!(parse_content_length(&0u8).unwrap(), 0);
+    assert_eq!(parse_content_length(&"0012").unwrap(), 12);
+    assert_eq!(parse_content_length(&u64::MAX).unwrap(), u64::MAX);
+
+    assert!(parse_content_length(&"").is_err());
+    assert!(parse_content_length(&-1i32).is_err());
+    assert!(parse_content_length(&"5, 5").is_err());
+    assert!(parse_content_length(&"18446744073709551616").is_err());
+    assert!(parse_content_length(&"100000000000000000000").is_err());
+  }
+
+  #[test]
+  fn transfer_encoding_then_content_length() {
+    let mut validator = Validator::default();
+
+    let framing = validator
+      .check_header(&Header::new("transfer-encoding", "chunked"))
+      .unwrap();
+    validator.record(framing);
+    assert!(validator
+      .check_header(&Header::new("content-length", 0))
+      .is_err());
+  }
+
+  #[test]
+  fn repeated_identical_content_length() {
+    let mut validator = Validator::default();
+
+    let framing = validator
+      .check_header(&Header::new("Content-Length", 10))
+      .unwrap();
+    validator.record(framing);
+    validator
+      .check_header(&Header::new("Content-Length", "10"))
+      .unwrap();
+  }
+
+  #[test]
+  fn failed_write_is_not_recorded() {
+    let mut buffer = [0u8; 20];
+    let mut builder = HttpBuilder::from_buffer(&mut buffer[..]).validated();
+
+    assert!(builder
+      .header(Header::new("Transfer-Encoding", "chunked"))
+      .is_err());
+    builder.header(Header::new("Content-Length", 0)).unwrap();
+  }
+
+  #[test]
+  fn multiplexed_rejects_http1_constructs() {
+    let validator = Validator {
+      multiplexed: true,
+      ..Validator::default()
+    };
+
+    let rejected = [
+      Header::new("connection", "close"),
+      Header::new("Transfer-Encoding", "chunked"),
+      Header::new("Upgrade", "websocket"),
+      Header::new("X-Folded", "a\r\n b"),
+    ];
+
+    for header in &rejected {
+      assert!(validator.clone().check_header(header).is_err());
+    }
+
+    assert!(validator
+      .clone()
+      .check_header(&Header::new("Content-Type", "text/plain"))
+      .is_ok());
+  }
+
+  #[test]
+  fn chunked_framing() {
+    let status = Status::with_reason(200, "OK");
+    let builder =
+      ValidatingBuilder::response(vec![], Version::HTTP_1_1, status).unwrap();
+    let mut body = builder
+      .chunked(TransferEncoding::new(&[TransferCoding::GZIP]))
+      .unwrap();
+    body.chunk(b"abc").unwrap();
+
+    assert_eq!(
+      body.finish().unwrap(),
+      b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip, chunked\r\n\r\n\
+        3\r\nabc\r\n0\r\n\r\n"
+    );
+
+    let mut builder =
+      ValidatingBuilder::response(vec![], Version::HTTP_1_1, Status::OK)
+        .unwrap();
+    builder
+      .header(Header::new("Transfer-Encoding", "gzip"))
+      .unwrap();
+    assert!(builder.chunked(TransferEncoding::CHUNKED).is_err());
+
+    let builder =
+      ValidatingBuilder::response(vec![], Version::HTTP_1_0, Status::OK)
+        .unwrap();
+    assert!(builder.chunked(TransferEncoding::CHUNKED).is_err());
+
+    let builder = ValidatingBuilder::response_opts(
+      vec![],
+      Version::HTTP_2,
+      Status::OK,
+      ReasonPhrase::Omit,
+    )
+    .unwrap();
+    assert!(builder.chunked(TransferEncoding::CHUNKED).is_err());
+  }
+
+  #[test]
+  fn value_policy_applies_before_validation() {
+    let mut builder = ValidatingBuilder::response_opts(
+      vec![],
+      Version::HTTP_2,
+      Status::OK,
+      ReasonPhrase::Omit,
+    )
+    .unwrap();
+
+    // Folding is rejected in HTTP/2 but a replaced value is fine.
+    assert!(builder.header(Header::new("X-Note", "a\r\nb")).is_err());
+    builder.value_policy(ValuePolicy::ReplaceWithSpace);
+    builder.header(Header::new("X-Note", "a\r\nb")).unwrap();
+
+    builder.value_policy(ValuePolicy::Reject);
+    assert!(builder.header(Header::new("X-Note", "a\r\nb")).is_err());
+
+    let output = builder.into_inner();
+    assert!(output.ends_with(b"X-Note: a  b\r\n"));
+  }
+}
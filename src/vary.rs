@@ -0,0 +1,138 @@
+use crate::{
+  BufMut, CheckedField, FallibleBufMut, HttpWriteable, InsufficientSpaceError,
+};
+
+/// A `Vary` header value listing the request fields that were used to
+/// select the response.
+///
+/// Field names are compared case-insensitively and only the first
+/// occurrence of each name is written out, so the list can be built up
+/// from every negotiation header that was inspected without worrying
+/// about duplicates.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// const ACCEPT_ENCODING: CheckedField = CheckedField::new("Accept-Encoding");
+///
+/// let inspected = [
+///   ACCEPT_ENCODING,
+///   CheckedField::new("Accept-Language"),
+///   CheckedField::new("accept-encoding"),
+/// ];
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Vary", Vary::new(&inspected)).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"Vary: Accept-Encoding, Accept-Language\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Vary<'data> {
+  fields: &'data [CheckedField<'data>],
+  any: bool,
+}
+
+impl<'data> Vary<'data> {
+  /// `Vary: *`, indicating that the response depends on more than just
+  /// the request fields.
+  pub const ANY: Self = Self {
+    fields: &[],
+    any: true,
+  };
+
+  /// Create a `Vary` value from a list of field names.
+  pub const fn new(fields: &'data [CheckedField<'data>]) -> Self {
+    Self { fields, any: false }
+  }
+
+  /// Whether this is `Vary: *`.
+  pub const fn is_any(&self) -> bool {
+    self.any
+  }
+
+  /// The field names that are written out, in order, skipping any that
+  /// already appeared earlier in the list.
+  pub fn fields(&self) -> impl Iterator<Item = CheckedField<'data>> + 'data {
+    let fields = self.fields;
+
+    fields.iter().enumerate().filter_map(move |(idx, field)| {
+      let seen = fields[..idx]
+        .iter()
+        .any(|prev| prev.eq_ignore_case(field.as_str()));
+
+      if seen {
+        None
+      } else {
+        Some(*field)
+      }
+    })
+  }
+}
+
+impl HttpWriteable for Vary<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    if self.any {
+      return buffer.try_put_u8(b'*');
+    }
+
+    for (idx, field) in self.fields().enumerate() {
+      if idx != 0 {
+        buffer.try_put_slice(b", ")?;
+      }
+
+      buffer.try_put_slice(field.as_str().as_bytes())?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = if self.any {
+      1
+    } else {
+      self
+        .fields()
+        .map(|field| field.as_str().len() + 2)
+        .sum::<usize>()
+        .saturating_sub(2)
+    };
+
+    (len, Some(len))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode(vary: Vary) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    vary.write_to(&mut buffer).unwrap();
+
+    assert_eq!(vary.size_hint(), (buffer.len(), Some(buffer.len())));
+
+    buffer
+  }
+
+  #[test]
+  fn dedup() {
+    let fields = [
+      CheckedField::new("Origin"),
+      CheckedField::new("ORIGIN"),
+      CheckedField::new("Accept"),
+      CheckedField::new("origin"),
+      CheckedField::new("accept"),
+    ];
+
+    assert_eq!(encode(Vary::new(&fields)), b"Origin, Accept");
+    assert_eq!(encode(Vary::new(&fields[..1])), b"Origin");
+    assert_eq!(encode(Vary::new(&[])), b"");
+    assert_eq!(encode(Vary::ANY), b"*");
+  }
+}
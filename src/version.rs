@@ -1,4 +1,10 @@
-use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::{
+  is_token, is_token_fast, BufMut, FallibleBufMut, HttpWriteable,
+  InsufficientSpaceError, InvalidVersionError,
+};
 
 /// HTTP Version.
 ///
@@ -7,11 +13,13 @@ use crate::{BufMut, FallibleBufMut, HttpWriteable, InsufficientSpaceError};
 /// code should be interacting with is the `HTTP_1_0` and `HTTP_1_1`
 /// constants.
 ///
-/// Users wanting a custom version identifier should build the
-/// first line of the request themselves and then constuct the
-/// [`HttpBuilder`](crate::HttpBuilder) themselves using
-/// [`HttpBuilder::from_buffer`](crate::HttpBuilder::from_buffer).
-#[derive(Copy, Clone, Debug)]
+/// Other HTTP-like protocols, such as RTSP or SIP, can be used by
+/// creating a version with [`Version::custom`](crate::Version::custom).
+///
+/// Versions of the same protocol are ordered by their major and then minor
+/// version numbers. Versions of different protocols are ordered by
+/// protocol name first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Version<'data> {
   proto: &'data str,
   major: u8,
@@ -19,17 +27,6 @@ pub struct Version<'data> {
 }
 
 impl<'data> Version<'data> {
-  pub(crate) fn write_to<B: BufMut>(
-    &self,
-    buffer: &mut B,
-  ) -> Result<(), InsufficientSpaceError> {
-    buffer.try_put_slice(self.proto.as_bytes())?;
-    buffer.try_put_u8(b'/')?;
-    self.major.write_to(buffer)?;
-    buffer.try_put_u8(b'.')?;
-    self.minor.write_to(buffer)
-  }
-
   /// Create a HTTP version with the given major and minor version
   /// numbers. When serialized, produces `HTTP/<major>.<minor>`.
   pub const fn http(major: u8, minor: u8) -> Self {
@@ -40,15 +37,88 @@ impl<'data> Version<'data> {
     }
   }
 
+  /// Create a version for an HTTP-like protocol with the given name and
+  /// version numbers. When serialized, produces
+  /// `<proto>/<major>.<minor>`.
+  ///
+  /// # Errors
+  /// Returns an error if `proto` is not a valid token.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let builder = HttpBuilder::request(
+  ///     vec![],
+  ///     Method::new("DESCRIBE"),
+  ///     Uri::new(b"rtsp://example.com/media.mp4"),
+  ///     Version::try_custom("RTSP", 1, 0)?
+  /// )?;
+  /// let output = builder.finish()?;
+  ///
+  /// assert_eq!(
+  ///   std::str::from_utf8(&output)?,
+  ///   "DESCRIBE rtsp://example.com/media.mp4 RTSP/1.0\r\n\r\n"
+  /// );
+  ///
+  /// assert!(Version::try_custom("HTTP/1.1 ", 1, 1).is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub const fn try_custom(
+    proto: &'data str,
+    major: u8,
+    minor: u8,
+  ) -> Result<Self, InvalidVersionError> {
+    if !is_token(proto) {
+      return Err(InvalidVersionError(()));
+    }
+
+    Ok(Self {
+      proto,
+      major,
+      minor,
+    })
+  }
+
+  /// Create a version for an HTTP-like protocol with the given name and
+  /// version numbers.
+  ///
+  /// # Panics
+  /// Panics if `proto` is not a valid token.
+  pub const fn custom(proto: &'data str, major: u8, minor: u8) -> Self {
+    match Self::try_custom(proto, major, minor) {
+      Ok(version) => version,
+      Err(_) => const_panic!("Invalid protocol name"),
+    }
+  }
+
   /// `HTTP/1.0` version identifier.
   pub const HTTP_1_0: Self = Self::http(1, 0);
   /// `HTTP/1.1` version identifier.
   pub const HTTP_1_1: Self = Self::http(1, 1);
+  /// `HTTP/2.0` version identifier.
+  ///
+  /// HTTP/2 does not use a textual start line so this is mainly useful
+  /// for the connection preface and for `Upgrade` headers.
+  /// [`ValidatingBuilder`](crate::ValidatingBuilder) rejects constructs
+  /// that are only valid in HTTP/1.x when this version is used.
+  pub const HTTP_2: Self = Self::http(2, 0);
+  /// `HTTP/3.0` version identifier.
+  ///
+  /// See [`HTTP_2`](Self::HTTP_2).
+  pub const HTTP_3: Self = Self::http(3, 0);
 
-  // No point exposing this for now as it's always "HTTP"
-  // pub const fn proto(&self) -> &'data str {
-  //   self.proto
-  // }
+  /// Protocol name component of this `Version`.
+  pub const fn proto(&self) -> &'data str {
+    self.proto
+  }
+
+  /// Whether this is a version of HTTP itself, as opposed to some other
+  /// HTTP-like protocol.
+  pub(crate) fn is_http(&self) -> bool {
+    self.proto == "HTTP"
+  }
 
   /// Major version component of this `Version`.
   pub const fn major(&self) -> u8 {
@@ -60,6 +130,99 @@ impl<'data> Version<'data> {
   }
 }
 
+/// Writes out the version identifier, e.g. for use in an `Upgrade` header.
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut builder = HttpBuilder::request(
+///     vec![],
+///     Method::GET,
+///     Uri::new(b"/"),
+///     Version::HTTP_1_1
+/// )?;
+/// builder.header(Header::new("Upgrade", Version::HTTP_2))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "GET / HTTP/1.1\r\n\
+///   Upgrade: HTTP/2.0\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl HttpWriteable for Version<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    buffer.try_put_slice(self.proto.as_bytes())?;
+    buffer.try_put_u8(b'/')?;
+    self.major.write_to(buffer)?;
+    buffer.try_put_u8(b'.')?;
+    self.minor.write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    // The protocol name and the '/' and '.' separators plus between one
+    // and three digits for each version number.
+    let fixed = self.proto.len() + 2;
+    (fixed + 2, Some(fixed + 6))
+  }
+}
+
+impl fmt::Display for Version<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}/{}.{}", self.proto, self.major, self.minor)
+  }
+}
+
+fn parse_version_number(digits: &str) -> Result<u8, InvalidVersionError> {
+  if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(InvalidVersionError(()));
+  }
+
+  digits.parse().map_err(|_| InvalidVersionError(()))
+}
+
+impl<'data> TryFrom<&'data str> for Version<'data> {
+  type Error = InvalidVersionError;
+
+  /// Parse a version identifier of the form `<proto>/<major>.<minor>`.
+  ///
+  /// # Example
+  /// ```
+  /// # use httpencode::*;
+  /// # use std::convert::TryFrom;
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let version = Version::try_from("HTTP/1.1")?;
+  ///
+  /// assert_eq!(version, Version::HTTP_1_1);
+  /// assert!(version > Version::HTTP_1_0);
+  /// assert_eq!(version.to_string(), "HTTP/1.1");
+  /// # Ok(())
+  /// # }
+  /// ```
+  fn try_from(version: &'data str) -> Result<Self, Self::Error> {
+    let slash = version.find('/').ok_or(InvalidVersionError(()))?;
+    let (proto, numbers) = (&version[..slash], &version[slash + 1..]);
+    let dot = numbers.find('.').ok_or(InvalidVersionError(()))?;
+
+    if !is_token_fast(proto.as_bytes()) {
+      return Err(InvalidVersionError(()));
+    }
+
+    Ok(Self {
+      proto,
+      major: parse_version_number(&numbers[..dot])?,
+      minor: parse_version_number(&numbers[dot + 1..])?,
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -70,5 +233,53 @@ mod tests {
 
     assert_eq!(http.major(), 10);
     assert_eq!(http.minor(), 4);
+    assert_eq!(http.proto(), "HTTP");
+  }
+
+  #[test]
+  fn custom_proto() {
+    let sip = Version::custom("SIP", 2, 0);
+    let mut buffer = Vec::new();
+    sip.write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"SIP/2.0");
+    assert!(Version::try_custom("", 1, 0).is_err());
+    assert!(Version::try_custom("A/B", 1, 0).is_err());
+  }
+
+  #[test]
+  fn ordering() {
+    assert!(Version::HTTP_1_0 < Version::HTTP_1_1);
+    assert!(Version::HTTP_1_1 < Version::HTTP_2);
+    assert!(Version::http(1, 10) > Version::http(1, 9));
+    assert_eq!(Version::http(2, 0), Version::HTTP_2);
+    assert_ne!(Version::custom("RTSP", 1, 1), Version::HTTP_1_1);
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(Version::try_from("HTTP/1.0").unwrap(), Version::HTTP_1_0);
+    assert_eq!(
+      Version::try_from("RTSP/2.0").unwrap(),
+      Version::custom("RTSP", 2, 0)
+    );
+    assert_eq!(Version::try_from("HTTP/10.255").unwrap().minor(), 255);
+
+    assert!(Version::try_from("HTTP/1").is_err());
+    assert!(Version::try_from("HTTP/1.").is_err());
+    assert!(Version::try_from("HTTP/+1.1").is_err());
+    assert!(Version::try_from("HTTP/1.256").is_err());
+    assert!(Version::try_from("HTTP/1.1.1").is_err());
+    assert!(Version::try_from("/1.1").is_err());
+    assert!(Version::try_from("HTTP 1.1").is_err());
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn display_round_trip() {
+    for version in &[Version::HTTP_1_0, Version::HTTP_2] {
+      let text = version.to_string();
+      assert_eq!(Version::try_from(text.as_str()).unwrap(), *version);
+    }
   }
 }
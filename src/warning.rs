@@ -0,0 +1,226 @@
+use crate::{
+  is_tchar, BufMut, FallibleBufMut, HttpDate, HttpWriteable,
+  InsufficientSpaceError, InvalidHeaderError, Quoted,
+};
+
+/// Whether `agent` is a valid warn-agent: either a `host[:port]` or a
+/// pseudonym token.
+const fn is_warn_agent(agent: &str) -> bool {
+  let bytes = agent.as_bytes();
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b':' | b'[' | b']' => (),
+      byte if is_tchar(byte) => (),
+      _ => return false,
+    }
+
+    idx += 1;
+  }
+
+  !bytes.is_empty()
+}
+
+/// The 3-digit code of a `Warning` header.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WarnCode(u16);
+
+impl WarnCode {
+  /// 110 Response is Stale
+  pub const RESPONSE_IS_STALE: Self = Self(110);
+  /// 111 Revalidation Failed
+  pub const REVALIDATION_FAILED: Self = Self(111);
+  /// 112 Disconnected Operation
+  pub const DISCONNECTED_OPERATION: Self = Self(112);
+  /// 199 Miscellaneous Warning
+  pub const MISCELLANEOUS_WARNING: Self = Self(199);
+  /// 214 Transformation Applied
+  pub const TRANSFORMATION_APPLIED: Self = Self(214);
+  /// 299 Miscellaneous Persistent Warning
+  pub const MISCELLANEOUS_PERSISTENT_WARNING: Self = Self(299);
+
+  /// Create a custom warning code.
+  ///
+  /// # Errors
+  /// Returns an error if `code` is not a 3-digit number.
+  pub const fn try_new(code: u16) -> Result<Self, InvalidHeaderError> {
+    if code < 100 || code > 999 {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self(code))
+  }
+
+  /// Create a custom warning code.
+  ///
+  /// # Panics
+  /// Panics if `code` is not a 3-digit number.
+  pub const fn new(code: u16) -> Self {
+    match Self::try_new(code) {
+      Ok(code) => code,
+      Err(_) => const_panic!("Warning code was not 3 digits"),
+    }
+  }
+
+  /// The numeric value of this code.
+  pub const fn as_u16(&self) -> u16 {
+    self.0
+  }
+}
+
+/// A `Warning` header value (RFC 7234 section 5.5).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let warning = Warning::new(
+///   WarnCode::RESPONSE_IS_STALE,
+///   "cache.example.com:8080",
+///   "Response is Stale",
+/// )
+/// .date(HttpDate::from_unix_secs(784_111_777));
+///
+/// let mut buffer = Vec::new();
+/// Header::new("Warning", warning).write_to(&mut buffer)?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&buffer)?,
+///   "Warning: 110 cache.example.com:8080 \"Response is Stale\" \
+///   \"Sun, 06 Nov 1994 08:49:37 GMT\"\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Warning<'data> {
+  code: WarnCode,
+  agent: &'data str,
+  text: &'data str,
+  date: Option<HttpDate>,
+}
+
+impl<'data> Warning<'data> {
+  /// Create a warning.
+  ///
+  /// `agent` is the `host[:port]` of the server adding the warning or a
+  /// pseudonym for it. Use `-` when the agent is unknown.
+  ///
+  /// # Errors
+  /// Returns an error if `agent` is not a host, host and port, or token
+  /// or if `text` contains a control character other than HTAB.
+  pub const fn try_new(
+    code: WarnCode,
+    agent: &'data str,
+    text: &'data str,
+  ) -> Result<Self, InvalidHeaderError> {
+    if !is_warn_agent(agent) || Quoted::try_new(text.as_bytes()).is_err() {
+      return Err(InvalidHeaderError(()));
+    }
+
+    Ok(Self {
+      code,
+      agent,
+      text,
+      date: None,
+    })
+  }
+
+  /// Create a warning.
+  ///
+  /// # Panics
+  /// Panics if `agent` is not a host, host and port, or token or if
+  /// `text` contains a control character other than HTAB.
+  pub const fn new(
+    code: WarnCode,
+    agent: &'data str,
+    text: &'data str,
+  ) -> Self {
+    match Self::try_new(code, agent, text) {
+      Ok(warning) => warning,
+      Err(_) => const_panic!("Invalid warning agent or text"),
+    }
+  }
+
+  /// Set the date at which the warning was generated.
+  pub const fn date(self, date: HttpDate) -> Self {
+    Self {
+      date: Some(date),
+      ..self
+    }
+  }
+
+  /// The warning code.
+  pub const fn code(&self) -> WarnCode {
+    self.code
+  }
+}
+
+impl HttpWriteable for Warning<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    self.code.0.write_to(buffer)?;
+    buffer.try_put_u8(b' ')?;
+    buffer.try_put_slice(self.agent.as_bytes())?;
+    buffer.try_put_u8(b' ')?;
+    // The text was checked when this was created.
+    Quoted(self.text.as_bytes()).write_to(buffer)?;
+
+    if let Some(date) = self.date {
+      buffer.try_put_slice(b" \"")?;
+      date.write_to(buffer)?;
+      buffer.try_put_u8(b'"')?;
+    }
+
+    Ok(())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut len = "000  ".len() + self.agent.len();
+    if let Some(date) = self.date {
+      len += " \"\"".len() + date.size_hint().0;
+    }
+
+    let (lower, upper) = Quoted(self.text.as_bytes()).size_hint();
+    (
+      lower.saturating_add(len),
+      upper.and_then(|upper| upper.checked_add(len)),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode_str;
+
+  #[test]
+  fn warning() {
+    assert_eq!(
+      encode_str(Warning::new(
+        WarnCode::TRANSFORMATION_APPLIED,
+        "-",
+        "Image \"re-encoded\""
+      )),
+      r#"214 - "Image \"re-encoded\"""#
+    );
+    assert_eq!(
+      encode_str(Warning::new(WarnCode::new(299), "[::1]:80", "")),
+      r#"299 [::1]:80 """#
+    );
+  }
+
+  #[test]
+  fn invalid() {
+    assert!(WarnCode::try_new(99).is_err());
+    assert!(WarnCode::try_new(1000).is_err());
+
+    let code = WarnCode::MISCELLANEOUS_WARNING;
+    assert!(Warning::try_new(code, "", "text").is_err());
+    assert!(Warning::try_new(code, "a b", "text").is_err());
+    assert!(Warning::try_new(code, "agent", "a\r\nb").is_err());
+  }
+}
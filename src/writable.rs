@@ -1,3 +1,8 @@
+use core::fmt;
+
+use bytes::buf::ext::Limit;
+use bytes::{Bytes, BytesMut};
+
 use crate::{util::ilog10, BufMut, FallibleBufMut, InsufficientSpaceError};
 
 /// Types that can safely be written out as an http value.
@@ -24,6 +29,37 @@ pub trait HttpWriteable {
     &self,
     buffer: &mut B,
   ) -> Result<(), InsufficientSpaceError>;
+
+  /// Returns the bounds on the number of bytes that
+  /// [`write_to`](HttpWriteable::write_to) will write out.
+  ///
+  /// This works the same as
+  /// [`Iterator::size_hint`](core::iter::Iterator::size_hint). The first
+  /// element is a lower bound and the second element is an upper bound,
+  /// if there is one. It is used to check that there's enough room in the
+  /// buffer for a whole header up front instead of checking each part
+  /// individually.
+  ///
+  /// The default implementation returns `(0, None)` which is correct for
+  /// any writable.
+  ///
+  /// # Note for Implementors
+  /// Returning incorrect bounds won't cause memory unsafety, panics or
+  /// malformed output. The bounds are only ever used to fail early, so
+  /// a lower bound that is too high may cause writes to fail with an
+  /// [`InsufficientSpaceError`](crate::InsufficientSpaceError) when
+  /// there was enough room, while an upper bound that is too low is
+  /// ignored.
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, None)
+  }
+}
+
+// Each unquoted CRLF may have a tab inserted after it so values can grow
+// by at most half their length.
+const fn folded_size_hint(len: usize) -> (usize, Option<usize>) {
+  (len, len.checked_add(len / 2))
 }
 
 fn reverse<T>(range: &mut [T]) {
@@ -39,7 +75,7 @@ fn reverse<T>(range: &mut [T]) {
   }
 }
 
-fn find_unquoted_crlf(bytes: &[u8]) -> UnquotedCRLFIterator {
+fn find_unquoted_crlf(bytes: &[u8]) -> UnquotedCRLFIterator<'_> {
   UnquotedCRLFIterator {
     bytes,
     inquotes: false,
@@ -113,6 +149,11 @@ macro_rules! writable_unsigned {
 
         buffer.try_put_slice(&bytes[..i])
       }
+
+      #[inline]
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        (1, Some(ilog10(Self::MAX as u128)))
+      }
     }
   };
 }
@@ -140,6 +181,11 @@ macro_rules! writable_signed {
 
         value.write_to(buffer)
       }
+
+      #[inline]
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        (1, Some(ilog10(Self::MAX as u128) + 1))
+      }
     }
   };
 }
@@ -151,6 +197,114 @@ writable_signed!(i64, u64);
 writable_signed!(i128, u128);
 writable_signed!(isize, usize);
 
+/// A `fmt::Write` adapter that drops trailing zeros (and then the decimal
+/// point) from the fractional part of a formatted number.
+struct TrimFraction<'b, B: BufMut> {
+  buffer: &'b mut B,
+  // The decimal point and the fractional digits seen so far.
+  fraction: [u8; 4],
+  len: usize,
+}
+
+impl<'b, B: BufMut> TrimFraction<'b, B> {
+  fn new(buffer: &'b mut B) -> Self {
+    Self {
+      buffer,
+      fraction: [0; 4],
+      len: 0,
+    }
+  }
+
+  fn finish(self) -> Result<(), InsufficientSpaceError> {
+    let mut fraction = &self.fraction[..self.len];
+    while let [rest @ .., b'0'] = fraction {
+      fraction = rest;
+    }
+
+    match fraction {
+      [b'.'] => Ok(()),
+      _ => self.buffer.try_put_slice(fraction),
+    }
+  }
+}
+
+impl<B: BufMut> fmt::Write for TrimFraction<'_, B> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for &byte in s.as_bytes() {
+      if byte == b'.' || self.len != 0 {
+        // Formatting with a precision of 3 never produces more digits.
+        *self.fraction.get_mut(self.len).ok_or(fmt::Error)? = byte;
+        self.len += 1;
+      } else {
+        self.buffer.try_put_u8(byte).map_err(|_| fmt::Error)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+macro_rules! writable_float {
+  ($ty:ident, $max_len:expr) => {
+    impl HttpWriteable for $ty {
+      /// Write out this number rounded to at most 3 decimal places, without
+      /// an exponent and without trailing zeros, e.g. `0.5` or `12`.
+      ///
+      /// NaN and infinities cannot be represented within a header so they
+      /// are written out as `0`.
+      fn write_to<B: BufMut>(
+        &self,
+        buffer: &mut B,
+      ) -> Result<(), InsufficientSpaceError> {
+        // Avoid writing out `-0` for tiny negative numbers.
+        let tiny = *self > -0.0005 && *self < 0.0005;
+        let value = if tiny || !self.is_finite() {
+          0.0
+        } else {
+          *self
+        };
+
+        let mut trim = TrimFraction::new(buffer);
+        fmt::write(&mut trim, format_args!("{:.3}", value))
+          .map_err(|_| InsufficientSpaceError::default())?;
+        trim.finish()
+      }
+
+      #[inline]
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        (1, Some($max_len))
+      }
+    }
+  };
+}
+
+// The sign, the integer digits of MAX, and 3 decimal places.
+writable_float!(f32, 1 + 39 + 4);
+writable_float!(f64, 1 + 309 + 4);
+
+impl HttpWriteable for bool {
+  /// Write out `true` or `false`. Use
+  /// [`StructuredBool`](crate::StructuredBool) for structured field
+  /// booleans.
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    let value: &[u8] = match self {
+      true => b"true",
+      false => b"false",
+    };
+
+    buffer.try_put_slice(value)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = if *self { 4 } else { 5 };
+    (len, Some(len))
+  }
+}
+
 impl HttpWriteable for &'_ [u8] {
   fn write_to<B: BufMut>(
     &self,
@@ -174,6 +328,11 @@ impl HttpWriteable for &'_ [u8] {
 
     buffer.try_put_slice(&data[prev..data.len()])
   }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    folded_size_hint(self.len())
+  }
 }
 
 impl HttpWriteable for &'_ str {
@@ -184,6 +343,11 @@ impl HttpWriteable for &'_ str {
   ) -> Result<(), InsufficientSpaceError> {
     self.as_bytes().write_to(buffer)
   }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    folded_size_hint(self.len())
+  }
 }
 
 impl<W> HttpWriteable for &'_ W
@@ -197,12 +361,274 @@ where
   ) -> Result<(), InsufficientSpaceError> {
     <W as HttpWriteable>::write_to(*self, buffer)
   }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    <W as HttpWriteable>::size_hint(*self)
+  }
+}
+
+/// A writer that applies the same line folding as the `&[u8]` writable
+/// to data that is written to it in pieces.
+///
+/// [`finish`](Folding::finish) must be called once everything has been
+/// written since a CRLF at the very end of the value still needs to be
+/// folded.
+pub(crate) struct Folding<'a, B> {
+  buffer: &'a mut B,
+  inquotes: bool,
+  escaped: bool,
+  prev_cr: bool,
+  // A CRLF was just written and needs to be followed by whitespace.
+  pending: bool,
+}
+
+impl<'a, B: BufMut> Folding<'a, B> {
+  pub(crate) fn new(buffer: &'a mut B) -> Self {
+    Self {
+      buffer,
+      inquotes: false,
+      escaped: false,
+      prev_cr: false,
+      pending: false,
+    }
+  }
+
+  pub(crate) fn write(
+    &mut self,
+    data: &[u8],
+  ) -> Result<(), InsufficientSpaceError> {
+    let mut prev = 0;
+
+    for (idx, &byte) in data.iter().enumerate() {
+      if self.pending {
+        self.pending = false;
+
+        if byte != b' ' && byte != b'\t' {
+          self.buffer.try_put_slice(&data[prev..idx])?;
+          self.buffer.try_put_u8(b'\t')?;
+          prev = idx;
+        }
+      }
+
+      if self.escaped {
+        self.escaped = false;
+        self.prev_cr = false;
+        continue;
+      }
+
+      match byte {
+        b'\\' => self.escaped = true,
+        b'"' => self.inquotes = !self.inquotes,
+        b'\n' if self.prev_cr && !self.inquotes => self.pending = true,
+        _ => (),
+      }
+
+      self.prev_cr = byte == b'\r';
+    }
+
+    self.buffer.try_put_slice(&data[prev..])
+  }
+
+  pub(crate) fn finish(self) -> Result<(), InsufficientSpaceError> {
+    if self.pending {
+      self.buffer.try_put_u8(b'\t')?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<B: BufMut> fmt::Write for Folding<'_, B> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.write(s.as_bytes()).map_err(|_| fmt::Error)
+  }
+}
+
+/// Writer for a header value that is written out piece by piece.
+///
+/// This is passed to the closure given to
+/// [`HttpBuilder::header_with`](crate::HttpBuilder::header_with). Line
+/// breaks are folded as the value is written, exactly as they would be
+/// if the whole value had been written out as a single `&[u8]`, so no
+/// scratch buffer is needed for very large values.
+///
+/// It also implements [`fmt::Write`](core::fmt::Write) so `write!` can be
+/// used to format into the value.
+pub struct ValueWriter<'a, B: BufMut> {
+  inner: Folding<'a, Limit<B>>,
+}
+
+impl<'a, B: BufMut> ValueWriter<'a, B> {
+  pub(crate) fn new(buffer: &'a mut Limit<B>) -> Self {
+    Self {
+      inner: Folding::new(buffer),
+    }
+  }
+
+  pub(crate) fn finish(self) -> Result<(), InsufficientSpaceError> {
+    self.inner.finish()
+  }
+
+  /// Append `data` to the value.
+  pub fn put_slice(
+    &mut self,
+    data: &[u8],
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    self.inner.write(data)?;
+    Ok(self)
+  }
+
+  /// Append `data` to the value.
+  pub fn put_str(
+    &mut self,
+    data: &str,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    self.put_slice(data.as_bytes())
+  }
+
+  /// Append a single byte to the value.
+  pub fn put_u8(
+    &mut self,
+    byte: u8,
+  ) -> Result<&mut Self, InsufficientSpaceError> {
+    self.put_slice(&[byte])
+  }
+}
+
+impl<B: BufMut> fmt::Write for ValueWriter<'_, B> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.inner.write_str(s)
+  }
+}
+
+/// Formats the arguments directly into the buffer without allocating.
+/// Line breaks within the formatted output are folded the same way as for
+/// `&str` values.
+///
+/// An error returned by any of the formatted values is reported as an
+/// [`InsufficientSpaceError`](crate::InsufficientSpaceError).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (id, attempt) = (42, 3);
+///
+/// let mut builder = HttpBuilder::request(
+///     vec![],
+///     Method::GET,
+///     Uri::new(b"/"),
+///     Version::HTTP_1_1
+/// )?;
+/// builder.header(Header::new("X-Req", format_args!("{}-{}", id, attempt)))?;
+/// let output = builder.finish()?;
+///
+/// assert_eq!(
+///   std::str::from_utf8(&output)?,
+///   "GET / HTTP/1.1\r\n\
+///   X-Req: 42-3\r\n\
+///   \r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl HttpWriteable for fmt::Arguments<'_> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    if let Some(s) = self.as_str() {
+      return s.write_to(buffer);
+    }
+
+    let mut folding = Folding::new(buffer);
+    fmt::write(&mut folding, *self)
+      .map_err(|_| InsufficientSpaceError::default())?;
+    folding.finish()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match self.as_str() {
+      Some(s) => folded_size_hint(s.len()),
+      None => (0, None),
+    }
+  }
+}
+
+/// A wrapper that writes out any [`Display`](core::fmt::Display) type as a
+/// header value.
+///
+/// The output is formatted directly into the buffer without allocating
+/// and line breaks within it are folded the same way as for `&str`
+/// values. An error returned by the `Display` implementation is reported
+/// as an [`InsufficientSpaceError`](crate::InsufficientSpaceError).
+///
+/// # Example
+/// ```
+/// # use httpencode::*;
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let addr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 60));
+///
+/// let mut buffer = Vec::new();
+/// Header::new("X-Real-IP", DisplayValue(addr)).write_to(&mut buffer)?;
+///
+/// assert_eq!(buffer, b"X-Real-IP: 192.0.2.60\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct DisplayValue<T>(pub T);
+
+impl<T: fmt::Display> HttpWriteable for DisplayValue<T> {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    format_args!("{}", self.0).write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, None)
+  }
+}
+
+impl HttpWriteable for Bytes {
+  #[inline]
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    (&self[..]).write_to(buffer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    folded_size_hint(self.len())
+  }
+}
+
+impl HttpWriteable for BytesMut {
+  #[inline]
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    (&self[..]).write_to(buffer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    folded_size_hint(self.len())
+  }
 }
 
 #[cfg(feature = "std")]
 mod with_std {
   use super::*;
   use std::borrow::Cow;
+  use std::sync::Arc;
 
   impl HttpWriteable for Vec<u8> {
     #[inline]
@@ -212,6 +638,11 @@ mod with_std {
     ) -> Result<(), InsufficientSpaceError> {
       self.as_slice().write_to(buffer)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      folded_size_hint(self.len())
+    }
   }
 
   impl HttpWriteable for String {
@@ -222,8 +653,34 @@ mod with_std {
     ) -> Result<(), InsufficientSpaceError> {
       self.as_str().write_to(buffer)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      folded_size_hint(self.len())
+    }
   }
 
+  macro_rules! writable_owned {
+    ($($ty:ty),*) => {$(
+      impl HttpWriteable for $ty {
+        #[inline]
+        fn write_to<B: BufMut>(
+          &self,
+          buffer: &mut B,
+        ) -> Result<(), InsufficientSpaceError> {
+          (&**self).write_to(buffer)
+        }
+
+        #[inline]
+        fn size_hint(&self) -> (usize, Option<usize>) {
+          folded_size_hint(self.len())
+        }
+      }
+    )*};
+  }
+
+  writable_owned!(Box<[u8]>, Box<str>, Arc<[u8]>, Arc<str>);
+
   impl<W> HttpWriteable for Cow<'_, W>
   where
     W: HttpWriteable + Clone,
@@ -233,7 +690,176 @@ mod with_std {
       &self,
       buffer: &mut B,
     ) -> Result<(), InsufficientSpaceError> {
-      <W as HttpWriteable>::write_to(&*self, buffer)
+      <W as HttpWriteable>::write_to(&**self, buffer)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      <W as HttpWriteable>::size_hint(&**self)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::encode;
+
+  #[test]
+  fn folding_matches_slice() {
+    let values: &[&[u8]] = &[
+      b"plain",
+      b"a\r\nb",
+      b"a\r\n b",
+      b"a\r\n\tb",
+      b"a\r\n",
+      b"\r\n\r\n",
+      b"\"quoted\r\nvalue\"\r\nx",
+      b"\\\r\nx",
+      b"\\\"\r\nx",
+      b"\r\r\nx",
+    ];
+
+    for &value in values {
+      let mut expected = Vec::new();
+      value.write_to(&mut expected).unwrap();
+
+      // Splitting the value at any point must not change the output.
+      for split in 0..=value.len() {
+        let mut actual = Vec::new();
+        let mut folding = Folding::new(&mut actual);
+        folding.write(&value[..split]).unwrap();
+        folding.write(&value[split..]).unwrap();
+        folding.finish().unwrap();
+
+        assert_eq!(actual, expected, "{:?} split at {}", value, split);
+      }
+    }
+  }
+
+  #[test]
+  fn bools() {
+    for &(value, expected) in &[(true, &b"true"[..]), (false, &b"false"[..])] {
+      let mut buffer = Vec::new();
+      value.write_to(&mut buffer).unwrap();
+
+      assert_eq!(buffer, expected);
+      assert_eq!(value.size_hint(), (buffer.len(), Some(buffer.len())));
+    }
+  }
+
+  #[test]
+  fn floats() {
+    let cases: &[(f64, &str)] = &[
+      (0.0, "0"),
+      (-0.0, "0"),
+      (-0.0001, "0"),
+      (1.0, "1"),
+      (0.5, "0.5"),
+      (0.125, "0.125"),
+      (0.12345, "0.123"),
+      (0.9999, "1"),
+      (-2.25, "-2.25"),
+      (1e6, "1000000"),
+      (f64::NAN, "0"),
+      (f64::INFINITY, "0"),
+      (f64::NEG_INFINITY, "0"),
+    ];
+
+    for &(value, expected) in cases {
+      let mut buffer = Vec::new();
+      value.write_to(&mut buffer).unwrap();
+      assert_eq!(std::str::from_utf8(&buffer).unwrap(), expected);
+
+      let mut buffer = Vec::new();
+      (value as f32).write_to(&mut buffer).unwrap();
+      assert_eq!(std::str::from_utf8(&buffer).unwrap(), expected);
     }
+
+    let mut buffer = Vec::new();
+    1e21.write_to(&mut buffer).unwrap();
+    assert_eq!(buffer, b"1000000000000000000000");
+
+    for &value in &[f64::MAX, f64::MIN, f64::MIN_POSITIVE] {
+      encode(value);
+    }
+
+    for &value in &[f32::MAX, f32::MIN] {
+      encode(value);
+    }
+  }
+
+  #[test]
+  fn shared_buffers() {
+    let bytes = Bytes::from_static(b"a\r\nb");
+    let bytes_mut = BytesMut::from(&b"a\r\nb"[..]);
+
+    let mut buffer = Vec::new();
+    bytes.write_to(&mut buffer).unwrap();
+    bytes_mut.write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"a\r\n\tba\r\n\tb");
+  }
+
+  #[test]
+  fn owned_containers() {
+    use std::sync::Arc;
+
+    let value: Arc<str> = Arc::from("a\r\nb");
+    let header = crate::Header::new("X-Cached", value.clone());
+
+    let mut buffer = Vec::new();
+    header.write_to(&mut buffer).unwrap();
+    Box::<[u8]>::from(&b"c"[..]).write_to(&mut buffer).unwrap();
+    Arc::<[u8]>::from(&b"d"[..]).write_to(&mut buffer).unwrap();
+    Box::<str>::from("e").write_to(&mut buffer).unwrap();
+
+    assert_eq!(buffer, b"X-Cached: a\r\n\tb\r\ncde");
+    assert_eq!(value.size_hint(), folded_size_hint(4));
+  }
+
+  #[test]
+  fn format_args() {
+    let mut buffer = Vec::new();
+    format_args!("{}\r\n{}", 1, 2)
+      .write_to(&mut buffer)
+      .unwrap();
+
+    assert_eq!(buffer, b"1\r\n\t2");
+  }
+
+  #[test]
+  fn display_value() {
+    struct Lines;
+
+    impl fmt::Display for Lines {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a\r")?;
+        f.write_str("\nb\r\n")
+      }
+    }
+
+    let mut buffer = Vec::new();
+    DisplayValue(Lines).write_to(&mut buffer).unwrap();
+    assert_eq!(buffer, b"a\r\n\tb\r\n\t");
+
+    let mut buffer = [0u8; 2];
+    assert!(DisplayValue(12345).write_to(&mut &mut buffer[..]).is_err());
+  }
+
+  #[test]
+  fn header_with_folds_incrementally() {
+    use core::fmt::Write;
+
+    let mut builder = crate::HttpBuilder::from_buffer(Vec::new());
+    builder
+      .header_with("X-Test", |w| {
+        w.put_str("a\r")?;
+        w.put_str("\nb")?;
+        write!(w, "{}\r\n", 1).map_err(|_| InsufficientSpaceError::default())
+      })
+      .unwrap();
+
+    assert_eq!(builder.into_inner(), b"X-Test: a\r\n\tb1\r\n\t\r\n");
   }
 }
@@ -137,3 +137,225 @@ fn large_request() -> Result<(), Box<dyn Error>> {
 
   Ok(())
 }
+
+#[test]
+fn headers_from_iterator() -> Result<(), Box<dyn Error>> {
+  let fields = vec!["Server", "Content-Type"];
+  let values = vec!["httpencode", "text/json"];
+
+  let mut builder = HttpBuilder::response(
+    vec![],
+    Version::HTTP_1_1,
+    Status::with_reason(200, "OK"),
+  )?;
+  builder.headers(
+    fields
+      .into_iter()
+      .zip(values)
+      .map(|(field, value)| Header::new(field, value)),
+  )?;
+  let output = builder.finish()?;
+
+  assert_eq!(
+    std::str::from_utf8(&output)?,
+    "HTTP/1.1 200 OK\r\n\
+    Server: httpencode\r\n\
+    Content-Type: text/json\r\n\
+    \r\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn headers_insufficient_space() {
+  const HEADERS: &[Header<&str>] =
+    &[Header::new("A", "1234"), Header::new("B", "5678")];
+
+  let mut buffer = [0u8; 20];
+  let mut builder = HttpBuilder::from_buffer(&mut buffer[..]);
+  builder.headers(HEADERS.iter().copied()).unwrap();
+  assert!(builder.finish().is_ok());
+  assert_eq!(&buffer[..], b"A: 1234\r\nB: 5678\r\n\r\n");
+
+  let mut buffer = [0u8; 12];
+  let mut builder = HttpBuilder::from_buffer(&mut buffer[..]);
+  assert!(builder.headers(HEADERS.iter().copied()).is_err());
+}
+
+// A writable whose size hint claims it writes nothing at all.
+struct Underestimated(&'static str);
+
+impl HttpWriteable for Underestimated {
+  fn write_to<B: BufMut>(
+    &self,
+    buffer: &mut B,
+  ) -> Result<(), InsufficientSpaceError> {
+    self.0.write_to(buffer)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, Some(0))
+  }
+}
+
+#[test]
+fn headers_wrong_size_hint() {
+  let headers = || {
+    vec![
+      Header::new("A", Underestimated("1234")),
+      Header::new("B", Underestimated("5678")),
+    ]
+  };
+
+  let mut buffer = [0u8; 20];
+  let mut builder = HttpBuilder::from_buffer(&mut buffer[..]);
+  builder.headers(headers()).unwrap();
+  assert!(builder.finish().is_ok());
+  assert_eq!(&buffer[..], b"A: 1234\r\nB: 5678\r\n\r\n");
+
+  let mut buffer = [0u8; 14];
+  let mut builder = HttpBuilder::from_buffer(&mut buffer[..]);
+  assert!(builder.headers(headers()).is_err());
+}
+
+#[test]
+fn body_insufficient_space() {
+  let mut buffer = [0u8; 40];
+  let builder =
+    HttpBuilder::response(&mut buffer[..], Version::HTTP_1_1, Status::OK)
+      .unwrap();
+
+  assert!(builder
+    .body(b"This body does not fit in the buffer")
+    .is_err());
+}
+
+#[test]
+fn finish_empty_request() -> Result<(), Box<dyn Error>> {
+  let builder = HttpBuilder::request(
+    vec![],
+    Method::POST,
+    Uri::new(b"/"),
+    Version::HTTP_1_1,
+  )?;
+  let output = builder.finish_empty()?;
+
+  assert_eq!(
+    std::str::from_utf8(&output)?,
+    "POST / HTTP/1.1\r\nContent-Length: 0\r\n\r\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn finish_empty_not_modified() -> Result<(), Box<dyn Error>> {
+  let builder = HttpBuilder::response(
+    vec![],
+    Version::HTTP_1_1,
+    Status::with_reason(304, "Not Modified"),
+  )?;
+  let output = builder.finish_empty()?;
+
+  assert_eq!(
+    std::str::from_utf8(&output)?,
+    "HTTP/1.1 304 Not Modified\r\n\r\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn content_length_placeholder_slice() -> Result<(), Box<dyn Error>> {
+  let mut buffer = [0u8; 128];
+  let mut remaining = &mut buffer[..];
+
+  let mut builder = HttpBuilder::request(
+    &mut remaining,
+    Method::POST,
+    Uri::new(b"/upload"),
+    Version::HTTP_1_1,
+  )?;
+  let placeholder = builder.content_length_placeholder()?;
+  let output = builder.finish()?;
+  output.put_slice(b"body");
+
+  let len = 128 - remaining.len();
+  placeholder.patch(&mut buffer[..len], 4);
+
+  assert_eq!(
+    std::str::from_utf8(&buffer[..len])?,
+    "POST /upload HTTP/1.1\r\n\
+    Content-Length: 4                   \r\n\
+    \r\n\
+    body"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn max_head_size_includes_terminator() -> Result<(), Box<dyn Error>> {
+  // "GET / HTTP/1.1\r\n\r\n" is exactly 18 bytes.
+  let mut builder = HttpBuilder::request(
+    vec![],
+    Method::GET,
+    Uri::new(b"/"),
+    Version::HTTP_1_1,
+  )?;
+  builder.max_head_size(18);
+  assert!(builder.finish().is_ok());
+
+  let mut builder = HttpBuilder::request(
+    vec![],
+    Method::GET,
+    Uri::new(b"/"),
+    Version::HTTP_1_1,
+  )?;
+  builder.max_head_size(17);
+  assert!(builder.finish().is_err());
+
+  Ok(())
+}
+
+#[test]
+fn size_hint_bounds_output() {
+  fn check<W: HttpWriteable>(val: W) {
+    let mut buf = vec![];
+    val.write_to(&mut buf).unwrap();
+
+    let (lower, upper) = val.size_hint();
+    assert!(lower <= buf.len());
+    if let Some(upper) = upper {
+      assert!(buf.len() <= upper);
+    }
+  }
+
+  check(0u8);
+  check(u128::MAX);
+  check(i64::MIN);
+  check("a\r\nb\r\nc\r\n");
+  check(&b"\r\n\r\n\r\n"[..]);
+  check(CheckedValue::new(b"checked"));
+}
+
+#[test]
+fn asterisk_form_validation() {
+  let builder = ValidatingBuilder::request(
+    vec![],
+    Method::GET,
+    Uri::ASTERISK,
+    Version::HTTP_1_1,
+  );
+  assert!(builder.is_err());
+
+  let builder = ValidatingBuilder::request(
+    vec![],
+    Method::OPTIONS,
+    Uri::ASTERISK,
+    Version::HTTP_1_1,
+  )
+  .unwrap();
+  assert_eq!(builder.finish().unwrap(), b"OPTIONS * HTTP/1.1\r\n\r\n");
+}